@@ -0,0 +1,300 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+
+use crate::cmd::Packages;
+use crate::metadata;
+use crate::session::Session;
+use crate::tools::BinCtx;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::ui;
+use crate::ui::style::{ERROR, GOOD, HEADER, TABLE_HEADER};
+
+/// The directory of the stored baselines, inside the prep directory.
+const BASELINES_DIR: &str = "bench";
+
+/// Runs the benchmarks of the selected `packages` with `cargo bench`.
+///
+/// In `strict` mode Cargo version is locked.
+///
+/// In `smoke` mode the benchmarks are built and every benchmark is run only once,
+/// without optimizations, to catch broken benchmarks without the full benchmark runtime.
+/// This works because Criterion, like libtest, runs every benchmark once when invoked as a test.
+///
+/// The Criterion results can be stored as the `save_baseline` in `.prep/bench/`,
+/// or compared against the stored `baseline`, failing on slowdowns above
+/// `max_regression` of `[bench]` in `prep.toml`.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    smoke: bool,
+    save_baseline: Option<&str>,
+    baseline: Option<&str>,
+    packages: &Packages,
+) -> Result<()> {
+    let baselines = save_baseline.is_some() || baseline.is_some();
+    ensure!(
+        !(smoke && baselines),
+        "baselines can't be used with `--smoke`, as it doesn't measure anything"
+    );
+    for name in save_baseline.iter().chain(baseline.iter()) {
+        ensure!(
+            !name.is_empty() && !name.contains(['/', '\\', '.']),
+            "invalid baseline name '{name}'"
+        );
+    }
+    let baseline_path = |name: &str| {
+        session
+            .prep_dir()
+            .join(BASELINES_DIR)
+            .join(format!("{name}.json"))
+    };
+    let saved = match baseline {
+        Some(name) => {
+            let path = baseline_path(name);
+            ensure!(
+                path.exists(),
+                "baseline '{name}' doesn't exist, store it with `prep bench --save-baseline {name}`"
+            );
+            let json = fs::read_to_string(&path)
+                .context(format!("failed to read '{}'", path.display()))?;
+            let saved: BTreeMap<String, f64> = serde_json::from_str(&json)
+                .context(format!("failed to parse '{}'", path.display()))?;
+            Some((name, saved))
+        }
+        None => None,
+    };
+    let save_path = save_baseline.map(baseline_path);
+
+    let metadata = metadata::fetch(session)?;
+    let criterion_dir = metadata
+        .target_directory
+        .join("criterion")
+        .into_std_path_buf();
+    let has_bench = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|p| packages.contains(&p.name))
+        .any(|p| p.targets.iter().any(|t| t.is_bench()));
+    if !has_bench {
+        let h = HEADER;
+        eprintln!("     {h}Skipped{h:#} benchmarks as no packages have bench targets");
+        return Ok(());
+    }
+
+    let cargo = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&[]);
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(rustup_ver_req, rust_components);
+        toolset.get::<Cargo>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(None, vec![]);
+        toolset.get::<Cargo>(&deps, None)?
+    };
+
+    let mut cmd = cmd(&cargo, smoke, packages)?;
+
+    ui::print_cmd(&cmd);
+
+    let start = SystemTime::now();
+    let status = ui::capture::status(&mut cmd).context("failed to run cargo bench")?;
+    ensure!(status.success(), "benchmarks failed: {status}");
+
+    if !baselines {
+        return Ok(());
+    }
+    let mut results = BTreeMap::new();
+    if criterion_dir.exists() {
+        collect_estimates(&criterion_dir, &criterion_dir, start, &mut results)?;
+    }
+    ensure!(
+        !results.is_empty(),
+        "no Criterion results were found, baselines are only supported for Criterion benchmarks"
+    );
+
+    if let Some(path) = save_path {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context(format!("failed to create '{}'", dir.display()))?;
+        }
+        let json =
+            serde_json::to_string_pretty(&results).context("failed to generate baseline JSON")?;
+        fs::write(&path, format!("{json}\n"))
+            .context(format!("failed to write '{}'", path.display()))?;
+        let h = HEADER;
+        eprintln!(
+            "       {h}Saved{h:#} baseline of {} benchmarks '{}'",
+            results.len(),
+            path.display()
+        );
+    }
+
+    if let Some((name, saved)) = saved {
+        compare(
+            name,
+            &saved,
+            &results,
+            session.config().bench().max_regression(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the command that runs the benchmarks of the selected `packages`.
+///
+/// In `smoke` mode the benchmarks are run only once as tests, without measuring them.
+pub fn cmd(cargo: &BinCtx, smoke: bool, packages: &Packages) -> Result<Command> {
+    let mut cmd = cargo.cmd();
+    if smoke {
+        // Only the bench targets, as `--benches` also includes the library unit tests.
+        cmd.arg("test").args(["--bench", "*"]);
+    } else {
+        cmd.arg("bench");
+    }
+    cmd.args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .args(packages.as_args()?)
+        .arg("--all-features");
+    Ok(cmd)
+}
+
+/// Compares the current `results` with the `saved` baseline `name`.
+///
+/// Fails if any benchmark is more than `max_regression` percent slower.
+fn compare(
+    name: &str,
+    saved: &BTreeMap<String, f64>,
+    results: &BTreeMap<String, f64>,
+    max_regression: f64,
+) -> Result<()> {
+    let rows = results
+        .iter()
+        .filter_map(|(id, current)| {
+            let saved = *saved.get(id)?;
+            let change = (current - saved) / saved * 100.0;
+            Some((id.as_str(), saved, *current, change))
+        })
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        bail!("none of the benchmarks are in baseline '{name}'");
+    }
+
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        // The durations contain `µ`, which is two bytes.
+        s.push_str(&" ".repeat(len.saturating_sub(s.chars().count())));
+        s
+    }
+
+    let nlen = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(9);
+    let (t, g, e) = (TABLE_HEADER, GOOD, ERROR);
+    eprintln!(
+        "{t}{}{t:#}  {t}Baseline{t:#}    {t}Current{t:#}     {t}Change{t:#}",
+        cell("Benchmark", nlen)
+    );
+    let mut regressed = Vec::new();
+    for (id, saved, current, change) in rows {
+        let change_text = format!("{change:+.2}%");
+        let change_text = if change > max_regression {
+            regressed.push(id);
+            format!("{e}{change_text}{e:#}")
+        } else {
+            format!("{g}{change_text}{g:#}")
+        };
+        eprintln!(
+            "{}  {}  {}  {change_text}",
+            cell(id, nlen),
+            cell(&format_time(saved), 10),
+            cell(&format_time(current), 10)
+        );
+    }
+
+    if !regressed.is_empty() {
+        bail!(
+            "benchmarks regressed more than {max_regression}% compared to baseline '{name}': {}",
+            regressed.join(", ")
+        );
+    }
+
+    let h = HEADER;
+    eprintln!(
+        "    {h}Verified{h:#} no benchmarks regressed more than {max_regression}% \
+        compared to baseline '{name}'"
+    );
+    Ok(())
+}
+
+/// Recursively adds the mean time in nanoseconds of the Criterion results in `dir`
+/// which were modified since `start` to the `results`, keyed by the benchmark ID.
+///
+/// Criterion stores the latest results of every benchmark in `<id>/new/estimates.json`.
+fn collect_estimates(
+    root: &Path,
+    dir: &Path,
+    start: SystemTime,
+    results: &mut BTreeMap<String, f64>,
+) -> Result<()> {
+    let estimates_path = dir.join("new").join("estimates.json");
+    if estimates_path.is_file() {
+        let modified = fs::metadata(&estimates_path)
+            .and_then(|m| m.modified())
+            .context(format!("failed to read '{}'", estimates_path.display()))?;
+        if modified >= start {
+            let json = fs::read_to_string(&estimates_path)
+                .context(format!("failed to read '{}'", estimates_path.display()))?;
+            let estimates: serde_json::Value = serde_json::from_str(&json)
+                .context(format!("failed to parse '{}'", estimates_path.display()))?;
+            if let Some(mean) = estimates["mean"]["point_estimate"].as_f64() {
+                let id = dir
+                    .strip_prefix(root)
+                    .unwrap_or(dir)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                results.insert(id, mean);
+            }
+        }
+    }
+
+    let read_dir = dir
+        .read_dir()
+        .context(format!("failed to read directory '{}'", dir.display()))?;
+    for entry in read_dir {
+        let entry = entry.context(format!("failed to read directory '{}'", dir.display()))?;
+        let name = entry.file_name();
+        // Criterion's own baselines and the HTML report don't contain new results.
+        if name == "new" || name == "base" || name == "report" {
+            continue;
+        }
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_estimates(root, &entry.path(), start, results)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `nanos` duration formatted with an appropriate unit.
+fn format_time(nanos: f64) -> String {
+    if nanos >= 1e9 {
+        format!("{:.2} s", nanos / 1e9)
+    } else if nanos >= 1e6 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else if nanos >= 1e3 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else {
+        format!("{nanos:.2} ns")
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::cmd::tools::list::format_size;
+use crate::metadata;
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_bloat::{CargoBloat, CargoBloatDeps};
+use crate::ui;
+use crate::ui::style::{HEADER, LITERAL, TABLE_HEADER};
+
+/// The file where the binary size baseline is stored, inside the prep directory.
+const BASELINE_FILE: &str = "bloat-baseline.json";
+
+/// One kibibyte, the unit of the configured budgets.
+const KIB: u64 = 1024;
+
+/// The parts of the cargo-bloat JSON report which are used.
+#[derive(Deserialize)]
+struct BloatReport {
+    #[serde(rename = "file-size")]
+    file_size: u64,
+    #[serde(rename = "text-section-size")]
+    text_size: u64,
+    #[serde(default)]
+    functions: Vec<BloatItem>,
+    #[serde(default)]
+    crates: Vec<BloatItem>,
+}
+
+/// A function or a crate in the cargo-bloat report.
+#[derive(Deserialize)]
+struct BloatItem {
+    /// The crate of a function, if known.
+    #[serde(rename = "crate")]
+    krate: Option<String>,
+    name: String,
+    size: u64,
+}
+
+/// Recorded sizes of the release binaries, keyed by the binary name.
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    binaries: BTreeMap<String, Sizes>,
+}
+
+/// Sizes of a release binary.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct Sizes {
+    file_size: u64,
+    text_size: u64,
+}
+
+/// Report what takes the most space in the release binaries of the workspace with cargo-bloat.
+///
+/// In `strict` mode Cargo and cargo-bloat versions are locked.
+///
+/// Only the binaries named in `bins` are analyzed, or all of them if it's empty.
+/// The `count` largest functions are listed, or the largest crates with `crates`.
+///
+/// This fails when a binary exceeds its budget or grows over the baseline more than allowed
+/// in `[bloat]` of `prep.toml`. Set `update_baseline` to record the current sizes instead.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    bins: &[String],
+    count: usize,
+    crates: bool,
+    update_baseline: bool,
+) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let (cargo, bloat) = if strict {
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let cargo_ver_req = tools_cfg.rust().clone();
+        let ver_req = tools_cfg.cargo_bloat().clone();
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+        let cargo = toolset.get::<Cargo>(&cargo_deps, &cargo_ver_req)?;
+        let deps = CargoBloatDeps::new(cargo_deps, cargo_ver_req);
+        (cargo, toolset.get::<CargoBloat>(&deps, &ver_req)?)
+    } else {
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(None, vec![]);
+        let cargo = toolset.get::<Cargo>(&cargo_deps, None)?;
+        let deps = CargoBloatDeps::new(cargo_deps, None);
+        (cargo, toolset.get::<CargoBloat>(&deps, None)?)
+    };
+
+    let metadata = metadata::fetch(session)?;
+    let targets = metadata
+        .workspace_packages()
+        .into_iter()
+        .flat_map(|p| {
+            p.targets
+                .iter()
+                .filter(|t| t.is_bin())
+                .map(|t| (p.name.to_string(), t.name.clone()))
+        })
+        .filter(|(_, bin)| bins.is_empty() || bins.contains(bin))
+        .collect::<Vec<_>>();
+    if let Some(missing) = bins.iter().find(|b| !targets.iter().any(|(_, t)| t == *b)) {
+        bail!("no binary named '{missing}' in the workspace");
+    }
+    let h = HEADER;
+    if targets.is_empty() {
+        eprintln!("     {h}Skipped{h:#} the workspace has no binaries");
+        return Ok(());
+    }
+
+    let baseline_path = session.prep_dir().join(BASELINE_FILE);
+    let mut baseline = load_baseline(&baseline_path)?.unwrap_or(Baseline {
+        binaries: BTreeMap::new(),
+    });
+    let mut sizes = BTreeMap::new();
+    for (package, bin) in &targets {
+        // The binary is run directly instead of as a Cargo subcommand,
+        // so the first argument must be `bloat`.
+        let mut cmd = bloat.clone().env(&cargo::toolchain_vars(&cargo)).cmd();
+        cmd.args(["bloat", "--release", "--message-format", "json"])
+            .args(["--package", package, "--bin", bin])
+            .args(["-n", &count.to_string()]);
+        if crates {
+            cmd.arg("--crates");
+        }
+
+        ui::print_cmd(&cmd);
+
+        let output = cmd
+            .output()
+            .context(format!("failed to run {}", CargoBloat::NAME))?;
+        ensure!(
+            output.status.success(),
+            "{} failed: {}\n{}",
+            CargoBloat::NAME,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: BloatReport = serde_json::from_slice(&output.stdout)
+            .context(format!("failed to parse {} JSON", CargoBloat::NAME))?;
+
+        let items = if crates {
+            &report.crates
+        } else {
+            &report.functions
+        };
+        let current = Sizes {
+            file_size: report.file_size,
+            text_size: report.text_size,
+        };
+        print_report(bin, items, crates, current, baseline.binaries.get(bin));
+        sizes.insert(bin.clone(), current);
+    }
+
+    if update_baseline {
+        baseline.binaries.extend(sizes);
+        session.ensure_prep_dir()?;
+        save_baseline(&baseline_path, &baseline)?;
+        eprintln!(
+            "     {h}Updated{h:#} binary size baseline '{}'",
+            baseline_path.display()
+        );
+        return Ok(());
+    }
+
+    let bloat_cfg = session.config().bloat();
+    check_budgets(
+        &sizes,
+        bloat_cfg.budgets(),
+        &baseline,
+        bloat_cfg.max_growth(),
+    )?;
+    if !bloat_cfg.budgets().is_empty() || bloat_cfg.max_growth().is_some() {
+        eprintln!("    {h}Verified{h:#} binary sizes are within the budgets");
+    }
+    Ok(())
+}
+
+/// Prints the largest `items` of the `bin` binary and its `sizes` compared to the `baseline`.
+fn print_report(
+    bin: &str,
+    items: &[BloatItem],
+    crates: bool,
+    sizes: Sizes,
+    baseline: Option<&Sizes>,
+) {
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    let clen = items
+        .iter()
+        .map(|i| i.krate.as_deref().unwrap_or("?").len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let t = TABLE_HEADER;
+    eprintln!();
+    if crates {
+        eprintln!("{t}Size{t:#}        {t}File{t:#}    {t}Crate{t:#}");
+    } else {
+        eprintln!(
+            "{t}Size{t:#}        {t}File{t:#}    {t}{}{t:#}  {t}Function{t:#}",
+            cell("Crate", clen)
+        );
+    }
+    for item in items {
+        let share = format!(
+            "{:.1}%",
+            item.size as f64 * 100.0 / sizes.file_size.max(1) as f64
+        );
+        let size = cell(&format_size(item.size), 10);
+        if crates {
+            eprintln!("{size}  {}  {}", cell(&share, 6), item.name);
+        } else {
+            let krate = item.krate.as_deref().unwrap_or("?");
+            eprintln!(
+                "{size}  {}  {}  {}",
+                cell(&share, 6),
+                cell(krate, clen),
+                item.name
+            );
+        }
+    }
+
+    let h = HEADER;
+    let growth = baseline
+        .map(|b| {
+            format!(
+                ", {} from the baseline",
+                format_delta(b.file_size, sizes.file_size)
+            )
+        })
+        .unwrap_or_default();
+    eprintln!(
+        "    {h}Analyzed{h:#} '{bin}' is {} with {} of code{growth}",
+        format_size(sizes.file_size),
+        format_size(sizes.text_size)
+    );
+}
+
+/// Returns the difference from `old` to `new` bytes, formatted with a sign and a binary unit.
+fn format_delta(old: u64, new: u64) -> String {
+    if new >= old {
+        format!("+{}", format_size(new - old))
+    } else {
+        format!("-{}", format_size(old - new))
+    }
+}
+
+/// Verifies that the binary `sizes` are within their `budgets` in KiB,
+/// and don't grow more than `max_growth` percent over the `baseline`.
+fn check_budgets(
+    sizes: &BTreeMap<String, Sizes>,
+    budgets: &BTreeMap<String, u64>,
+    baseline: &Baseline,
+    max_growth: Option<f64>,
+) -> Result<()> {
+    let l = LITERAL;
+    let mut msg = String::new();
+    for (bin, size) in sizes {
+        if let Some(budget) = budgets.get(bin)
+            && size.file_size > budget.saturating_mul(KIB)
+        {
+            msg.push_str(&format!(
+                "\n{l}{bin}{l:#}: {} exceeds the budget of {}",
+                format_size(size.file_size),
+                format_size(budget.saturating_mul(KIB))
+            ));
+        }
+        if let Some(max_growth) = max_growth
+            && let Some(base) = baseline.binaries.get(bin)
+        {
+            let growth = (size.file_size as f64 - base.file_size as f64) * 100.0
+                / base.file_size.max(1) as f64;
+            if growth > max_growth {
+                msg.push_str(&format!(
+                    "\n{l}{bin}{l:#}: grew {growth:.1}% over the baseline, more than {max_growth}%"
+                ));
+            }
+        }
+    }
+    if msg.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "release binaries exceed the size budgets:{msg}\n\
+        Reduce the size, or run `prep bloat --update-baseline` to accept the growth"
+    )
+}
+
+/// Loads the binary size baseline from `path`, if it exists.
+fn load_baseline(path: &Path) -> Result<Option<Baseline>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read(path).context(format!(
+        "failed to read binary size baseline file '{}'",
+        path.display()
+    ))?;
+    let baseline =
+        serde_json::from_slice(&json).context("failed to parse binary size baseline JSON")?;
+    Ok(Some(baseline))
+}
+
+/// Saves the binary size `baseline` to `path`.
+fn save_baseline(path: &Path, baseline: &Baseline) -> Result<()> {
+    let json = serde_json::to_string_pretty(baseline)
+        .context("failed to generate binary size baseline JSON")?;
+    fs::write(path, json).context(format!(
+        "failed to write binary size baseline file '{}'",
+        path.display()
+    ))?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo::CargoDeps;
+use crate::tools::git_cliff::{GitCliff, GitCliffDeps};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// The changelog file name, in the workspace root.
+const CHANGELOG_NAME: &str = "CHANGELOG.md";
+
+/// Generates `CHANGELOG.md` from the conventional commits of the Git history with git-cliff.
+///
+/// The whole changelog is regenerated, with the commits since the last tag as unreleased.
+/// git-cliff is configured with `cliff.toml`, or its defaults if there is none.
+///
+/// In `strict` mode git-cliff version is locked.
+///
+/// Set `check` to verify that the changelog is already up to date, without changing it.
+pub fn run(session: &mut Session, strict: bool, check: bool) -> Result<()> {
+    let git_cliff = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let cargo_ver_req = tools_cfg.rust().clone();
+        let ver_req = tools_cfg.git_cliff().clone();
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+        let deps = GitCliffDeps::new(cargo_deps, cargo_ver_req);
+        toolset.get::<GitCliff>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(None, vec![]);
+        let deps = GitCliffDeps::new(cargo_deps, None);
+        toolset.get::<GitCliff>(&deps, None)?
+    };
+    let path = session.root_dir().join(CHANGELOG_NAME);
+
+    let mut cmd = git_cliff.cmd();
+    if !check {
+        cmd.arg("--output").arg(&path);
+
+        ui::print_cmd(&cmd);
+
+        let status =
+            ui::capture::status(&mut cmd).context(format!("failed to run {}", GitCliff::NAME))?;
+        ensure!(status.success(), "{} failed: {status}", GitCliff::NAME);
+
+        let h = HEADER;
+        eprintln!("   {h}Generated{h:#} changelog '{}'", path.display());
+        return Ok(());
+    }
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd
+        .output()
+        .context(format!("failed to run {}", GitCliff::NAME))?;
+    ensure!(
+        output.status.success(),
+        "{} failed: {}\n{}",
+        GitCliff::NAME,
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let generated = String::from_utf8(output.stdout)
+        .context(format!("{} output not valid UTF-8", GitCliff::NAME))?;
+    let current = if path.exists() {
+        fs::read_to_string(&path).context(format!("failed to read '{}'", path.display()))?
+    } else {
+        String::new()
+    };
+    // Line endings and trailing newlines can differ by platform and editor.
+    if generated.trim_end().lines().ne(current.trim_end().lines()) {
+        bail!(
+            "changelog '{}' is not up to date, regenerate it with `prep changelog`",
+            path.display()
+        );
+    }
+
+    let h = HEADER;
+    eprintln!(
+        "    {h}Verified{h:#} changelog '{}' is up to date",
+        path.display()
+    );
+    Ok(())
+}
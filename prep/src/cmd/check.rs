@@ -0,0 +1,84 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::env;
+use std::process::Command;
+
+use anyhow::{Context, Result, ensure};
+
+use crate::cmd::{CargoTargets, Packages};
+use crate::session::Session;
+use crate::tools::BinCtx;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::ui;
+
+/// Runs `cargo check` on the given `targets` of the selected `packages`.
+///
+/// In `strict` mode warnings are treated as errors and Cargo version is locked.
+///
+/// Set `nightly` to use the nightly Rust toolchain configured in `prep.toml`.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    nightly: bool,
+    packages: &Packages,
+    targets: CargoTargets,
+) -> Result<()> {
+    let cargo = if nightly {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = strict.then(|| tools_cfg.rustup().clone());
+        let channel = tools_cfg.rust_nightly().to_string();
+        let toolset = session.toolset();
+        let deps = CargoDeps::channel(rustup_ver_req, channel, vec![]);
+        toolset.get::<Cargo>(&deps, None)?
+    } else if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&[]);
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(rustup_ver_req, rust_components);
+        toolset.get::<Cargo>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(None, vec![]);
+        toolset.get::<Cargo>(&deps, None)?
+    };
+
+    let mut cmd = cmd(&cargo, strict, packages, targets)?;
+    ui::print_cmd(&cmd);
+
+    let status = ui::capture::status(&mut cmd).context("failed to run cargo check")?;
+    ensure!(status.success(), "cargo check failed: {status}");
+
+    Ok(())
+}
+
+/// Returns the `cargo check` command for the given `targets` of the selected `packages`.
+///
+/// In `strict` mode warnings are treated as errors.
+pub fn cmd(
+    cargo: &BinCtx,
+    strict: bool,
+    packages: &Packages,
+    targets: CargoTargets,
+) -> Result<Command> {
+    let mut cmd = cargo.cmd();
+    cmd.arg("check")
+        .args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .args(packages.as_args()?)
+        .args(targets.as_args())
+        .arg("--all-features");
+    if strict {
+        // Unlike clippy, cargo check has no way to pass lint flags only to the workspace crates.
+        // Cargo caps the lints of dependencies anyway, so RUSTFLAGS is fine here.
+        let mut rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+        if !rustflags.is_empty() {
+            rustflags.push(' ');
+        }
+        rustflags.push_str("-D warnings");
+        cmd.env("RUSTFLAGS", rustflags);
+    }
+    Ok(cmd)
+}
@@ -1,43 +1,411 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
 
-use crate::cmd::{CargoTargets, clippy, copyright, format};
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+use crate::cmd::{
+    CargoTargets, OutputFormat, Packages, bench, check, clippy, copyright, duplicates, format,
+    hakari, links, lockfile, manifest_lint, no_std, readme, run_shell, secrets, test,
+    unsafe_report,
+};
+use crate::config::{Check, Config};
+use crate::hash::Sha256;
 use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::git::{self, Git};
+use crate::tools::lychee::Lychee;
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::taplo::Taplo;
+use crate::tools::{BinCtx, Tool};
+use crate::ui::style::{ERROR, GOOD, HEADER, TABLE_HEADER};
+use crate::{files, github, host, metadata, toolchain_file, ui};
+
+/// The function that runs a CI step.
+pub type StepFn = Box<dyn Fn(&mut Session) -> Result<()>>;
+
+/// The maximum number of output lines of a failed step in the GitHub Actions job summary.
+const SUMMARY_OUTPUT_LINES: usize = 50;
+
+/// The file where the CI state is persisted, inside the prep directory.
+pub const STATE_FILE: &str = "ci-state.json";
+
+/// A built-in CI step.
+struct Builtin {
+    /// The name of the step, which configured steps and `--only` refer to.
+    name: &'static str,
+    /// Runs the step.
+    run: fn(&mut Session) -> Result<()>,
+    /// Adds what the step would do to the plan, without running anything.
+    ///
+    /// The commands are built by the same functions that the step runs them with.
+    plan: fn(&mut Session, &mut Plan) -> Result<()>,
+}
+
+/// The built-in CI steps.
+static BUILTINS: [Builtin; 19] = [
+    Builtin {
+        name: "check",
+        run: |s| check::run(s, true, false, &Packages::default(), CargoTargets::All),
+        plan: |s, plan| {
+            let cargo = plan.cargo(s);
+            let cmd = check::cmd(&cargo, true, &Packages::default(), CargoTargets::All)?;
+            plan.commands.push(cmd);
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "copyright",
+        run: |s| copyright::run(s, true, false),
+        plan: copyright_plan,
+    },
+    Builtin {
+        name: "format",
+        run: |s| format::run(s, true, true, true, &Packages::default(), None),
+        plan: format_plan,
+    },
+    Builtin {
+        name: "clippy",
+        run: |s| clippy_step(s, CargoTargets::All),
+        plan: |s, plan| clippy_plan(s, plan, CargoTargets::All),
+    },
+    Builtin {
+        name: "clippy-main",
+        run: |s| clippy_step(s, CargoTargets::Main),
+        plan: |s, plan| clippy_plan(s, plan, CargoTargets::Main),
+    },
+    Builtin {
+        name: "clippy-aux",
+        run: |s| clippy_step(s, CargoTargets::Auxiliary),
+        plan: |s, plan| clippy_plan(s, plan, CargoTargets::Auxiliary),
+    },
+    Builtin {
+        name: "test",
+        run: |s| test::run(s, true, false, None, &Packages::default()),
+        plan: test_plan,
+    },
+    Builtin {
+        name: "doc-test",
+        run: |s| test::run(s, true, true, None, &Packages::default()),
+        plan: |s, plan| {
+            let cargo = plan.cargo(s);
+            plan.commands
+                .push(test::cargo_cmd(&cargo, true, &Packages::default())?);
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "bench-smoke",
+        run: |s| bench::run(s, true, true, None, None, &Packages::default()),
+        plan: |s, plan| {
+            let cargo = plan.cargo(s);
+            plan.commands
+                .push(bench::cmd(&cargo, true, &Packages::default())?);
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "lockfile",
+        run: lockfile::run,
+        plan: |s, plan| {
+            let cargo = plan.cargo(s);
+            plan.commands.push(lockfile::cmd(&cargo));
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "manifest-lint",
+        run: |s| manifest_lint::run(s, false),
+        plan: |s, plan| {
+            let ver_req = s.config().tools().cargo_sort().clone();
+            let cargo_sort = plan.tool::<CargoSort>(s, &ver_req);
+            let cmd = manifest_lint::cmd(&cargo_sort, s.root_dir(), false);
+            plan.commands.push(cmd);
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "no-std",
+        run: no_std::run,
+        plan: no_std_plan,
+    },
+    Builtin {
+        name: "duplicates",
+        run: duplicates::run,
+        plan: |s, plan| {
+            let cmd = metadata::graph_cmd(s).cargo_command();
+            plan.commands.push(cmd);
+            plan.internal = Some("checks the dependency graph for multiple versions of a package");
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "readme",
+        run: |s| readme::run(s, false),
+        plan: |s, plan| {
+            plan.commands.push(metadata::cmd(s).cargo_command());
+            plan.internal = Some("compares the READMEs with the crate documentation");
+            Ok(())
+        },
+    },
+    Builtin {
+        name: "links",
+        run: |s| links::run(s, true, false),
+        plan: |s, plan| links_plan(s, plan, false),
+    },
+    Builtin {
+        name: "links-offline",
+        run: |s| links::run(s, true, true),
+        plan: |s, plan| links_plan(s, plan, true),
+    },
+    Builtin {
+        name: "unsafe-report",
+        run: |s| unsafe_report::run(s, true, false),
+        plan: unsafe_report_plan,
+    },
+    Builtin {
+        name: "hakari",
+        run: hakari::verify,
+        plan: hakari_plan,
+    },
+    Builtin {
+        name: "secrets",
+        run: secrets::run,
+        plan: |_, plan| {
+            plan.internal = Some("scans the files for committed credentials");
+            Ok(())
+        },
+    },
+];
+
+/// A single CI step.
+type Step = (String, StepFn);
+
+/// How a CI step is run.
+enum StepKind {
+    /// A built-in step, by name.
+    Builtin(String),
+    /// Cargo with the given arguments.
+    Cargo(Vec<String>),
+    /// A shell command.
+    Command(String),
+}
+
+/// What a CI step would do, as shown by a dry run.
+#[derive(Default)]
+struct Plan {
+    /// Descriptions of the tool versions that the step would resolve.
+    tools: Vec<String>,
+    /// Description of the work done by Prep itself, if that's most of the step.
+    internal: Option<&'static str>,
+    /// The commands that the step would run.
+    commands: Vec<Command>,
+}
+
+/// The outcome of a single CI step.
+struct StepResult {
+    name: String,
+    skipped: bool,
+    duration: Duration,
+    error: Option<anyhow::Error>,
+    output: String,
+}
+
+/// Persisted CI state for resuming runs.
+#[derive(Serialize, Deserialize)]
+struct State {
+    /// Git tree hash of the working tree that the steps ran on.
+    tree: String,
+    /// Names of the steps that passed.
+    passed: Vec<String>,
+    /// Names of the steps that failed.
+    #[serde(default)]
+    failed: Vec<String>,
+}
+
+/// The outcome of the last CI run, from the persisted state.
+pub struct LastRun {
+    /// Names of the steps that passed.
+    pub passed: Vec<String>,
+    /// Names of the steps that failed.
+    pub failed: Vec<String>,
+    /// Whether the run was on the current working tree.
+    pub current: bool,
+}
+
+/// Machine readable CI run report.
+#[derive(Serialize)]
+struct Report {
+    success: bool,
+    duration_secs: f64,
+    steps: Vec<StepReport>,
+}
+
+/// Machine readable CI step report.
+#[derive(Serialize)]
+struct StepReport {
+    name: String,
+    success: bool,
+    skipped: bool,
+    duration_secs: f64,
+    error: Option<String>,
+    output: String,
+}
+
+/// Options of a CI run.
+#[derive(Clone, Copy)]
+pub struct Options<'a> {
+    /// Stop at the first failed step, instead of running the rest of them regardless.
+    pub fail_fast: bool,
+    /// The format of the run report.
+    pub format: OutputFormat,
+    /// The path to write the JSON run report to, instead of stdout.
+    pub output: Option<&'a Path>,
+    /// The path to also write a JUnit XML report to.
+    pub junit: Option<&'a Path>,
+    /// Skip the steps that already passed for the same Git tree in the previous run.
+    pub resume: bool,
+}
 
 /// Runs CI verification.
 ///
 /// Can be ran in `extended` mode for more thorough checks.
 ///
-/// Set `fail_fast` to `false` to run the checks to the end regardless of failure.
-pub fn run(session: &mut Session, extended: bool, fail_fast: bool) -> Result<()> {
-    let mut errs: Vec<anyhow::Error> = Vec::new();
-    let mut step = |f: &mut dyn FnMut() -> Result<()>| -> Result<()> {
-        if let Err(e) = f() {
-            if fail_fast {
-                return Err(e);
-            }
-            errs.push(e);
+/// The steps configured in `prep.toml` are run instead of the default pipeline, if there are any.
+/// The custom checks are appended to the default pipeline, and can be named by configured steps.
+///
+/// Only the steps named in `only` are run, unless it's empty.
+///
+/// The passed steps are recorded for the current Git tree, for the `resume` option.
+///
+/// A summary of the step durations is printed at the end,
+/// and also written to the GitHub step summary when running in GitHub Actions.
+pub fn run(
+    session: &mut Session,
+    extended: bool,
+    only: &[String],
+    options: &Options,
+) -> Result<()> {
+    let Options {
+        fail_fast,
+        format,
+        output,
+        junit,
+        resume,
+    } = *options;
+    let mut steps = select_steps(steps(session, extended)?, only)?;
+
+    // The state can only be persisted in Git repositories, which is only required when resuming.
+    let verify_clean_tree = session.config().ci().verify_clean_tree();
+    let tree = match tree_hash(session) {
+        Ok(tree) => Some(tree),
+        Err(e) if resume => return Err(e.context("failed to compute Git tree hash for resuming")),
+        Err(e) if verify_clean_tree => {
+            return Err(e.context("failed to compute Git tree hash for verifying a clean tree"));
         }
-        Ok(())
+        Err(_) => None,
+    };
+    if verify_clean_tree && let Some(before) = tree.clone() {
+        let step: StepFn = Box::new(move |s| verify_unchanged(s, &before));
+        steps.push(("clean tree".into(), step));
+    }
+    let mut passed = match &tree {
+        Some(tree) if resume => load_state(session)?
+            .filter(|state| state.tree == *tree)
+            .map(|state| state.passed)
+            .unwrap_or_default(),
+        _ => Vec::new(),
     };
 
-    step(&mut || copyright::run(session, true))?;
-    step(&mut || format::run(session, true, true))?;
+    let capture = format == OutputFormat::Json || junit.is_some() || github::is_actions();
+    let start = Instant::now();
+    let mut results = Vec::new();
+    for (name, step) in steps {
+        if passed.contains(&name) {
+            let h = HEADER;
+            eprintln!("     {h}Skipped{h:#} {name} as it already passed for this tree");
+            results.push(StepResult {
+                name,
+                skipped: true,
+                duration: Duration::ZERO,
+                error: None,
+                output: String::new(),
+            });
+            continue;
+        }
+        if capture {
+            ui::capture::start();
+        }
+        let step_start = Instant::now();
+        let result = step(session);
+        let duration = step_start.elapsed();
+        let output = if capture {
+            ui::capture::finish()
+        } else {
+            String::new()
+        };
+        let failed = result.is_err();
+        if !failed {
+            passed.push(name.clone());
+        }
+        results.push(StepResult {
+            name,
+            skipped: false,
+            duration,
+            error: result.err(),
+            output,
+        });
+        if failed && fail_fast {
+            break;
+        }
+    }
+    let duration = start.elapsed();
 
-    if extended {
-        // We need to avoid --all-targets because it will unify dev and regular dep features.
-        step(&mut || clippy::run(session, true, CargoTargets::Main))?;
-        step(&mut || clippy::run(session, true, CargoTargets::Auxiliary))?;
-    } else {
-        // Slightly faster due to shared build cache,
-        // but will miss unified feature bugs.
-        step(&mut || clippy::run(session, true, CargoTargets::All))?;
+    if let Some(tree) = tree {
+        let failed = results
+            .iter()
+            .filter(|r| r.error.is_some())
+            .map(|r| r.name.clone())
+            .collect();
+        save_state(
+            session,
+            &State {
+                tree,
+                passed,
+                failed,
+            },
+        )?;
     }
 
+    print_summary(&results, duration);
+    write_step_summary(&results, duration)?;
+
+    if format == OutputFormat::Json {
+        write_report(&results, duration, output)?;
+    }
+    if let Some(junit) = junit {
+        write_junit(&results, duration, junit)?;
+    }
+
+    let mut errs = results
+        .into_iter()
+        .filter_map(|r| r.error)
+        .collect::<Vec<_>>();
     if errs.is_empty() {
         Ok(())
+    } else if fail_fast {
+        Err(errs.remove(0))
     } else {
         let mut msg = String::from("CI verification failed:\n");
         for (i, e) in errs.into_iter().enumerate() {
@@ -46,3 +414,776 @@ pub fn run(session: &mut Session, extended: bool, fail_fast: bool) -> Result<()>
         Err(anyhow::anyhow!(msg))
     }
 }
+
+/// Prints the CI steps that would run, the tool versions they would resolve,
+/// and the commands they would run, without running anything.
+///
+/// Tool versions are resolved from the tool manifest only,
+/// so tools that aren't installed yet are listed with their version requirements.
+pub fn plan(session: &mut Session, extended: bool, only: &[String]) -> Result<()> {
+    // All the plans are made before printing any, so that configuration errors come first.
+    let mut plans = Vec::new();
+    for (name, kind) in select_steps(step_kinds(session, extended)?, only)? {
+        let (source, plan) = match kind {
+            StepKind::Builtin(builtin) => {
+                let plan = builtin_plan(session, &builtin)?;
+                let source = if find_builtin(&builtin).is_some() {
+                    format!("built-in `{builtin}`")
+                } else {
+                    format!("check `{builtin}`")
+                };
+                (source, plan)
+            }
+            StepKind::Cargo(args) => {
+                let mut plan = Plan::default();
+                let mut cmd = plan.cargo(session).cmd();
+                cmd.args(args);
+                plan.commands.push(cmd);
+                ("Cargo".into(), plan)
+            }
+            StepKind::Command(command) => {
+                let plan = Plan {
+                    commands: vec![shell_cmd(session, &command)],
+                    ..Plan::default()
+                };
+                ("shell command".into(), plan)
+            }
+        };
+        plans.push((name, source, plan));
+    }
+    if session.config().ci().verify_clean_tree() {
+        let mut plan = Plan {
+            internal: Some("compares the Git tree with the one before the other steps"),
+            ..Plan::default()
+        };
+        plan.git(session);
+        plans.push(("clean tree".into(), "verify_clean_tree".into(), plan));
+    }
+
+    print_hooks(session, "pre-ci");
+    for (idx, (name, source, plan)) in plans.iter().enumerate() {
+        print_plan(idx + 1, name, source, plan);
+    }
+    print_hooks(session, "post-ci");
+
+    let h = HEADER;
+    eprintln!(
+        "     {h}Planned{h:#} {} CI steps without running them",
+        plans.len()
+    );
+    Ok(())
+}
+
+/// Prints the shell commands of the `hook`, e.g. `pre-ci`.
+fn print_hooks(session: &mut Session, hook: &str) {
+    let Some(hook_commands) = session.config().hooks().get(hook).cloned() else {
+        return;
+    };
+    let h = HEADER;
+    for hook_command in hook_commands {
+        let cmd = shell_cmd(session, &hook_command);
+        eprintln!("        {h}Hook{h:#} {hook} `{}`", ui::cmd_line(&cmd));
+    }
+}
+
+/// Returns the command that runs the shell `command`, just like [`run_shell`] does.
+fn shell_cmd(session: &mut Session, command: &str) -> Command {
+    let (shell, shell_args) = host::shell();
+    let mut cmd = session.toolset().binctx(shell.into()).cmd();
+    cmd.args(shell_args);
+    host::shell_arg(&mut cmd, command);
+    cmd
+}
+
+/// Prints the `plan` of the CI step `name` at the one-based `position`.
+fn print_plan(position: usize, name: &str, source: &str, plan: &Plan) {
+    let h = HEADER;
+    eprintln!(
+        "{h}{:>12}{h:#} {name}, {source}",
+        format!("Step {position}")
+    );
+    for tool in &plan.tools {
+        eprintln!("        {h}Tool{h:#} {tool}");
+    }
+    if let Some(internal) = plan.internal {
+        eprintln!("    {h}Internal{h:#} {internal}");
+    }
+    for cmd in &plan.commands {
+        eprintln!("     {h}Command{h:#} `{}`", ui::cmd_line(cmd));
+    }
+}
+
+/// Prints a cache key for CI caches of the tools and the Cargo build to stdout.
+///
+/// The key is a SHA-256 hash of the tools configuration, `rust-toolchain.toml`,
+/// `Cargo.lock`, and the host triple, so it changes whenever any of them does.
+/// Line endings are normalized, so the key doesn't depend on the Git checkout settings.
+pub fn cache_key(session: &Session) -> Result<()> {
+    let tools = toml::to_string(session.config().tools())
+        .context("failed to serialize the tools configuration")?;
+
+    let mut hasher = Sha256::new();
+    let mut add = |label: &str, content: &str| {
+        hasher.update(label.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(content.replace("\r\n", "\n").as_bytes());
+        hasher.update(&[0]);
+    };
+    add("host", host::TRIPLE);
+    add("tools", &tools);
+    // The resolved version also covers the fallback to `rust-toolchain.toml` or the default.
+    add("rust", &session.config().tools().rust().to_string());
+    for name in [toolchain_file::FILE_NAME, "Cargo.lock"] {
+        let path = session.root_dir().join(name);
+        let content = if path.exists() {
+            fs::read_to_string(&path).context(format!("failed to read '{}'", path.display()))?
+        } else {
+            String::new()
+        };
+        add(name, &content);
+    }
+
+    println!("{}", hasher.finish());
+    Ok(())
+}
+
+/// Returns the Git tree hash of the project's working tree, excluding the CI state file.
+fn tree_hash(session: &mut Session) -> Result<String> {
+    let state_path = session.prep_dir().join(STATE_FILE);
+    let state_path = state_path
+        .strip_prefix(session.root_dir())
+        .unwrap_or(&state_path)
+        .to_path_buf();
+    let git = session.toolset().get::<Git>(&(), None)?;
+    git::tree_hash(&git, &[&state_path])
+}
+
+/// Verifies that the working tree still has the `before` Git tree hash,
+/// i.e. that the previous steps didn't modify, add, or remove any files.
+fn verify_unchanged(session: &mut Session, before: &str) -> Result<()> {
+    let after = tree_hash(session)?;
+    let h = HEADER;
+    if after == before {
+        eprintln!("    {h}Verified{h:#} the CI steps didn't modify the working tree");
+        return Ok(());
+    }
+    let git = session.toolset().get::<Git>(&(), None)?;
+    let changes = git::output(&git, &["diff-tree", "-r", "--name-status", before, &after])?;
+    bail!(
+        "the CI steps modified the working tree, commit the changes:\n{}",
+        changes.trim_end()
+    )
+}
+
+/// Returns the outcome of the last CI run, if its state was persisted.
+pub fn last_run(session: &mut Session) -> Result<Option<LastRun>> {
+    let Some(state) = load_state(session)? else {
+        return Ok(None);
+    };
+    let current = tree_hash(session).is_ok_and(|tree| tree == state.tree);
+    Ok(Some(LastRun {
+        passed: state.passed,
+        failed: state.failed,
+        current,
+    }))
+}
+
+/// Returns the names of the CI steps that would run.
+pub fn step_names(session: &Session, extended: bool) -> Result<Vec<String>> {
+    Ok(steps(session, extended)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect())
+}
+
+/// Loads the persisted CI state, if there is any.
+fn load_state(session: &Session) -> Result<Option<State>> {
+    let path = session.prep_dir().join(STATE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json =
+        fs::read(&path).context(format!("failed to read CI state file '{}'", path.display()))?;
+    let state = serde_json::from_slice(&json).context("failed to parse CI state JSON")?;
+    Ok(Some(state))
+}
+
+/// Persists the CI state.
+fn save_state(session: &Session, state: &State) -> Result<()> {
+    session.ensure_prep_dir()?;
+    let path = session.prep_dir().join(STATE_FILE);
+    let json = serde_json::to_string_pretty(state).context("failed to generate CI state JSON")?;
+    fs::write(&path, json).context(format!(
+        "failed to write CI state file '{}'",
+        path.display()
+    ))?;
+    Ok(())
+}
+
+/// Returns the CI steps to run.
+fn steps(session: &Session, extended: bool) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    for (name, kind) in step_kinds(session, extended)? {
+        let run: StepFn = match kind {
+            StepKind::Builtin(builtin) => builtin_step(session.config(), &builtin)?,
+            StepKind::Cargo(args) => Box::new(move |s| run_cargo(s, &args)),
+            StepKind::Command(command) => Box::new(move |s| run_shell(s, &command)),
+        };
+        steps.push((name, run));
+    }
+    Ok(steps)
+}
+
+/// Returns only the `steps` named in `only`, or all of them if it's empty.
+fn select_steps<T>(steps: Vec<(String, T)>, only: &[String]) -> Result<Vec<(String, T)>> {
+    if only.is_empty() {
+        return Ok(steps);
+    }
+    for name in only {
+        if !steps.iter().any(|(n, _)| n == name) {
+            let names = steps.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+            bail!(
+                "unknown CI step '{name}', expected one of: {}",
+                names.join(", ")
+            );
+        }
+    }
+    Ok(steps
+        .into_iter()
+        .filter(|(name, _)| only.contains(name))
+        .collect())
+}
+
+/// Returns the names and kinds of the CI steps to run.
+fn step_kinds(session: &Session, extended: bool) -> Result<Vec<(String, StepKind)>> {
+    let checks = session.config().checks();
+    for (idx, check) in checks.iter().enumerate() {
+        let name = check.name();
+        ensure!(
+            find_builtin(name).is_none(),
+            "check '{name}' has the same name as a built-in CI step"
+        );
+        ensure!(
+            !checks[..idx].iter().any(|c| c.name() == name),
+            "check '{name}' is configured more than once"
+        );
+    }
+
+    let configured = session.config().ci().steps();
+    if configured.is_empty() {
+        let no_std = !session.config().no_std().packages().is_empty();
+        let unsafe_report = session.config().unsafe_code().deny_new();
+        let hakari = session.root_dir().join(hakari::CONFIG_FILE).is_file();
+        let secrets = session.config().secrets().ci();
+        let mut steps = default_steps(extended, no_std, unsafe_report, hakari, secrets);
+        for check in checks {
+            let name = check.name().to_string();
+            steps.push((name.clone(), StepKind::Builtin(name)));
+        }
+        return Ok(steps);
+    }
+
+    let mut steps = Vec::new();
+    for step in configured {
+        if step.extended() && !extended {
+            continue;
+        }
+        let name = step.name().to_string();
+        let kind = match (step.builtin(), step.cargo(), step.command()) {
+            (None, None, None) => StepKind::Builtin(name.clone()),
+            (Some(builtin), None, None) => StepKind::Builtin(builtin.into()),
+            (None, Some(args), None) => StepKind::Cargo(args.to_vec()),
+            (None, None, Some(command)) => StepKind::Command(command.into()),
+            _ => bail!("CI step '{name}' must set at most one of builtin, cargo, and command"),
+        };
+        steps.push((name, kind));
+    }
+    Ok(steps)
+}
+
+/// Returns the default CI steps, which are named after the built-in steps they run.
+///
+/// Set `no_std` to also build the `no_std` packages in extended mode.
+/// Set `unsafe_report` to also verify the unsafe code baseline in extended mode.
+/// Set `hakari` to also verify the workspace-hack crate in extended mode.
+/// Set `secrets` to also scan for committed credentials in extended mode.
+fn default_steps(
+    extended: bool,
+    no_std: bool,
+    unsafe_report: bool,
+    hakari: bool,
+    secrets: bool,
+) -> Vec<(String, StepKind)> {
+    let mut steps = vec!["copyright", "format", "lockfile"];
+
+    if extended {
+        // We need to avoid --all-targets because it will unify dev and regular dep features.
+        steps.push("clippy-main");
+        steps.push("clippy-aux");
+        steps.push("doc-test");
+        steps.push("bench-smoke");
+        if no_std {
+            steps.push("no-std");
+        }
+        steps.push("duplicates");
+        steps.push("readme");
+        if unsafe_report {
+            steps.push("unsafe-report");
+        }
+        if hakari {
+            steps.push("hakari");
+        }
+        if secrets {
+            steps.push("secrets");
+        }
+    } else {
+        // Slightly faster due to shared build cache,
+        // but will miss unified feature bugs.
+        steps.push("clippy");
+    }
+
+    steps
+        .into_iter()
+        .map(|name| (name.into(), StepKind::Builtin(name.into())))
+        .collect()
+}
+
+/// Returns the built-in step or the custom check with the given `name`.
+pub fn builtin_step(config: &Config, name: &str) -> Result<StepFn> {
+    if let Some(builtin) = find_builtin(name) {
+        return Ok(Box::new(builtin.run));
+    }
+    let check = find_check(config, name)?.clone();
+    Ok(Box::new(move |s| run_check(s, &check)))
+}
+
+/// Returns the built-in step with the given `name`, if there is one.
+fn find_builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
+
+/// Returns the custom check with the given `name`.
+fn find_check<'a>(config: &'a Config, name: &str) -> Result<&'a Check> {
+    let Some(check) = config.checks().iter().find(|c| c.name() == name) else {
+        let mut names = BUILTINS.iter().map(|b| b.name).collect::<Vec<_>>();
+        names.extend(config.checks().iter().map(|c| c.name()));
+        bail!(
+            "unknown built-in CI step or check '{name}', expected one of: {}",
+            names.join(", ")
+        );
+    };
+    Ok(check)
+}
+
+/// Runs the shell command of the custom `check`.
+///
+/// The check is skipped if it has file globs and none of the project files match them.
+fn run_check(session: &mut Session, check: &Check) -> Result<()> {
+    if !check.files().is_empty() {
+        let files = files::collect(session.root_dir(), check.files())?;
+        if files.is_empty() {
+            let h = HEADER;
+            eprintln!("     {h}Skipped{h:#} {} as no files match", check.name());
+            return Ok(());
+        }
+    }
+    let result = run_shell(session, check.command());
+    match check.message() {
+        Some(message) => result.context(message.to_string()),
+        None => result,
+    }
+}
+
+/// Returns what the built-in step or the custom check with the given `name` would do, without running anything.
+fn builtin_plan(session: &mut Session, name: &str) -> Result<Plan> {
+    let mut plan = Plan::default();
+    if let Some(builtin) = find_builtin(name) {
+        (builtin.plan)(session, &mut plan)?;
+        return Ok(plan);
+    }
+    let check = find_check(session.config(), name)?.clone();
+    plan.commands.push(shell_cmd(session, check.command()));
+    if !check.files().is_empty() {
+        plan.internal = Some("skips the check when no files match its globs");
+    }
+    Ok(plan)
+}
+
+/// Adds the ripgrep command of the copyright step to the `plan`.
+fn copyright_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let project = session.config().project();
+    let header_regex = copyright::header_regex(project.name(), project.license());
+    let args = copyright::ripgrep_args(session, &header_regex);
+    let ver_req = session.config().tools().ripgrep().clone();
+    let mut cmd = plan.tool::<Ripgrep>(session, &ver_req).cmd();
+    cmd.args(args);
+    plan.commands.push(cmd);
+    plan.internal = Some("verifies the license files and the license of every package");
+    Ok(())
+}
+
+/// Adds the rustfmt and Taplo commands of the format step to the `plan`.
+fn format_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let rustfmt = plan.cargo(session).args(vec!["fmt".into()]);
+    plan.commands
+        .push(format::rustfmt_cmd(&rustfmt, None, true));
+    let ver_req = session.config().tools().taplo().clone();
+    let taplo = plan.tool::<Taplo>(session, &ver_req);
+    plan.commands.push(format::taplo_cmd(&taplo, true, &[]));
+    Ok(())
+}
+
+/// Adds the strict Clippy commands for the `targets` of all workspace packages to the `plan`.
+fn clippy_plan(session: &mut Session, plan: &mut Plan, targets: CargoTargets) -> Result<()> {
+    let clippy = plan.cargo(session).args(vec!["clippy".into()]);
+    plan.commands
+        .extend(clippy::strict_cmds(session, clippy, targets)?);
+    Ok(())
+}
+
+/// Adds the test command to the `plan`, which is cargo-nextest if retries are configured.
+fn test_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let cargo = plan.cargo(session);
+    let workspace = Packages::default();
+    if !session.config().test().has_retries() {
+        plan.commands
+            .push(test::cargo_cmd(&cargo, false, &workspace)?);
+        return Ok(());
+    }
+    let target_dir = metadata::fetch(session)?.target_directory;
+    let config_path = target_dir.join(test::NEXTEST_CONFIG_NAME);
+    let ver_req = session.config().tools().cargo_nextest().clone();
+    let nextest = plan
+        .tool::<CargoNextest>(session, &ver_req)
+        .env(&cargo::toolchain_vars(&cargo));
+    let cmd = test::nextest_cmd(&nextest, &workspace, config_path.as_std_path(), None)?;
+    plan.commands.push(cmd);
+    Ok(())
+}
+
+/// Adds the build command of every configured `no_std` package to the `plan`.
+fn no_std_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let no_std_cfg = session.config().no_std();
+    let target = no_std_cfg.target().to_string();
+    let packages = no_std_cfg.packages().clone();
+    for (package, features) in &packages {
+        let cargo = plan.cargo(session);
+        plan.commands
+            .push(no_std::cmd(&cargo, package, &target, features));
+    }
+    Ok(())
+}
+
+/// Adds the Lychee command of the links step to the `plan`, only checking local links if `offline`.
+fn links_plan(session: &mut Session, plan: &mut Plan, offline: bool) -> Result<()> {
+    let files = links::files(session)?;
+    let ver_req = session.config().tools().lychee().clone();
+    let lychee = plan.tool::<Lychee>(session, &ver_req);
+    plan.commands
+        .push(links::cmd(session, &lychee, &files, offline));
+    Ok(())
+}
+
+/// Adds the cargo-geiger command of every workspace package to the `plan`.
+fn unsafe_report_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let cargo = plan.cargo(session);
+    let ver_req = session.config().tools().cargo_geiger().clone();
+    let geiger = plan
+        .tool::<CargoGeiger>(session, &ver_req)
+        .env(&cargo::toolchain_vars(&cargo));
+    for package in metadata::fetch(session)?.workspace_packages() {
+        let cmd = unsafe_report::cmd(&geiger, package.manifest_path.as_std_path());
+        plan.commands.push(cmd);
+    }
+    plan.internal = Some("compares the unsafe code usage with the baseline");
+    Ok(())
+}
+
+/// Adds the cargo-hakari verification commands to the `plan`.
+fn hakari_plan(session: &mut Session, plan: &mut Plan) -> Result<()> {
+    let cargo = plan.cargo(session);
+    let ver_req = session.config().tools().cargo_hakari().clone();
+    let cargo_hakari = plan
+        .tool::<CargoHakari>(session, &ver_req)
+        .env(&cargo::toolchain_vars(&cargo));
+    for (args, _) in hakari::VERIFY_ARGS {
+        plan.commands.push(hakari::cmd(&cargo_hakari, args));
+    }
+    Ok(())
+}
+
+impl Plan {
+    /// Returns the locked Cargo, recording the Rust toolchain it uses.
+    fn cargo(&mut self, session: &mut Session) -> BinCtx {
+        let ver_req = session.config().tools().rust().to_string();
+        let toolchain = ver_req.trim_start_matches('=').to_string();
+        self.add_tool(format!("rust {toolchain} managed by rustup"));
+        let toolset = session.toolset();
+        let environment = toolset.environment().clone().rust(Some(toolchain));
+        BinCtx::new(
+            Cargo::BIN.into(),
+            toolset.working_dir().to_path_buf(),
+            environment,
+        )
+    }
+
+    /// Returns the default Git, recording that it's used.
+    fn git(&mut self, session: &mut Session) -> BinCtx {
+        self.add_tool(format!("{} from PATH without a version check", Git::NAME));
+        session.toolset().binctx(Git::BIN.into())
+    }
+
+    /// Returns the managed tool `T` that satisfies `ver_req`, recording its resolved version.
+    ///
+    /// Only the tool manifest is consulted, so nothing is set up and no binary is run.
+    fn tool<T: Tool>(&mut self, session: &mut Session, ver_req: &VersionReq) -> BinCtx {
+        let toolset = session.toolset();
+        let path = match toolset.manifest().get(T::NAME, ver_req) {
+            Some((version, path)) => {
+                self.add_tool(format!("{} {version} from the tool manifest", T::NAME));
+                // Relative paths are relative to the tools directory.
+                toolset.tools_dir().join(path)
+            }
+            None => {
+                self.add_tool(format!(
+                    "{} {ver_req} would be set up, as it's not installed",
+                    T::NAME
+                ));
+                PathBuf::from(T::BIN)
+            }
+        };
+        toolset.binctx(path)
+    }
+
+    /// Records the `tool` description, unless it already was.
+    fn add_tool(&mut self, tool: String) {
+        if !self.tools.contains(&tool) {
+            self.tools.push(tool);
+        }
+    }
+}
+
+/// Runs strict Clippy on the `targets` of all workspace packages.
+fn clippy_step(session: &mut Session, targets: CargoTargets) -> Result<()> {
+    let options = clippy::Options::default();
+    clippy::run(session, true, &Packages::default(), targets, &options)
+}
+
+/// Runs Cargo with the given `args` using the locked Rust toolchain.
+pub fn run_cargo(session: &mut Session, args: &[String]) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let ver_req = tools_cfg.rust().clone();
+    let rust_components = tools_cfg.rust_components(&[]);
+    let toolset = session.toolset();
+    let deps = CargoDeps::new(rustup_ver_req, rust_components);
+    let cargo = toolset.get::<Cargo>(&deps, &ver_req)?;
+
+    let mut cmd = cargo.cmd();
+    cmd.args(args);
+
+    ui::print_cmd(&cmd);
+
+    let status = ui::capture::status(&mut cmd).context("failed to run cargo")?;
+    ensure!(status.success(), "cargo failed: {status}");
+
+    Ok(())
+}
+
+/// Prints an aligned table of the step results and durations.
+fn print_summary(results: &[StepResult], duration: Duration) {
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    let nlen = results
+        .iter()
+        .map(|r| r.name.len())
+        .chain(["Total".len()])
+        .max()
+        .unwrap_or_default();
+    const DLEN: usize = 8;
+
+    let (h, g, e) = (TABLE_HEADER, GOOD, ERROR);
+    eprintln!();
+    eprintln!(
+        "{h}Step{h:#}{}  {h}Duration{h:#}  {h}Result{h:#}",
+        " ".repeat(nlen.saturating_sub("Step".len()))
+    );
+    for result in results {
+        let status = if result.skipped {
+            "skipped".to_string()
+        } else if result.error.is_none() {
+            format!("{g}passed{g:#}")
+        } else {
+            format!("{e}failed{e:#}")
+        };
+        eprintln!(
+            "{}  {}  {status}",
+            cell(&result.name, nlen),
+            cell(&format_duration(result.duration), DLEN)
+        );
+    }
+    eprintln!("{}  {}", cell("Total", nlen), format_duration(duration));
+}
+
+/// Writes a Markdown table of the step results and durations to the GitHub step summary,
+/// followed by the truncated output of the failed steps.
+fn write_step_summary(results: &[StepResult], duration: Duration) -> Result<()> {
+    let mut md =
+        String::from("### Prep CI\n\n| Step | Duration | Result |\n| --- | ---: | --- |\n");
+    for result in results {
+        let status = if result.skipped {
+            "skipped"
+        } else if result.error.is_none() {
+            "passed"
+        } else {
+            "**failed**"
+        };
+        md.push_str(&format!(
+            "| {} | {} | {status} |\n",
+            result.name,
+            format_duration(result.duration)
+        ));
+    }
+    md.push_str(&format!(
+        "| **Total** | **{}** | |\n",
+        format_duration(duration)
+    ));
+
+    for result in results {
+        let Some(error) = &result.error else {
+            continue;
+        };
+        let output = anstream::adapter::strip_str(&result.output).to_string();
+        let lines = output.lines().collect::<Vec<_>>();
+        // Failures are usually reported at the end, and the summary size is limited.
+        let skipped = lines.len().saturating_sub(SUMMARY_OUTPUT_LINES);
+        let mut tail = lines[skipped..].join("\n");
+        if skipped > 0 {
+            tail = format!("... {skipped} lines truncated ...\n{tail}");
+        }
+        md.push_str(&format!(
+            "\n<details><summary>{} failed: {}</summary>\n\n```text\n{}\n```\n\n</details>\n",
+            result.name,
+            html_escape(&format!("{error:#}")),
+            tail.replace("```", "` ` `")
+        ));
+    }
+
+    github::append_step_summary(&md)
+}
+
+/// Escapes the HTML special characters of `s`.
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Formats the `duration` as seconds with two decimal places.
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+/// Writes the JSON run report to `output`, or to stdout if `None`.
+fn write_report(results: &[StepResult], duration: Duration, output: Option<&Path>) -> Result<()> {
+    let report = Report {
+        success: results.iter().all(|r| r.error.is_none()),
+        duration_secs: duration.as_secs_f64(),
+        steps: results
+            .iter()
+            .map(|r| StepReport {
+                name: r.name.clone(),
+                success: r.error.is_none(),
+                skipped: r.skipped,
+                duration_secs: r.duration.as_secs_f64(),
+                error: r.error.as_ref().map(|e| format!("{e:#}")),
+                output: r.output.clone(),
+            })
+            .collect(),
+    };
+
+    let json =
+        serde_json::to_string_pretty(&report).context("failed to generate CI report JSON")?;
+    match output {
+        Some(path) => fs::write(path, json).context(format!(
+            "failed to write CI report file '{}'",
+            path.display()
+        ))?,
+        None => println!("{json}"),
+    }
+
+    Ok(())
+}
+
+/// Writes the JUnit XML report to `path`, with each step as a test case.
+fn write_junit(results: &[StepResult], duration: Duration, path: &Path) -> Result<()> {
+    let tests = results.len();
+    let failures = results.iter().filter(|r| r.error.is_some()).count();
+    let skipped = results.iter().filter(|r| r.skipped).count();
+    let time = duration.as_secs_f64();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"prep ci\" tests=\"{tests}\" failures=\"{failures}\" \
+        skipped=\"{skipped}\" time=\"{time:.3}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"prep ci\" tests=\"{tests}\" failures=\"{failures}\" \
+        skipped=\"{skipped}\" time=\"{time:.3}\">\n"
+    ));
+    for result in results {
+        let name = xml_escape(&result.name);
+        let time = result.duration.as_secs_f64();
+        xml.push_str(&format!(
+            "    <testcase name=\"{name}\" classname=\"prep.ci\" time=\"{time:.3}\">\n"
+        ));
+        if result.skipped {
+            xml.push_str("      <skipped message=\"already passed for this tree\"/>\n");
+        }
+        if let Some(error) = &result.error {
+            let message = xml_escape(&error.to_string());
+            let details = xml_escape(&format!("{error:#}"));
+            xml.push_str(&format!(
+                "      <failure message=\"{message}\">{details}</failure>\n"
+            ));
+        }
+        if !result.output.is_empty() {
+            let output = xml_escape(&result.output);
+            xml.push_str(&format!("      <system-out>{output}</system-out>\n"));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+
+    fs::write(path, xml).context(format!(
+        "failed to write JUnit report file '{}'",
+        path.display()
+    ))?;
+
+    Ok(())
+}
+
+/// Escapes `s` for use in XML text and attribute values.
+///
+/// Control characters, e.g. ANSI escape codes, are not allowed in XML and are removed.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::cmd::ci;
+use crate::cmd::tools::prune::{self, MAX_AGE_DEFAULT};
+use crate::session::Session;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::ui;
+use crate::ui::log;
+use crate::ui::style::HEADER;
+
+/// Categories of state that can be cleaned.
+#[derive(Clone, Copy)]
+pub struct Categories {
+    /// The caches in the prep directory, i.e. the CI state and the logs of previous runs.
+    pub cache: bool,
+    /// The tool installations unused for the configured prune age.
+    pub tools: bool,
+    /// The Cargo build artifacts in the target directory.
+    pub cargo: bool,
+}
+
+/// Remove Prep caches, stale tool installations, and Cargo build artifacts.
+///
+/// When no `categories` are selected, the caches and the stale tool installations are removed.
+/// Cargo build artifacts are only removed when explicitly selected, as they take long to rebuild.
+///
+/// In `dry_run` mode nothing is removed, only the would-be removals are listed.
+pub fn run(session: &mut Session, mut categories: Categories, dry_run: bool) -> Result<()> {
+    if !categories.cache && !categories.tools && !categories.cargo {
+        categories.cache = true;
+        categories.tools = true;
+    }
+
+    if categories.cache {
+        clean_cache(session, dry_run)?;
+    }
+    if categories.tools {
+        clean_tools(session, dry_run)?;
+    }
+    if categories.cargo {
+        clean_cargo(session, dry_run)?;
+    }
+
+    Ok(())
+}
+
+/// Removes the CI state and the logs from the prep directory.
+fn clean_cache(session: &Session, dry_run: bool) -> Result<()> {
+    let h = HEADER;
+    let state_path = session.prep_dir().join(ci::STATE_FILE);
+    if state_path.is_file() {
+        if dry_run {
+            eprintln!("{h}Would remove{h:#} '{}'", state_path.display());
+        } else {
+            fs::remove_file(&state_path).context(format!(
+                "failed to remove CI state file '{}'",
+                state_path.display()
+            ))?;
+            eprintln!("     {h}Removed{h:#} '{}'", state_path.display());
+        }
+    }
+    let logs_dir = session.prep_dir().join(log::DIR_NAME);
+    if logs_dir.is_dir() {
+        if dry_run {
+            eprintln!("{h}Would remove{h:#} '{}'", logs_dir.display());
+        } else {
+            fs::remove_dir_all(&logs_dir).context(format!(
+                "failed to remove logs directory '{}'",
+                logs_dir.display()
+            ))?;
+            eprintln!("     {h}Removed{h:#} '{}'", logs_dir.display());
+        }
+    }
+    Ok(())
+}
+
+/// Removes the tool installations which haven't been used for the configured prune age.
+fn clean_tools(session: &mut Session, dry_run: bool) -> Result<()> {
+    let max_age = session
+        .config()
+        .tools()
+        .prune_after()
+        .unwrap_or(MAX_AGE_DEFAULT);
+    let toolset = session.toolset();
+    if dry_run {
+        let h = HEADER;
+        for (name, version) in toolset.stale(max_age) {
+            eprintln!("{h}Would remove{h:#} {name} {version}");
+        }
+    } else {
+        prune::print_pruned(&toolset.prune(max_age)?);
+    }
+    Ok(())
+}
+
+/// Removes the Cargo build artifacts of the workspace with `cargo clean`.
+fn clean_cargo(session: &mut Session, dry_run: bool) -> Result<()> {
+    let toolset = session.toolset();
+    let cargo = toolset.get::<Cargo>(&CargoDeps::default(), None)?;
+
+    let mut cmd = cargo.cmd();
+    cmd.arg("clean").args(ui::cargo_verbosity_args());
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+
+    ui::print_cmd(&cmd);
+
+    let status = cmd.status().context("failed to run cargo clean")?;
+    ensure!(status.success(), "cargo clean failed: {status}");
+
+    Ok(())
+}
@@ -1,24 +1,199 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anstream::eprintln;
 use anyhow::{Context, Result, bail, ensure};
+use cargo_metadata::Message;
+use cargo_metadata::diagnostic::{Diagnostic, DiagnosticLevel};
 use semver::{Op, VersionReq};
+use serde::{Deserialize, Serialize};
 
-use crate::cmd::CargoTargets;
+use crate::cmd::{CargoTargets, Packages};
+use crate::github;
 use crate::session::Session;
+use crate::tools::BinCtx;
 use crate::tools::cargo::CargoDeps;
 use crate::tools::clippy::{Clippy, ClippyDeps};
 use crate::ui;
+use crate::ui::style::{HEADER, LITERAL};
+
+/// The file where the warning baseline is stored, inside the prep directory.
+pub const BASELINE_FILE: &str = "clippy-baseline.json";
+
+/// The SARIF schema of the written reports.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Clippy fix mode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FixMode {
+    /// Only report the diagnostics.
+    #[default]
+    Off,
+    /// Apply the suggestions, allowing staged changes in the working tree.
+    AllowStaged,
+    /// Apply the suggestions, allowing any changes in the working tree.
+    AllowDirty,
+}
+
+/// A warning identified independently of its line, so that it survives unrelated edits.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Warning {
+    lint: String,
+    file: String,
+    message: String,
+}
+
+/// Recorded warnings which don't fail strict analysis.
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    warnings: Vec<Warning>,
+}
+
+/// SARIF report, as much of it as needed to describe diagnostics.
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+/// SARIF run of a single tool.
+#[derive(Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
 
-/// Runs Clippy analysis on the given `targets`.
+/// SARIF tool description.
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+/// SARIF tool driver, i.e. the analyzer and its rules.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+/// SARIF rule, i.e. a lint.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+/// SARIF result, i.e. a diagnostic.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+/// SARIF message.
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+/// SARIF location of a result.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+/// SARIF location in a file.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+/// SARIF file reference, relative to the source root.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+    uri_base_id: &'static str,
+}
+
+/// SARIF region in a file, with 1-based lines and columns.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+/// A Clippy invocation shared by all the analyzed feature sets.
+struct Invocation<'a> {
+    clippy: BinCtx,
+    packages: &'a Packages,
+    targets: CargoTargets,
+    lints: Vec<String>,
+    fix: FixMode,
+    /// Whether to request JSON diagnostics, for GitHub annotations or diagnostic collection.
+    json: bool,
+}
+
+/// Options of a Clippy run, which are all off by default.
+#[derive(Clone, Copy, Default)]
+pub struct Options<'a> {
+    /// The mode for automatically applying the suggestions.
+    pub fix: FixMode,
+    /// Analyze separately with no default features, default features, all features,
+    /// and each of the feature sets configured in `prep.toml`.
+    pub features_matrix: bool,
+    /// Record all the current warnings as the new baseline.
+    pub update_baseline: bool,
+    /// The path to also write the diagnostics to as a SARIF report.
+    pub sarif: Option<&'a Path>,
+}
+
+/// Runs Clippy analysis on the given `targets` of the selected `packages`.
 ///
 /// In `strict` mode warnings are treated as errors and Cargo version is locked.
-pub fn run(session: &mut Session, strict: bool, targets: CargoTargets) -> Result<()> {
-    let rust_components = vec!["clippy".into()];
+/// If a warning baseline exists, then only warnings not in the baseline are treated as errors.
+/// The lints configured in `prep.toml` are always applied.
+///
+/// When running in GitHub Actions, the diagnostics are also emitted as annotations.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    packages: &Packages,
+    targets: CargoTargets,
+    options: &Options,
+) -> Result<()> {
+    let Options {
+        fix,
+        features_matrix,
+        update_baseline,
+        sarif,
+    } = *options;
     let clippy = if strict {
         let tools_cfg = session.config().tools();
         let rustup_ver_req = tools_cfg.rustup().clone();
         let cargo_ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&["clippy"]);
         let toolset = session.toolset();
         let cargo_deps = CargoDeps::new(rustup_ver_req, rust_components);
         let ver_req = derive_version(&cargo_ver_req)?;
@@ -26,34 +201,443 @@ pub fn run(session: &mut Session, strict: bool, targets: CargoTargets) -> Result
         toolset.get::<Clippy>(&deps, &ver_req)?
     } else {
         let toolset = session.toolset();
-        let cargo_deps = CargoDeps::new(None, rust_components);
+        let cargo_deps = CargoDeps::new(None, vec!["clippy".into()]);
         let deps = ClippyDeps::new(cargo_deps, None);
         toolset.get::<Clippy>(&deps, None)?
     };
 
-    let mut cmd = clippy.cmd();
-    cmd.arg("--locked")
-        .arg("--workspace")
-        .args(targets.as_args())
-        .arg("--all-features");
-    if strict {
-        cmd.args(["--", "-D", "warnings"]);
+    let baseline_path = session.prep_dir().join(BASELINE_FILE);
+    let baseline = if strict && !update_baseline {
+        load_baseline(&baseline_path)?
+    } else {
+        None
+    };
+    let collect = update_baseline || baseline.is_some();
+
+    // With a baseline the warnings are checked by us instead.
+    let invocation = Invocation::new(
+        session,
+        clippy,
+        packages,
+        targets,
+        strict && !collect,
+        fix,
+        collect || sarif.is_some(),
+    );
+    let feature_sets = feature_sets(session, features_matrix);
+
+    // Analyze all the feature sets before reporting, to show every breakage at once.
+    let h = HEADER;
+    let mut counts: BTreeMap<Warning, usize> = BTreeMap::new();
+    let mut diagnostics = Vec::new();
+    let mut failed = Vec::new();
+    for (name, features) in &feature_sets {
+        if features_matrix {
+            eprintln!("   {h}Analyzing{h:#} feature set '{name}'");
+        }
+        let result = invocation.run(features, &mut diagnostics);
+        match result {
+            Ok(w) => {
+                // The same warning is reported again for every feature set.
+                let mut set_counts: BTreeMap<Warning, usize> = BTreeMap::new();
+                for warning in w {
+                    *set_counts.entry(warning).or_default() += 1;
+                }
+                for (warning, count) in set_counts {
+                    let max = counts.entry(warning).or_default();
+                    *max = (*max).max(count);
+                }
+            }
+            Err(e) if !features_matrix => {
+                // Errors are diagnostics too, so the report is written before failing.
+                if let Some(sarif) = sarif {
+                    write_sarif(sarif, &diagnostics)?;
+                }
+                return Err(e);
+            }
+            Err(e) => {
+                ui::print_err(&format!("feature set '{name}': {e:#}"));
+                failed.push(name.as_str());
+            }
+        }
+    }
+    if let Some(sarif) = sarif {
+        write_sarif(sarif, &diagnostics)?;
+    }
+    if !failed.is_empty() {
+        bail!(
+            "cargo clippy failed for feature sets: {}",
+            failed.join(", ")
+        );
     }
 
-    ui::print_cmd(&cmd);
+    let warnings = counts
+        .into_iter()
+        .flat_map(|(warning, count)| std::iter::repeat_n(warning, count))
+        .collect::<Vec<_>>();
 
-    let status = cmd.status().context("failed to run cargo clippy")?;
-    ensure!(status.success(), "cargo clippy failed: {status}");
+    if update_baseline {
+        session.ensure_prep_dir()?;
+        save_baseline(&baseline_path, Baseline { warnings })?;
+        eprintln!(
+            "     {h}Updated{h:#} clippy warning baseline '{}'",
+            baseline_path.display()
+        );
+    } else if let Some(baseline) = baseline {
+        check_baseline(&baseline, &warnings)?;
+    }
 
     Ok(())
 }
 
+/// Returns the strict Clippy commands for the `targets` of all workspace packages, without running them.
+pub fn strict_cmds(
+    session: &Session,
+    clippy: BinCtx,
+    targets: CargoTargets,
+) -> Result<Vec<Command>> {
+    // The warnings are collected if there is a baseline, which is only loaded when running.
+    let collect = session.prep_dir().join(BASELINE_FILE).is_file();
+    let packages = Packages::default();
+    let invocation = Invocation::new(
+        session,
+        clippy,
+        &packages,
+        targets,
+        !collect,
+        FixMode::Off,
+        collect,
+    );
+    feature_sets(session, false)
+        .iter()
+        .map(|(_, features)| invocation.cmd(features))
+        .collect()
+}
+
+/// Returns the names and the arguments of the feature sets to analyze separately.
+///
+/// Set `features_matrix` to get no default features, default features, all features,
+/// and each of the configured feature sets, instead of just the configured features.
+fn feature_sets(session: &Session, features_matrix: bool) -> Vec<(String, Vec<String>)> {
+    let clippy_cfg = session.config().clippy();
+    let mut feature_sets = Vec::new();
+    if features_matrix {
+        feature_sets.push((
+            "no-default".to_string(),
+            vec!["--no-default-features".to_string()],
+        ));
+        feature_sets.push(("default".to_string(), vec![]));
+        feature_sets.push(("all".to_string(), vec!["--all-features".to_string()]));
+        for (name, features) in clippy_cfg.feature_sets() {
+            let mut args = vec!["--no-default-features".to_string()];
+            if !features.is_empty() {
+                args.extend(["--features".to_string(), features.join(",")]);
+            }
+            feature_sets.push((name.clone(), args));
+        }
+    } else if clippy_cfg.all_features() {
+        feature_sets.push(("all".to_string(), vec!["--all-features".to_string()]));
+    } else {
+        feature_sets.push(("default".to_string(), vec![]));
+    }
+    feature_sets
+}
+
+impl<'a> Invocation<'a> {
+    /// Creates a new invocation with the lints configured in `prep.toml`.
+    ///
+    /// Set `deny_warnings` to treat warnings as errors,
+    /// and `collect` to request JSON diagnostics for collecting them.
+    fn new(
+        session: &Session,
+        clippy: BinCtx,
+        packages: &'a Packages,
+        targets: CargoTargets,
+        deny_warnings: bool,
+        fix: FixMode,
+        collect: bool,
+    ) -> Self {
+        let mut lints = Vec::new();
+        if deny_warnings {
+            lints.extend(["-D".to_string(), "warnings".to_string()]);
+        }
+        lints.extend(session.config().clippy().lint_args());
+        Self {
+            clippy,
+            packages,
+            targets,
+            lints,
+            fix,
+            json: collect || github::is_actions(),
+        }
+    }
+
+    /// Returns the Clippy command with the given `features` arguments.
+    fn cmd(&self, features: &[String]) -> Result<Command> {
+        let mut cmd = self.clippy.cmd();
+        cmd.args(ui::cargo_verbosity_args())
+            .arg("--locked")
+            .args(self.packages.as_args()?)
+            .args(self.targets.as_args())
+            .args(features);
+        match self.fix {
+            FixMode::Off => (),
+            FixMode::AllowStaged => {
+                cmd.args(["--fix", "--allow-staged"]);
+            }
+            FixMode::AllowDirty => {
+                cmd.args(["--fix", "--allow-staged", "--allow-dirty"]);
+            }
+        }
+        if self.json {
+            cmd.arg("--message-format=json-diagnostic-rendered-ansi");
+        }
+        if !self.lints.is_empty() {
+            cmd.arg("--").args(&self.lints);
+        }
+        Ok(cmd)
+    }
+
+    /// Runs Clippy once with the given `features` arguments.
+    ///
+    /// Returns the reported warnings, which are only collected in JSON mode,
+    /// same as all the diagnostics, which are added to `diagnostics`.
+    fn run(&self, features: &[String], diagnostics: &mut Vec<Diagnostic>) -> Result<Vec<Warning>> {
+        let mut cmd = self.cmd(features)?;
+
+        ui::print_cmd(&cmd);
+
+        let mut warnings = Vec::new();
+        let status = if self.json {
+            let working_dir = self.clippy.working_dir().to_path_buf();
+            let is_actions = github::is_actions();
+            ui::capture::status_lines(&mut cmd, |line| {
+                if let Some(diagnostic) = print_message(line) {
+                    if is_actions {
+                        annotate(&working_dir, &diagnostic);
+                    }
+                    if let Some(warning) = warning(&diagnostic) {
+                        warnings.push(warning);
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            })
+        } else {
+            ui::capture::status(&mut cmd)
+        }
+        .context("failed to run cargo clippy")?;
+        ensure!(status.success(), "cargo clippy failed: {status}");
+
+        Ok(warnings)
+    }
+}
+
+/// Prints the rendered diagnostic of the JSON message `line` and returns the diagnostic.
+///
+/// Lines that aren't compiler messages are printed as is.
+fn print_message(line: &str) -> Option<Diagnostic> {
+    let Ok(message) = serde_json::from_str::<Message>(line) else {
+        ui::capture::eprint(&format!("{line}\n"));
+        return None;
+    };
+    let Message::CompilerMessage(message) = message else {
+        return None;
+    };
+    let diagnostic = message.message;
+    if let Some(rendered) = &diagnostic.rendered {
+        ui::capture::eprint(rendered);
+    }
+    Some(diagnostic)
+}
+
+/// Returns the baseline [`Warning`] of the `diagnostic`, if it is a warning with a location.
+fn warning(diagnostic: &Diagnostic) -> Option<Warning> {
+    if diagnostic.level != DiagnosticLevel::Warning {
+        return None;
+    }
+    let span = diagnostic.spans.iter().find(|s| s.is_primary)?;
+    Some(Warning {
+        lint: diagnostic
+            .code
+            .as_ref()
+            .map(|c| c.code.clone())
+            .unwrap_or_default(),
+        file: span.file_name.replace('\\', "/"),
+        message: diagnostic.message.clone(),
+    })
+}
+
+/// Loads the warning baseline from `path`, if it exists.
+fn load_baseline(path: &Path) -> Result<Option<Baseline>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read(path).context(format!(
+        "failed to read clippy baseline file '{}'",
+        path.display()
+    ))?;
+    let baseline = serde_json::from_slice(&json).context("failed to parse clippy baseline JSON")?;
+    Ok(Some(baseline))
+}
+
+/// Saves the warning `baseline` to `path`.
+fn save_baseline(path: &Path, baseline: Baseline) -> Result<()> {
+    let json = serde_json::to_string_pretty(&baseline)
+        .context("failed to generate clippy baseline JSON")?;
+    fs::write(path, json).context(format!(
+        "failed to write clippy baseline file '{}'",
+        path.display()
+    ))?;
+    Ok(())
+}
+
+/// Verifies that all the `warnings` are present in the `baseline`.
+///
+/// Every baseline entry accounts for only a single occurrence of the same warning.
+fn check_baseline(baseline: &Baseline, warnings: &[Warning]) -> Result<()> {
+    let mut allowed: BTreeMap<&Warning, usize> = BTreeMap::new();
+    for warning in &baseline.warnings {
+        *allowed.entry(warning).or_default() += 1;
+    }
+    let mut new = Vec::new();
+    for warning in warnings {
+        match allowed.get_mut(warning) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => new.push(warning),
+        }
+    }
+    if new.is_empty() {
+        return Ok(());
+    }
+
+    let l = LITERAL;
+    let mut msg = format!(
+        "found {} new clippy warnings not in the baseline:",
+        new.len()
+    );
+    for warning in new {
+        msg.push_str(&format!(
+            "\n{l}{}{l:#}: {} ({})",
+            warning.file, warning.message, warning.lint
+        ));
+    }
+    bail!(msg)
+}
+
+/// Writes the `diagnostics` with a primary span to `path` as a SARIF report.
+///
+/// Diagnostics reported multiple times, e.g. for multiple feature sets, are written once.
+fn write_sarif(path: &Path, diagnostics: &[Diagnostic]) -> Result<()> {
+    let mut rules = BTreeMap::new();
+    let mut results = Vec::new();
+    let mut seen = Vec::new();
+    for diagnostic in diagnostics {
+        let level = match diagnostic.level {
+            DiagnosticLevel::Ice | DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+            _ => continue,
+        };
+        let Some(span) = diagnostic.spans.iter().find(|s| s.is_primary) else {
+            continue;
+        };
+        let rule_id = diagnostic.code.as_ref().map(|c| c.code.clone());
+        let file = span.file_name.replace('\\', "/");
+        let key = (
+            rule_id.clone(),
+            file.clone(),
+            span.line_start,
+            span.column_start,
+        );
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        if let Some(id) = &rule_id {
+            let help_uri = id.strip_prefix("clippy::").map(|lint| {
+                format!("https://rust-lang.github.io/rust-clippy/master/index.html#{lint}")
+            });
+            rules.entry(id.clone()).or_insert(SarifRule {
+                id: id.clone(),
+                help_uri,
+            });
+        }
+        results.push(SarifResult {
+            rule_id,
+            level,
+            message: SarifMessage {
+                text: diagnostic.message.clone(),
+            },
+            locations: vec![SarifLocation {
+                physical_location: SarifPhysicalLocation {
+                    artifact_location: SarifArtifactLocation {
+                        uri: file,
+                        uri_base_id: "%SRCROOT%",
+                    },
+                    region: SarifRegion {
+                        start_line: span.line_start,
+                        start_column: span.column_start,
+                        end_line: span.line_end,
+                        end_column: span.column_end,
+                    },
+                },
+            }],
+        });
+    }
+
+    let count = results.len();
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: "2.1.0",
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "clippy",
+                    information_uri: "https://github.com/rust-lang/rust-clippy",
+                    rules: rules.into_values().collect(),
+                },
+            },
+            results,
+        }],
+    };
+    let json = serde_json::to_string_pretty(&log).context("failed to generate SARIF JSON")?;
+    fs::write(path, json).context(format!("failed to write SARIF report '{}'", path.display()))?;
+
+    let h = HEADER;
+    eprintln!(
+        "   {h}Generated{h:#} SARIF report of {count} diagnostics '{}'",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Emits a GitHub annotation for the primary span of the `diagnostic`.
+fn annotate(working_dir: &Path, diagnostic: &Diagnostic) {
+    let level = match diagnostic.level {
+        DiagnosticLevel::Ice | DiagnosticLevel::Error => github::Level::Error,
+        DiagnosticLevel::Warning => github::Level::Warning,
+        _ => return,
+    };
+    let Some(span) = diagnostic.spans.iter().find(|s| s.is_primary) else {
+        return;
+    };
+    let location = github::Location {
+        file: Path::new(&span.file_name),
+        line: span.line_start,
+        end_line: Some(span.line_end),
+        col: Some(span.column_start),
+        end_col: Some(span.column_end),
+    };
+    let title = diagnostic.code.as_ref().map(|c| c.code.as_str());
+    github::annotate(level, working_dir, &location, title, &diagnostic.message);
+}
+
 /// Derives the clippy version from the Rust toolchain version.
 // NOTE: When we move to Rust toolchain names instead, the Clippy version could probably be any.
 //       That is because if we only use a non-default clippy version with a single toolchain version
 //       then there is no risk of getting an incorrect version back from the cache.
 //       Clippy, by design, can be only called by the primary toolchain. So this would work fine.
-fn derive_version(rust_ver_req: &VersionReq) -> Result<VersionReq> {
+pub fn derive_version(rust_ver_req: &VersionReq) -> Result<VersionReq> {
     if rust_ver_req.comparators.len() != 1 {
         bail!(
             "Only simple `=MAJOR.MINOR` version requirements are supported for the Rust toolchain, got: {}",
@@ -0,0 +1,76 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::io;
+
+use anyhow::Result;
+use clap::{Command, ValueEnum};
+use clap_complete::Shell;
+
+use crate::session::Session;
+
+/// Dynamically completed values, which depend on the project and the installed tools.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Candidates {
+    /// Task names configured in `prep.toml`.
+    Tasks,
+    /// Names of the installed tools.
+    Tools,
+}
+
+/// Bash completion of task and tool names, wrapping the generated `_prep` function.
+const BASH_DYNAMIC: &str = r#"
+_prep_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    if [[ ${COMP_CWORD} -eq 2 && ${COMP_WORDS[1]} == run ]]; then
+        COMPREPLY=($(compgen -W "$(prep completions --list tasks 2>/dev/null)" -- "$cur"))
+    elif [[ ${COMP_CWORD} -eq 3 && ${COMP_WORDS[1]} == tools && ${COMP_WORDS[2]} == uninstall ]]; then
+        COMPREPLY=($(compgen -W "$(prep completions --list tools 2>/dev/null)" -- "$cur"))
+    else
+        _prep "$@"
+    fi
+}
+complete -F _prep_dynamic -o bashdefault -o default prep
+"#;
+
+/// Fish completion of task and tool names.
+const FISH_DYNAMIC: &str = r#"
+complete -c prep -n "__fish_prep_using_subcommand run" -f -a "(prep completions --list tasks 2>/dev/null)"
+complete -c prep -n "__fish_prep_using_subcommand tools; and __fish_seen_subcommand_from uninstall" -f -a "(prep completions --list tools 2>/dev/null)"
+"#;
+
+/// Prints the completion script for the `shell` to stdout.
+///
+/// The scripts for bash and fish also complete task and tool names,
+/// by calling back into Prep with [`list`].
+pub fn generate(shell: Shell, cmd: &mut Command) -> Result<()> {
+    clap_complete::generate(shell, cmd, "prep", &mut io::stdout());
+    match shell {
+        Shell::Bash => print!("{BASH_DYNAMIC}"),
+        Shell::Fish => print!("{FISH_DYNAMIC}"),
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Prints the `candidates` to stdout, one per line, for use by the completion scripts.
+pub fn list(session: &mut Session, candidates: Candidates) -> Result<()> {
+    let names = match candidates {
+        Candidates::Tasks => session.config().tasks().keys().cloned().collect(),
+        Candidates::Tools => {
+            let mut names = session
+                .toolset()
+                .manifest()
+                .entries()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            names.dedup();
+            names
+        }
+    };
+    for name in names {
+        println!("{name}");
+    }
+    Ok(())
+}
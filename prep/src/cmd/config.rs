@@ -0,0 +1,150 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::config::{self, CONFIG_VERSION, Config};
+use crate::session::Session;
+use crate::ui::style::HEADER;
+
+/// Upgrades the project and user config files to the current config layout.
+///
+/// The files are edited in place, so comments and formatting are preserved.
+pub fn migrate(session: &mut Session) -> Result<()> {
+    let h = HEADER;
+    let config_paths = [
+        session.user_config_path(),
+        session.config_path().to_path_buf(),
+    ];
+    for config_path in config_paths.iter().filter(|p| p.exists()) {
+        let config_toml = fs::read_to_string(config_path).context(format!(
+            "failed to read config file '{}'",
+            config_path.display()
+        ))?;
+        let Some(migrated) = config::migrate(&config_toml).context(format!(
+            "failed to migrate config file '{}'",
+            config_path.display()
+        ))?
+        else {
+            eprintln!(
+                "    {h}Verified{h:#} config file '{}' is up to date",
+                config_path.display()
+            );
+            continue;
+        };
+        fs::write(config_path, migrated).context(format!(
+            "failed to write config file '{}'",
+            config_path.display()
+        ))?;
+        eprintln!(
+            "    {h}Migrated{h:#} config file '{}' to version {CONFIG_VERSION}",
+            config_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Prints the effective value of the dotted config `key`, including defaults and the user config.
+///
+/// Strings are printed as is, other values as TOML.
+pub fn get(session: &mut Session, key: &str) -> Result<()> {
+    let config =
+        toml::Value::try_from(session.config()).context("failed to generate config TOML")?;
+    let mut value = &config;
+    for part in key.split('.') {
+        value = value
+            .get(part)
+            .with_context(|| format!("config key `{key}` is not set"))?;
+    }
+    match value {
+        toml::Value::String(s) => println!("{s}"),
+        toml::Value::Table(table) => {
+            let table = toml::to_string(table).context("failed to generate config TOML")?;
+            print!("{table}");
+        }
+        value => println!("{value}"),
+    }
+    Ok(())
+}
+
+/// Sets the dotted config `key` to `value` in the project config file, creating it if needed.
+///
+/// The `value` is parsed as TOML, falling back to a string, unless the current value is a string.
+/// The file is edited in place, so comments and formatting are preserved,
+/// and it is validated before it's written.
+pub fn set(session: &mut Session, key: &str, value: &str) -> Result<()> {
+    let config_path = session.config_path().to_path_buf();
+    let config_toml = if config_path.exists() {
+        let config_toml = fs::read_to_string(&config_path).context(format!(
+            "failed to read config file '{}'",
+            config_path.display()
+        ))?;
+        config::migrate(&config_toml)?.unwrap_or(config_toml)
+    } else {
+        format!("config-version = {CONFIG_VERSION}\n")
+    };
+    let mut doc = config_toml.parse::<DocumentMut>().context(format!(
+        "failed to parse config file '{}'",
+        config_path.display()
+    ))?;
+
+    let parts = key.split('.').collect::<Vec<_>>();
+    let (name, tables) = parts.split_last().context("config key can't be empty")?;
+    ensure!(
+        parts.iter().all(|p| !p.is_empty()),
+        "config key `{key}` has an empty part"
+    );
+
+    // Quoting strings on the command line is tedious, so known strings are taken verbatim.
+    let current = toml::Value::try_from(session.config())
+        .ok()
+        .and_then(|c| parts.iter().try_fold(c, |v, p| v.get(p).cloned()));
+    let value = match current {
+        Some(toml::Value::String(_)) => Value::from(value),
+        _ => value
+            .parse::<Value>()
+            .unwrap_or_else(|_| Value::from(value)),
+    };
+
+    let mut table = doc.as_table_mut() as &mut dyn TableLike;
+    for part in tables {
+        let item = table.entry(part).or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        table = item
+            .as_table_like_mut()
+            .with_context(|| format!("config key `{part}` of `{key}` is not a table"))?;
+    }
+    // Replace an existing value in place to keep the comments around it.
+    match table.get_mut(name) {
+        Some(Item::Value(existing)) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(name, Item::Value(value.decorated(" ", "")));
+        }
+    }
+
+    let config_toml = doc.to_string();
+    Config::parse(&config_toml).context(format!("invalid value for config key `{key}`"))?;
+    session.ensure_prep_dir()?;
+    fs::write(&config_path, config_toml).context(format!(
+        "failed to write config file '{}'",
+        config_path.display()
+    ))?;
+
+    let h = HEADER;
+    eprintln!(
+        "     {h}Updated{h:#} `{key}` in config file '{}'",
+        config_path.display()
+    );
+    Ok(())
+}
@@ -1,7 +1,11 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::fs;
+
+use anstream::eprintln;
 use anyhow::{Context, Result, bail, ensure};
+use regex::bytes::RegexBuilder;
 use time::UtcDateTime;
 
 use crate::session::Session;
@@ -9,14 +13,173 @@ use crate::tools::cargo::CargoDeps;
 use crate::tools::ripgrep::{Ripgrep, RipgrepDeps};
 use crate::ui;
 use crate::ui::style::{ERROR, HEADER, LITERAL, NOTE};
+use crate::{files, metadata};
 
 // TODO: Allow configuring the regex
-// TODO: Allow excluding files from the check
 
 /// Verify copyright headers.
 ///
-/// In `strict` mode ripgrep version is locked.
-pub fn run(session: &mut Session, strict: bool) -> Result<()> {
+/// By default the files are scanned internally, set `ripgrep` to use ripgrep instead.
+///
+/// In `strict` mode the managed ripgrep with the locked version is always used.
+pub fn run(session: &mut Session, strict: bool, ripgrep: bool) -> Result<()> {
+    let project = session.config().project();
+    let name = project.name().to_string();
+    let license = project.license().to_string();
+    let header_regex = header_regex(&name, &license);
+
+    let missing = if ripgrep || strict {
+        scan_ripgrep(session, strict, &header_regex)?
+    } else {
+        scan(session, &header_regex)?
+    };
+
+    if !missing.is_empty() {
+        print_missing(&name, &license, &missing);
+        bail!("failed copyright header verification");
+    }
+
+    let h = HEADER;
+    eprintln!("    {h}Verified{h:#} all source files have correct copyright headers.");
+
+    verify_license_files(session, &license)?;
+    eprintln!("    {h}Verified{h:#} all license files exist and manifests declare {license}.");
+
+    Ok(())
+}
+
+/// Verifies that there is a license file at the project root for every license in `license`,
+/// and that every published workspace package declares `license` as its `license` field.
+///
+/// Packages with a `license-file` are skipped, and so are the manifests outside a Cargo workspace.
+fn verify_license_files(session: &mut Session, license: &str) -> Result<()> {
+    let ids = license_ids(license);
+    let mut problems = Vec::new();
+    for id in &ids {
+        let candidates = license_file_names(id, ids.len() == 1);
+        if !candidates
+            .iter()
+            .any(|name| session.root_dir().join(name).is_file())
+        {
+            problems.push(format!(
+                "no license file for {id}, expected one of: {}",
+                candidates.join(", ")
+            ));
+        }
+    }
+
+    // The manifests can only be verified in a Cargo workspace, e.g. not at a Git root.
+    let metadata = if session.root_dir().join("Cargo.toml").is_file() {
+        Some(metadata::fetch(session)?)
+    } else {
+        None
+    };
+    let packages = metadata.iter().flat_map(|m| m.workspace_packages());
+    for package in packages {
+        // Unpublished packages and ones with their own license file can have any license.
+        let unpublished = package.publish.as_ref().is_some_and(Vec::is_empty);
+        if unpublished || package.license_file.is_some() {
+            continue;
+        }
+        match &package.license {
+            Some(declared) if same_ids(&license_ids(declared), &ids) => (),
+            Some(declared) => problems.push(format!(
+                "package {} declares license `{declared}` in '{}'",
+                package.name, package.manifest_path
+            )),
+            None => problems.push(format!(
+                "package {} declares no license in '{}'",
+                package.name, package.manifest_path
+            )),
+        }
+    }
+
+    if !problems.is_empty() {
+        let (e, l) = (ERROR, LITERAL);
+        eprintln!("{e}The license files and manifests don't match {l}{license}{l:#}:{e:#}");
+        for problem in &problems {
+            eprintln!("  {problem}");
+        }
+        bail!("failed license file verification");
+    }
+    Ok(())
+}
+
+/// Returns the license identifiers of the SPDX `expression`, e.g. `Apache-2.0` and `MIT`.
+///
+/// License exceptions like `LLVM-exception` are not licenses of their own, so they are skipped.
+fn license_ids(expression: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    let mut exception = false;
+    for token in expression.split(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        match token {
+            "" | "OR" | "AND" => (),
+            "WITH" => exception = true,
+            _ if exception => exception = false,
+            id => {
+                let id = id.trim_end_matches('+');
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// Returns whether the license identifiers `a` and `b` are the same, in any order.
+///
+/// So e.g. `MIT OR Apache-2.0` matches `Apache-2.0 OR MIT`.
+fn same_ids(a: &[&str], b: &[&str]) -> bool {
+    a.len() == b.len() && a.iter().all(|id| b.contains(id))
+}
+
+/// Returns the accepted license file names for the license `id`,
+/// e.g. `LICENSE-APACHE` for `Apache-2.0`.
+///
+/// When it's the `only` license, the plain `LICENSE` file names are accepted too.
+fn license_file_names(id: &str, only: bool) -> Vec<String> {
+    // The common short form is the identifier without the version, e.g. `MPL` for `MPL-2.0`.
+    let short = id.split('-').next().unwrap_or(id).to_uppercase();
+    let mut stems = vec![format!("LICENSE-{short}")];
+    if short != id.to_uppercase() {
+        stems.push(format!("LICENSE-{id}"));
+    }
+    if only {
+        stems.push("LICENSE".into());
+        stems.push("COPYING".into());
+    }
+    stems
+        .into_iter()
+        .flat_map(|stem| ["", ".md", ".txt"].map(|ext| format!("{stem}{ext}")))
+        .collect()
+}
+
+/// Returns the paths of Rust source files which don't match `header_regex`.
+fn scan(session: &Session, header_regex: &str) -> Result<Vec<String>> {
+    let header_re = RegexBuilder::new(header_regex)
+        .multi_line(true)
+        .build()
+        .context("failed to build copyright header regex")?;
+
+    let h = HEADER;
+    eprintln!("    {h}Scanning{h:#} Rust source files for copyright headers");
+
+    let mut missing = Vec::new();
+    for file in files::collect(session.root_dir(), &file_globs(session))? {
+        let path = session.root_dir().join(&file);
+        let contents =
+            fs::read(&path).context(format!("failed to read file '{}'", path.display()))?;
+        if !header_re.is_match(&contents) {
+            missing.push(format!("./{}", file.to_string_lossy().replace('\\', "/")));
+        }
+    }
+
+    Ok(missing)
+}
+
+/// Returns the paths of Rust source files which don't match `header_regex`, using ripgrep.
+fn scan_ripgrep(session: &mut Session, strict: bool, header_regex: &str) -> Result<Vec<String>> {
     let ripgrep = if strict {
         let tools_cfg = session.config().tools();
         let cargo_ver_req = tools_cfg.rust().clone();
@@ -33,15 +196,8 @@ pub fn run(session: &mut Session, strict: bool) -> Result<()> {
         toolset.get::<Ripgrep>(&deps, None)?
     };
 
-    let project = session.config().project();
-    let header_regex = header_regex(project.name(), project.license());
-
     let mut cmd = ripgrep.cmd();
-    cmd.arg(header_regex)
-        .arg("--files-without-match")
-        .arg("--multiline")
-        .args(["-g", "*.rs"])
-        .arg(".");
+    cmd.args(ripgrep_args(session, header_regex));
 
     ui::print_cmd(&cmd);
 
@@ -54,22 +210,14 @@ pub fn run(session: &mut Session, strict: bool) -> Result<()> {
         output.status
     );
 
-    if !output.stdout.is_empty() {
-        print_missing(
-            project.name(),
-            project.license(),
-            String::from_utf8(output.stdout).unwrap(),
-        );
-        bail!("failed copyright header verification");
-    }
-
-    let h = HEADER;
-    eprintln!("    {h}Verified{h:#} all source files have correct copyright headers.");
+    let stdout = String::from_utf8(output.stdout).context("ripgrep output not valid UTF-8")?;
+    let mut missing = stdout.lines().map(String::from).collect::<Vec<_>>();
+    missing.sort();
 
-    Ok(())
+    Ok(missing)
 }
 
-fn header_regex(name: &str, license: &str) -> String {
+pub fn header_regex(name: &str, license: &str) -> String {
     let name = regex::escape(name);
     let license = regex::escape(license);
 
@@ -82,12 +230,39 @@ fn header_regex(name: &str, license: &str) -> String {
     re
 }
 
-fn print_missing(name: &str, license: &str, msg: String) {
+/// Returns the ripgrep arguments that list the files to check which don't match `header_regex`.
+pub fn ripgrep_args(session: &Session, header_regex: &str) -> Vec<String> {
+    let mut args = vec![
+        header_regex.to_string(),
+        "--files-without-match".into(),
+        "--multiline".into(),
+        // A user's ripgrep config could change which files are checked.
+        "--no-config".into(),
+    ];
+    for glob in file_globs(session) {
+        args.push("-g".into());
+        args.push(glob);
+    }
+    args.push(".".into());
+    args
+}
+
+/// Returns the ripgrep style globs of the files to check, which both scanners use,
+/// so that they check the exact same files.
+fn file_globs(session: &Session) -> Vec<String> {
+    let mut globs = vec!["*.rs".to_string()];
+    for glob in session.config().copyright().exclude() {
+        globs.push(format!("!{glob}"));
+    }
+    globs
+}
+
+fn print_missing(name: &str, license: &str, missing: &[String]) {
     let (e, l, n) = (ERROR, LITERAL, NOTE);
     let year = UtcDateTime::now().year();
 
     eprintln!("{e}The following files lack the correct copyright header:{e:#}");
-    eprintln!("{l}{msg}{l:#}");
+    eprintln!("{l}{}{l:#}", missing.join("\n"));
     eprintln!("{n}Please add the following header:{n:#}\n");
     eprintln!("// Copyright {year} the {name} Authors");
     eprintln!("// SPDX-License-Identifier: {license}");
@@ -0,0 +1,147 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+
+use crate::cmd::clippy::derive_version;
+use crate::session::Session;
+use crate::tools::BinCtx;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::clippy::{Clippy, ClippyDeps};
+use crate::tools::rustup;
+use crate::ui;
+use crate::ui::style::{ERROR, GOOD, HEADER, TABLE_HEADER};
+
+/// The outcome of checking a single target.
+struct TargetResult {
+    target: String,
+    check: bool,
+    clippy: bool,
+}
+
+/// Checks the workspace for each of the cross-compilation `targets` with Cargo and Clippy.
+///
+/// Without any `targets`, the ones configured in `prep.toml` are checked.
+/// Missing targets are added to the locked Rust toolchain with Rustup.
+///
+/// Every target is checked before reporting, to show every breakage at once.
+pub fn run(session: &mut Session, targets: &[String]) -> Result<()> {
+    let targets = if targets.is_empty() {
+        session.config().cross().targets().to_vec()
+    } else {
+        targets.to_vec()
+    };
+    if targets.is_empty() {
+        bail!(
+            "no cross-compilation targets configured, \
+            add them to `[cross] targets` in `prep.toml` or use `--target`"
+        );
+    }
+
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let cargo_ver_req = tools_cfg.rust().clone();
+    let rust_components = tools_cfg.rust_components(&["clippy"]);
+    let mut lints = vec!["-D".to_string(), "warnings".to_string()];
+    lints.extend(session.config().clippy().lint_args());
+    let toolset = session.toolset();
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), rust_components.clone());
+    let cargo = toolset.get::<Cargo>(&cargo_deps, &cargo_ver_req)?;
+    let ver_req = derive_version(&cargo_ver_req)?;
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), rust_components);
+    let deps = ClippyDeps::new(cargo_deps, cargo_ver_req);
+    let clippy = toolset.get::<Clippy>(&deps, &ver_req)?;
+
+    rustup::add_targets(toolset, Some(&rustup_ver_req), &cargo, &targets)?;
+
+    let h = HEADER;
+    let mut results = Vec::new();
+    for target in targets {
+        eprintln!("    {h}Checking{h:#} target '{target}'");
+        let check = run_target(&cargo, &["check"], &target, &[], "cargo check")?;
+        let clippy = run_target(&clippy, &[], &target, &lints, "cargo clippy")?;
+        results.push(TargetResult {
+            target,
+            check,
+            clippy,
+        });
+    }
+
+    print_matrix(&results);
+
+    let failed = results
+        .iter()
+        .filter(|r| !r.check || !r.clippy)
+        .map(|r| r.target.as_str())
+        .collect::<Vec<_>>();
+    ensure!(
+        failed.is_empty(),
+        "cross-compilation checks failed for targets: {}",
+        failed.join(", ")
+    );
+
+    Ok(())
+}
+
+/// Runs the Cargo `subcommand` of `binctx` for the `target`, followed by the `lints`.
+///
+/// Returns whether it succeeded, with the failure already reported.
+fn run_target(
+    binctx: &BinCtx,
+    subcommand: &[&str],
+    target: &str,
+    lints: &[String],
+    name: &str,
+) -> Result<bool> {
+    let mut cmd = binctx.cmd();
+    cmd.args(subcommand)
+        .args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .arg("--workspace")
+        .args(["--target", target]);
+    if !lints.is_empty() {
+        cmd.arg("--").args(lints);
+    }
+
+    ui::print_cmd(&cmd);
+
+    let status = ui::capture::status(&mut cmd).context(format!("failed to run {name}"))?;
+    if !status.success() {
+        ui::print_err(&format!("{name} failed for target '{target}': {status}"));
+    }
+    Ok(status.success())
+}
+
+/// Prints an aligned pass/fail matrix of the targets and checks.
+fn print_matrix(results: &[TargetResult]) {
+    let tlen = results
+        .iter()
+        .map(|r| r.target.len())
+        .chain(["Target".len()])
+        .max()
+        .unwrap_or_default();
+
+    let (h, g, e) = (TABLE_HEADER, GOOD, ERROR);
+    eprintln!();
+    eprintln!(
+        "{h}Target{h:#}{}  {h}Check{h:#}   {h}Clippy{h:#}",
+        " ".repeat(tlen - "Target".len())
+    );
+    for result in results {
+        let status = |passed| {
+            if passed {
+                format!("{g}passed{g:#}")
+            } else {
+                format!("{e}failed{e:#}")
+            }
+        };
+        eprintln!(
+            "{}{}  {}  {}",
+            result.target,
+            " ".repeat(tlen - result.target.len()),
+            status(result.check),
+            status(result.clippy)
+        );
+    }
+}
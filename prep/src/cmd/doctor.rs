@@ -0,0 +1,227 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::io::ErrorKind;
+use std::{env, fs};
+
+use anstream::eprintln;
+use anyhow::{Result, bail};
+
+use crate::session::Session;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::curl::{self, Curl};
+use crate::tools::git::Git;
+use crate::tools::rustup::Rustup;
+use crate::ui;
+use crate::ui::style::{ERROR, GOOD, HEADER, HELP, WARN};
+
+/// The hosts that Prep downloads tools and metadata from.
+const HOSTS: [(&str, &str); 2] = [
+    ("crates.io", "https://index.crates.io/config.json"),
+    ("GitHub", "https://github.com"),
+];
+
+/// Environment variables which override the toolchain selection of Prep.
+const CONFLICTING_VARS: [(&str, &str); 3] = [
+    (
+        "RUSTUP_TOOLCHAIN",
+        "overrides the Rust toolchain of unlocked commands",
+    ),
+    (
+        "CARGO",
+        "makes Cargo subcommands use a specific Cargo binary",
+    ),
+    ("RUSTC", "makes Cargo use a specific compiler binary"),
+];
+
+/// Result of a single diagnosis.
+enum Diagnosis {
+    /// Everything is fine, with the details of what was found.
+    Ok(String),
+    /// Something may cause problems, with the details and a remediation.
+    Warn(String, String),
+    /// Something will cause problems, with the details and a remediation.
+    Fail(String, String),
+}
+
+/// Diagnose the environment for common problems that would make Prep commands fail.
+///
+/// Every problem is reported with a suggestion on how to fix it.
+pub fn run(session: &mut Session) -> Result<()> {
+    let diagnoses = [
+        ("rustup", rustup(session)),
+        ("toolchain", toolchain(session)),
+        ("tools dir", tools_dir(session)),
+        ("git", git(session)),
+        ("env vars", env_vars()),
+    ]
+    .into_iter()
+    .chain(HOSTS.map(|(name, url)| (name, network(session, url))));
+
+    let (g, w, e, h) = (GOOD, WARN, ERROR, HELP);
+    let mut failed = 0;
+    for (name, diagnosis) in diagnoses {
+        match diagnosis {
+            Diagnosis::Ok(details) => eprintln!("          {g}Ok{g:#} {name}: {details}"),
+            Diagnosis::Warn(details, remedy) => {
+                eprintln!("     {w}Warning{w:#} {name}: {details}");
+                eprintln!("             {h}help:{h:#} {remedy}");
+            }
+            Diagnosis::Fail(details, remedy) => {
+                eprintln!("      {e}Failed{e:#} {name}: {details}");
+                eprintln!("             {h}help:{h:#} {remedy}");
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("found {failed} problems with the environment");
+    }
+
+    let h = HEADER;
+    eprintln!("    {h}Verified{h:#} the environment is ready for Prep");
+    Ok(())
+}
+
+/// Checks that rustup is installed and satisfies the configured version requirement.
+fn rustup(session: &mut Session) -> Diagnosis {
+    let ver_req = session.config().tools().rustup().clone();
+    let toolset = session.toolset();
+    let version = toolset
+        .get::<Rustup>(&(), None)
+        .and_then(|rustup| toolset.version::<Rustup>(&rustup));
+    match version {
+        Ok(Some(version)) if ver_req.matches(&version) => Diagnosis::Ok(version.to_string()),
+        Ok(Some(version)) => Diagnosis::Warn(
+            format!("{version} doesn't satisfy the required {ver_req}"),
+            "run `rustup self update`, or change `rustup` in the [tools] section of prep.toml"
+                .into(),
+        ),
+        Ok(None) => Diagnosis::Fail(
+            "not found".into(),
+            "install rustup from https://rustup.rs/".into(),
+        ),
+        Err(e) => Diagnosis::Fail(
+            format!("{e:#}"),
+            "reinstall rustup from https://rustup.rs/".into(),
+        ),
+    }
+}
+
+/// Checks that the default Rust toolchain works.
+fn toolchain(session: &mut Session) -> Diagnosis {
+    let toolset = session.toolset();
+    let version = toolset
+        .get::<Cargo>(&CargoDeps::default(), None)
+        .and_then(|cargo| toolset.version::<Cargo>(&cargo));
+    match version {
+        Ok(Some(version)) => Diagnosis::Ok(format!("Cargo {version}")),
+        Ok(None) => Diagnosis::Fail(
+            "Cargo not found".into(),
+            "install a default toolchain with `rustup default stable`".into(),
+        ),
+        Err(e) => Diagnosis::Fail(
+            format!("{e:#}"),
+            "repair the default toolchain with `rustup toolchain install stable --force` \
+            and `rustup default stable`"
+                .into(),
+        ),
+    }
+}
+
+/// Checks that the tools directory can be written to.
+fn tools_dir(session: &mut Session) -> Diagnosis {
+    let toolset = session.toolset();
+    let dir = toolset.tools_dir().to_path_buf();
+    let remedy = format!(
+        "make '{}' writable, or set `dir` in the [tools] section of prep.toml",
+        dir.display()
+    );
+    if let Err(e) = toolset.ensure_tools_dir() {
+        return Diagnosis::Fail(format!("{e:#}"), remedy);
+    }
+    let probe = dir.join(".doctor-probe");
+    match fs::write(&probe, "").and_then(|()| fs::remove_file(&probe)) {
+        Ok(()) => Diagnosis::Ok(format!("'{}' is writable", dir.display())),
+        Err(e) => Diagnosis::Fail(format!("'{}' is not writable: {e}", dir.display()), remedy),
+    }
+}
+
+/// Checks that Git is installed.
+fn git(session: &mut Session) -> Diagnosis {
+    let toolset = session.toolset();
+    let version = toolset
+        .get::<Git>(&(), None)
+        .and_then(|git| toolset.version::<Git>(&git));
+    match version {
+        Ok(Some(version)) => Diagnosis::Ok(version.to_string()),
+        Ok(None) => Diagnosis::Fail(
+            "not found".into(),
+            "install Git from https://git-scm.com/".into(),
+        ),
+        Err(e) => Diagnosis::Fail(
+            format!("{e:#}"),
+            "reinstall Git from https://git-scm.com/".into(),
+        ),
+    }
+}
+
+/// Checks that no environment variables override the toolchain selection.
+fn env_vars() -> Diagnosis {
+    let set = CONFLICTING_VARS
+        .iter()
+        .filter(|(var, _)| env::var_os(var).is_some())
+        .collect::<Vec<_>>();
+    if set.is_empty() {
+        return Diagnosis::Ok("no toolchain overrides".into());
+    }
+    let details = set
+        .iter()
+        .map(|(var, effect)| format!("{var} {effect}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let vars = set.iter().map(|(var, _)| *var).collect::<Vec<_>>();
+    Diagnosis::Warn(
+        details,
+        format!("unset {} unless it is intentional", vars.join(" and ")),
+    )
+}
+
+/// Checks that `url` can be reached with the network configuration.
+fn network(session: &mut Session, url: &str) -> Diagnosis {
+    let toolset = session.toolset();
+    let curl = match toolset.get::<Curl>(&(), None) {
+        Ok(curl) => curl,
+        Err(e) => {
+            return Diagnosis::Fail(
+                format!("{e:#}"),
+                "install curl from https://curl.se/".into(),
+            );
+        }
+    };
+    let mut cmd = curl::download_cmd(&curl, toolset.network(), url);
+
+    ui::print_cmd(&cmd);
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => Diagnosis::Ok(format!("reached {url}")),
+        Ok(output) => Diagnosis::Fail(
+            format!(
+                "failed to reach {url}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            "check the connection, or configure `proxy` or `mirrors` \
+            in the [network] section of prep.toml"
+                .into(),
+        ),
+        Err(e) if e.kind() == ErrorKind::NotFound => Diagnosis::Fail(
+            "curl not found".into(),
+            "install curl from https://curl.se/".into(),
+        ),
+        Err(e) => Diagnosis::Fail(
+            format!("failed to run curl: {e}"),
+            "reinstall curl from https://curl.se/".into(),
+        ),
+    }
+}
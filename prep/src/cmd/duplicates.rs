@@ -0,0 +1,88 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+
+use anstream::eprintln;
+use anyhow::{Result, bail};
+use semver::Version;
+
+use crate::metadata;
+use crate::session::Session;
+use crate::ui::style::{ERROR, HEADER};
+
+/// Reports the dependencies which are in the dependency graph in multiple versions.
+///
+/// Every duplicate adds build time and binary size, so they are failures
+/// unless the package is in the `allow` list of `[duplicates]` in `prep.toml`.
+///
+/// Every version is reported with the packages which depend on it, to help unify them.
+pub fn run(session: &mut Session) -> Result<()> {
+    let allow = session.config().duplicates().allow().to_vec();
+
+    let metadata = metadata::fetch_graph(session)?;
+
+    // Dependents of every package version, keyed by the package name.
+    let mut versions: BTreeMap<&str, BTreeMap<&Version, Vec<String>>> = BTreeMap::new();
+    for package in &metadata.packages {
+        if metadata.workspace_members.contains(&package.id) {
+            continue;
+        }
+        versions
+            .entry(&package.name)
+            .or_default()
+            .insert(&package.version, Vec::new());
+    }
+    if let Some(resolve) = &metadata.resolve {
+        for node in &resolve.nodes {
+            let dependent = &metadata[&node.id];
+            for dep_id in &node.dependencies {
+                let dep = &metadata[dep_id];
+                if let Some(dependents) = versions
+                    .get_mut(dep.name.as_str())
+                    .and_then(|v| v.get_mut(&dep.version))
+                {
+                    dependents.push(format!("{} {}", dependent.name, dependent.version));
+                }
+            }
+        }
+    }
+    versions.retain(|_, v| v.len() > 1);
+
+    let h = HEADER;
+    if versions.is_empty() {
+        eprintln!("    {h}Verified{h:#} there are no duplicate dependencies");
+        return Ok(());
+    }
+
+    let e = ERROR;
+    let mut denied = Vec::new();
+    for (name, versions) in &versions {
+        let allowed = allow.iter().any(|a| a == name);
+        if allowed {
+            eprintln!("{name}");
+        } else {
+            eprintln!("{e}{name}{e:#}");
+            denied.push(*name);
+        }
+        for (version, dependents) in versions {
+            let mut dependents = dependents.clone();
+            dependents.sort();
+            dependents.dedup();
+            eprintln!("  {version} <- {}", dependents.join(", "));
+        }
+    }
+
+    if !denied.is_empty() {
+        bail!(
+            "dependencies with multiple versions that are not allowed: {}",
+            denied.join(", ")
+        );
+    }
+
+    eprintln!(
+        "    {h}Verified{h:#} all {} duplicate dependencies are allowed",
+        versions.len()
+    );
+    Ok(())
+}
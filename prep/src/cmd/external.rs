@@ -0,0 +1,120 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::process::{Command, ExitStatus};
+
+use anyhow::{Context, Result, bail};
+
+use crate::cmd::tools::list;
+use crate::config;
+use crate::host;
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::rustup::Rustup;
+use crate::ui;
+
+/// The prefix of external subcommand executables, e.g. `prep-foo` for `prep foo`.
+const PREFIX: &str = "prep-";
+
+/// A failed external subcommand, whose exit code Prep exits with too.
+#[derive(Debug)]
+pub struct Failed {
+    name: String,
+    status: ExitStatus,
+}
+
+impl Failed {
+    /// Returns the exit code of the subcommand, or 1 if it was terminated by a signal.
+    pub fn code(&self) -> i32 {
+        self.status.code().unwrap_or(1)
+    }
+}
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PREFIX}{} failed: {}", self.name, self.status)
+    }
+}
+
+impl std::error::Error for Failed {}
+
+/// Run the external subcommand `args[0]` with the `prep-<name>` executable found on `PATH`,
+/// passing it the rest of the `args`.
+///
+/// The executable runs in the current directory and learns about the project
+/// from the environment variables:
+/// - `PREP` with the path of the Prep executable, so it can run Prep commands.
+/// - `PREP_ROOT` with the project root directory.
+/// - `PREP_CONFIG` with the path of `prep.toml`, which may not exist.
+/// - `PREP_TOOLS_DIR` with the directory of the managed tools.
+/// - `PREP_RUST_TOOLCHAIN` with the locked Rust toolchain, e.g. `1.93`.
+/// - `PREP_TOOL_<NAME>` with the path of every installed managed tool that satisfies its
+///   configured version requirement, e.g. `PREP_TOOL_CARGO_NEXTEST`.
+///   Tools are not installed for this, so missing ones are omitted.
+///
+/// If there is no such executable, the nearest of the `builtins` commands is suggested.
+/// If the executable fails, the error is [`Failed`] with its exit code.
+pub fn run(session: &mut Session, args: &[OsString], builtins: &[&str]) -> Result<()> {
+    let (name, args) = args
+        .split_first()
+        .context("external subcommand name is missing")?;
+    let name = name.to_string_lossy();
+    let Some(path) = host::find_executable(&format!("{PREFIX}{name}")) else {
+        let mut msg = format!(
+            "no such command: `{name}`, and no `{PREFIX}{name}` executable was found on PATH"
+        );
+        match config::nearest(&name, builtins) {
+            Some(nearest) => msg.push_str(&format!("\n\ndid you mean `{nearest}`?")),
+            None => msg.push_str("\n\nRun `prep help` to see the built-in commands."),
+        }
+        bail!(msg);
+    };
+
+    let mut cmd = Command::new(&path);
+    session.toolset().environment().apply(&mut cmd);
+    cmd.args(args);
+
+    // The project variables are left out of the printed command, as they would drown it.
+    ui::print_cmd(&cmd);
+
+    if let Ok(exe) = env::current_exe() {
+        cmd.env("PREP", exe);
+    }
+    cmd.env("PREP_ROOT", session.root_dir())
+        .env("PREP_CONFIG", session.config_path())
+        .env("PREP_TOOLS_DIR", session.toolset().tools_dir());
+    for (tool, ver_req, _) in list::tools(session) {
+        let Some(ver_req) = ver_req else {
+            continue;
+        };
+        if tool == "rust" {
+            let toolchain = ver_req.to_string().trim_start_matches('=').to_string();
+            cmd.env("PREP_RUST_TOOLCHAIN", toolchain);
+            continue;
+        }
+        // Rustup is never in the tool manifest.
+        if tool == Rustup::NAME {
+            continue;
+        }
+        let toolset = session.toolset();
+        if let Some((_, tool_path)) = toolset.manifest().get(tool, &ver_req) {
+            let var = format!("PREP_TOOL_{}", tool.to_uppercase().replace('-', "_"));
+            cmd.env(var, toolset.tools_dir().join(tool_path));
+        }
+    }
+
+    let status = cmd
+        .status()
+        .context(format!("failed to run '{}'", path.display()))?;
+    if !status.success() {
+        return Err(Failed {
+            name: name.into_owned(),
+            status,
+        }
+        .into());
+    }
+    Ok(())
+}
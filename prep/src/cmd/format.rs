@@ -1,45 +1,298 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use anstream::eprintln;
 use anyhow::{Context, Result, ensure};
-use semver::VersionReq;
+use regex::Regex;
 
+use crate::cmd::Packages;
 use crate::session::Session;
+use crate::tools::BinCtx;
 use crate::tools::cargo::CargoDeps;
+use crate::tools::git::Git;
 use crate::tools::rustfmt::{Rustfmt, RustfmtDeps};
+use crate::tools::taplo::{Taplo, TaploDeps};
+use crate::toolset::ToolVersionReq;
 use crate::ui;
+use crate::ui::style::HEADER;
+use crate::{github, metadata};
 
 /// Format the workspace.
 ///
 /// In `strict` mode Cargo version is locked.
-pub fn run(session: &mut Session, strict: bool, check: bool) -> Result<()> {
-    let rust_components = vec!["rustfmt".into()];
+///
+/// Set `toml` to `true` to also format TOML files with Taplo.
+///
+/// Only the Rust files of the selected `packages` are formatted, TOML files are not affected.
+///
+/// When `since` is provided, only the files changed since that Git revision are formatted.
+///
+/// When checking in GitHub Actions, the unformatted Rust files are also emitted as annotations.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    check: bool,
+    toml: bool,
+    packages: &Packages,
+    since: Option<&str>,
+) -> Result<()> {
+    run_rustfmt(session, strict, check, packages, since)?;
+    if toml {
+        run_taplo(session, strict, check, since)?;
+    }
+    Ok(())
+}
+
+/// Format the Rust files of the workspace with rustfmt.
+fn run_rustfmt(
+    session: &mut Session,
+    strict: bool,
+    check: bool,
+    packages: &Packages,
+    since: Option<&str>,
+) -> Result<()> {
     let rustfmt = if strict {
         let tools_cfg = session.config().tools();
         let rustup_ver_req = tools_cfg.rustup().clone();
         let cargo_ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&["rustfmt"]);
         let toolset = session.toolset();
         let cargo_deps = CargoDeps::new(rustup_ver_req, rust_components);
         let deps = RustfmtDeps::new(cargo_deps, cargo_ver_req);
-        let ver_req = VersionReq::parse("=1.8.0-stable")?; // TODO: Replace with a custom 'Any' (NOT None!)
-        toolset.get::<Rustfmt>(&deps, &ver_req)?
+        // Rustfmt is versioned independently, so use whichever ships with the locked toolchain.
+        toolset.get::<Rustfmt>(&deps, ToolVersionReq::Any)?
     } else {
         let toolset = session.toolset();
-        let cargo_deps = CargoDeps::new(None, rust_components);
+        let cargo_deps = CargoDeps::new(None, vec!["rustfmt".into()]);
         let deps = RustfmtDeps::new(cargo_deps, None);
         toolset.get::<Rustfmt>(&deps, None)?
     };
 
+    if let Some(since) = since {
+        return run_rustfmt_since(session, &rustfmt, check, packages, since);
+    }
+
+    let selected = if packages.is_all() {
+        None
+    } else {
+        // cargo fmt doesn't support --exclude, so we list the selected packages explicitly.
+        let selected = metadata::fetch(session)?
+            .workspace_packages()
+            .into_iter()
+            .map(|package| package.name.to_string())
+            .filter(|name| packages.contains(name))
+            .collect::<Vec<_>>();
+        ensure!(!selected.is_empty(), "no workspace packages selected");
+        Some(selected)
+    };
+    let mut cmd = rustfmt_cmd(&rustfmt, selected.as_deref(), check);
+
+    ui::print_cmd(&cmd);
+
+    let status = rustfmt_status(&mut cmd, rustfmt.working_dir(), check)
+        .context("failed to run cargo fmt")?;
+    ensure!(status.success(), "cargo fmt failed: {status}");
+
+    Ok(())
+}
+
+/// Returns the `cargo fmt` command for the `selected` packages, or all of them if `None`.
+pub fn rustfmt_cmd(rustfmt: &BinCtx, selected: Option<&[String]>, check: bool) -> Command {
     let mut cmd = rustfmt.cmd();
-    cmd.arg("--all");
+    match selected {
+        Some(selected) => {
+            for name in selected {
+                cmd.args(["--package", name]);
+            }
+        }
+        None => {
+            cmd.arg("--all");
+        }
+    }
     if check {
         cmd.arg("--check");
     }
+    cmd
+}
+
+/// Format only the Rust files changed since the `since` Git revision.
+///
+/// Files are passed directly to rustfmt, grouped by the edition of the package they belong to.
+/// Files that don't belong to any workspace package are skipped, just like with `cargo fmt`.
+fn run_rustfmt_since(
+    session: &mut Session,
+    rustfmt: &BinCtx,
+    check: bool,
+    packages: &Packages,
+    since: &str,
+) -> Result<()> {
+    let files = changed_files(session, since, "*.rs")?;
+    if files.is_empty() {
+        let h = HEADER;
+        eprintln!("     {h}Skipped{h:#} rustfmt as no Rust files have changed since {since}");
+        return Ok(());
+    }
+
+    // Package directories with their editions.
+    let metadata = metadata::fetch(session)?;
+    let dirs = metadata::package_dirs(&metadata)
+        .into_iter()
+        .filter(|(package, _)| packages.contains(&package.name))
+        .map(|(package, dir)| (dir, package.edition.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut editions: BTreeMap<&str, Vec<PathBuf>> = BTreeMap::new();
+    for file in files {
+        let path = rustfmt.working_dir().join(&file);
+        if let Some((_, edition)) = dirs.iter().find(|(dir, _)| path.starts_with(dir)) {
+            editions.entry(edition).or_default().push(file);
+        }
+    }
+
+    // Given that the toolchain is selected via the environment, we'll just use the binary name.
+    let rustfmt = BinCtx::new(
+        "rustfmt".into(),
+        rustfmt.working_dir().to_path_buf(),
+        rustfmt.environment().clone(),
+    );
+    for (edition, files) in editions {
+        let mut cmd = rustfmt.cmd();
+        cmd.args(["--edition", edition]);
+        if check {
+            cmd.arg("--check");
+        }
+        cmd.args(&files);
+
+        ui::print_cmd(&cmd);
+
+        let status = rustfmt_status(&mut cmd, rustfmt.working_dir(), check)
+            .context("failed to run rustfmt")?;
+        ensure!(status.success(), "rustfmt failed: {status}");
+    }
+
+    Ok(())
+}
+
+/// Runs the rustfmt command to completion.
+///
+/// When checking in GitHub Actions, each reported diff is also emitted as an annotation.
+fn rustfmt_status(cmd: &mut Command, working_dir: &Path, check: bool) -> io::Result<ExitStatus> {
+    if !check || !github::is_actions() {
+        return ui::capture::status(cmd);
+    }
+    let re = Regex::new(r"^Diff in (.+):(\d+):$").expect("rustfmt diff regex was incorrect");
+    ui::capture::status_lines(cmd, |line| {
+        ui::capture::eprint(&format!("{line}\n"));
+        let Some(captures) = re.captures(line) else {
+            return;
+        };
+        let location = github::Location {
+            file: Path::new(&captures[1]),
+            line: captures[2].parse().unwrap_or(1),
+            end_line: None,
+            col: None,
+            end_col: None,
+        };
+        github::annotate(
+            github::Level::Error,
+            working_dir,
+            &location,
+            Some("rustfmt"),
+            "File is not formatted correctly, run `prep format` to fix it.",
+        );
+    })
+}
+
+/// Format the TOML files of the workspace with Taplo.
+///
+/// In `strict` mode Taplo version is locked.
+fn run_taplo(session: &mut Session, strict: bool, check: bool, since: Option<&str>) -> Result<()> {
+    let taplo = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let cargo_ver_req = tools_cfg.rust().clone();
+        let ver_req = tools_cfg.taplo().clone();
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+        let deps = TaploDeps::new(cargo_deps, cargo_ver_req);
+        toolset.get::<Taplo>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(None, vec![]);
+        let deps = TaploDeps::new(cargo_deps, None);
+        toolset.get::<Taplo>(&deps, None)?
+    };
+
+    let files = match since {
+        Some(since) => {
+            let files = changed_files(session, since, "*.toml")?;
+            if files.is_empty() {
+                let h = HEADER;
+                eprintln!("     {h}Skipped{h:#} taplo as no TOML files have changed since {since}");
+                return Ok(());
+            }
+            files
+        }
+        None => Vec::new(),
+    };
+
+    let mut cmd = taplo_cmd(&taplo, check, &files);
 
     ui::print_cmd(&cmd);
 
-    let status = cmd.status().context("failed to run cargo fmt")?;
-    ensure!(status.success(), "cargo fmt failed: {status}");
+    let status = ui::capture::status(&mut cmd).context("failed to run taplo")?;
+    ensure!(status.success(), "taplo format failed: {status}");
 
     Ok(())
 }
+
+/// Returns the `taplo format` command for the `files`, or all the TOML files if empty.
+pub fn taplo_cmd(taplo: &BinCtx, check: bool, files: &[PathBuf]) -> Command {
+    let mut cmd = taplo.cmd();
+    cmd.arg("format");
+    if check {
+        cmd.arg("--check");
+    }
+    cmd.args(files);
+    cmd
+}
+
+/// Returns the files matching `pathspec` that have changed since the `since` Git revision.
+///
+/// This includes uncommitted and untracked files, but not deleted files.
+/// The paths are relative to the toolset working directory.
+fn changed_files(session: &mut Session, since: &str, pathspec: &str) -> Result<Vec<PathBuf>> {
+    let git = session.toolset().get::<Git>(&(), None)?;
+
+    let mut diff_cmd = git.cmd();
+    diff_cmd
+        .args(["diff", "--name-only", "--relative", "--diff-filter=d"])
+        .arg(since)
+        .args(["--", pathspec]);
+    let mut untracked_cmd = git.cmd();
+    untracked_cmd.args(["ls-files", "--others", "--exclude-standard", "--", pathspec]);
+
+    let mut files = Vec::new();
+    for mut cmd in [diff_cmd, untracked_cmd] {
+        ui::print_cmd(&cmd);
+
+        let output = cmd.output().context("failed to run git")?;
+        ensure!(
+            output.status.success(),
+            "git failed: {}\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8(output.stdout).context("git output not valid UTF-8")?;
+        files.extend(stdout.lines().map(PathBuf::from));
+    }
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
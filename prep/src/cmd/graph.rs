@@ -0,0 +1,223 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use cargo_metadata::{DependencyKind, Metadata, PackageId};
+use clap::ValueEnum;
+
+use crate::metadata;
+use crate::session::Session;
+use crate::ui::style::HEADER;
+
+/// Dependency graph format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    Dot,
+    /// Mermaid flowchart.
+    Mermaid,
+}
+
+/// Dependency graph edge selection.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Edges {
+    /// Only include the edges between workspace packages.
+    pub workspace_only: bool,
+    /// Also include dev-dependency edges.
+    pub dev: bool,
+    /// Also include build-dependency edges.
+    pub build: bool,
+}
+
+/// Kind of a dependency graph edge, in increasing order of precedence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum EdgeKind {
+    Dev,
+    Build,
+    Normal,
+}
+
+/// A dependency graph edge from the dependent to the dependency.
+type Edge<'a> = (&'a PackageId, &'a PackageId, EdgeKind);
+
+/// A package in the dependency graph.
+struct Node {
+    label: String,
+    workspace: bool,
+    duplicate: bool,
+}
+
+/// Renders the dependency graph of the workspace packages, starting from the workspace packages.
+///
+/// Normal dependencies are always included, dev and build dependencies only if selected in `edges`.
+/// Packages which are in the graph in multiple versions are highlighted.
+///
+/// The graph is written to `output`, or printed to stdout if there is none.
+pub fn run(
+    session: &mut Session,
+    format: GraphFormat,
+    edges: Edges,
+    output: Option<&Path>,
+) -> Result<()> {
+    let metadata = metadata::fetch_graph(session)?;
+
+    let (nodes, graph_edges) = collect(&metadata, edges);
+    let graph = match format {
+        GraphFormat::Dot => dot(&nodes, &graph_edges),
+        GraphFormat::Mermaid => mermaid(&nodes, &graph_edges),
+    };
+
+    let Some(output) = output else {
+        print!("{graph}");
+        return Ok(());
+    };
+    fs::write(output, &graph).context(format!("failed to write graph '{}'", output.display()))?;
+
+    let h = HEADER;
+    eprintln!(
+        "   {h}Generated{h:#} dependency graph of {} packages and {} edges '{}'",
+        nodes.len(),
+        graph_edges.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Returns the packages reachable from the workspace packages through the selected `edges`,
+/// along with the edges between them.
+fn collect(metadata: &Metadata, edges: Edges) -> (BTreeMap<&PackageId, Node>, BTreeSet<Edge<'_>>) {
+    let workspace = metadata.workspace_members.iter().collect::<BTreeSet<_>>();
+
+    let mut graph_edges = BTreeSet::new();
+    let mut reached = workspace.clone();
+    let mut queue = workspace.iter().copied().collect::<Vec<_>>();
+    let resolve_nodes = metadata
+        .resolve
+        .as_ref()
+        .map(|r| {
+            r.nodes
+                .iter()
+                .map(|n| (&n.id, n))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    while let Some(id) = queue.pop() {
+        let Some(node) = resolve_nodes.get(id) else {
+            continue;
+        };
+        for dep in &node.deps {
+            if edges.workspace_only && !workspace.contains(&dep.pkg) {
+                continue;
+            }
+            let kind = dep
+                .dep_kinds
+                .iter()
+                .filter_map(|info| match info.kind {
+                    DependencyKind::Normal => Some(EdgeKind::Normal),
+                    DependencyKind::Build if edges.build => Some(EdgeKind::Build),
+                    DependencyKind::Development if edges.dev => Some(EdgeKind::Dev),
+                    _ => None,
+                })
+                .max();
+            let Some(kind) = kind else {
+                continue;
+            };
+            graph_edges.insert((id, &dep.pkg, kind));
+            if reached.insert(&dep.pkg) {
+                queue.push(&dep.pkg);
+            }
+        }
+    }
+
+    let mut versions: BTreeMap<&str, usize> = BTreeMap::new();
+    for id in &reached {
+        *versions.entry(metadata[*id].name.as_str()).or_default() += 1;
+    }
+    let nodes = reached
+        .into_iter()
+        .map(|id| {
+            let package = &metadata[id];
+            let node = Node {
+                label: format!("{} {}", package.name, package.version),
+                workspace: workspace.contains(id),
+                duplicate: versions[package.name.as_str()] > 1,
+            };
+            (id, node)
+        })
+        .collect();
+
+    (nodes, graph_edges)
+}
+
+/// Returns the graph in Graphviz DOT format.
+fn dot(nodes: &BTreeMap<&PackageId, Node>, edges: &BTreeSet<Edge<'_>>) -> String {
+    let mut dot = String::from("digraph dependencies {\n    node [shape=box];\n");
+    for node in nodes.values() {
+        let mut attrs = vec![format!("label=\"{}\"", node.label)];
+        if node.workspace {
+            attrs.push("style=bold".into());
+        }
+        if node.duplicate {
+            attrs.push("color=red".into());
+        }
+        dot.push_str(&format!("    \"{}\" [{}];\n", node.label, attrs.join(", ")));
+    }
+    for (from, to, kind) in edges {
+        let style = match kind {
+            EdgeKind::Normal => "",
+            EdgeKind::Build => " [style=dotted]",
+            EdgeKind::Dev => " [style=dashed]",
+        };
+        dot.push_str(&format!(
+            "    \"{}\" -> \"{}\"{style};\n",
+            nodes[from].label, nodes[to].label
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Returns the graph as a Mermaid flowchart.
+fn mermaid(nodes: &BTreeMap<&PackageId, Node>, edges: &BTreeSet<Edge<'_>>) -> String {
+    // Mermaid node identifiers can't contain most punctuation, so they are numbered instead.
+    let ids = nodes
+        .keys()
+        .enumerate()
+        .map(|(i, id)| (*id, format!("n{i}")))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut mermaid = String::from("flowchart LR\n");
+    mermaid.push_str("    classDef workspace font-weight:bold\n");
+    mermaid.push_str("    classDef duplicate stroke:red,stroke-width:2px\n");
+    for (id, node) in nodes {
+        mermaid.push_str(&format!("    {}[\"{}\"]\n", ids[id], node.label));
+    }
+    for (from, to, kind) in edges {
+        let arrow = match kind {
+            EdgeKind::Normal => "-->",
+            EdgeKind::Build => "-. build .->",
+            EdgeKind::Dev => "-. dev .->",
+        };
+        mermaid.push_str(&format!("    {} {arrow} {}\n", ids[from], ids[to]));
+    }
+    let workspace = nodes.iter().filter(|(_, node)| node.workspace);
+    let duplicate = nodes.iter().filter(|(_, node)| node.duplicate);
+    for (class, members) in [
+        ("workspace", workspace.collect::<Vec<_>>()),
+        ("duplicate", duplicate.collect()),
+    ] {
+        if !members.is_empty() {
+            let members = members
+                .iter()
+                .map(|(id, _)| ids[*id].as_str())
+                .collect::<Vec<_>>();
+            mermaid.push_str(&format!("    class {} {class}\n", members.join(",")));
+        }
+    }
+    mermaid
+}
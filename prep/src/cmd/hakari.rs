@@ -0,0 +1,102 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::process::Command;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_hakari::{CargoHakari, CargoHakariDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// The cargo-hakari config file, relative to the project root.
+pub const CONFIG_FILE: &str = ".config/hakari.toml";
+
+/// The arguments of the verification commands, with the context of their failure.
+pub const VERIFY_ARGS: [(&[&str], Option<&str>); 3] = [
+    (
+        &["generate", "--diff"],
+        Some("the workspace-hack crate is out of date, run `prep hakari generate` to update it"),
+    ),
+    (
+        &["manage-deps", "--dry-run"],
+        Some(
+            "workspace packages are missing the workspace-hack dependency, \
+            run `prep hakari generate` to add it",
+        ),
+    ),
+    (&["verify"], None),
+];
+
+/// Regenerates the workspace-hack crate and its dependency lines in all workspace packages.
+pub fn generate(session: &mut Session) -> Result<()> {
+    let cargo_hakari = cargo_hakari(session)?;
+
+    run_hakari(&cargo_hakari, &["generate"])?;
+    run_hakari(&cargo_hakari, &["manage-deps", "--yes"])?;
+
+    let h = HEADER;
+    eprintln!("   {h}Generated{h:#} the workspace-hack crate");
+    Ok(())
+}
+
+/// Verifies that the workspace-hack crate is up to date and that it unifies all the features.
+///
+/// Nothing is written, so this is suitable for CI.
+pub fn verify(session: &mut Session) -> Result<()> {
+    let cargo_hakari = cargo_hakari(session)?;
+
+    for (args, context) in VERIFY_ARGS {
+        let result = run_hakari(&cargo_hakari, args);
+        match context {
+            Some(context) => result.context(context)?,
+            None => result?,
+        }
+    }
+
+    let h = HEADER;
+    eprintln!("    {h}Verified{h:#} the workspace-hack crate is up to date");
+    Ok(())
+}
+
+/// Runs cargo-hakari with the given `args`.
+fn run_hakari(cargo_hakari: &BinCtx, args: &[&str]) -> Result<()> {
+    let mut cmd = cmd(cargo_hakari, args);
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", CargoHakari::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", CargoHakari::NAME);
+    Ok(())
+}
+
+/// Returns the locked cargo-hakari, which uses the locked Rust toolchain for Cargo metadata.
+///
+/// The binary is run directly instead of as a Cargo subcommand, so the first argument must be `hakari`.
+pub fn cmd(cargo_hakari: &BinCtx, args: &[&str]) -> Command {
+    let mut cmd = cargo_hakari.cmd();
+    cmd.arg("hakari").args(args);
+    cmd
+}
+
+fn cargo_hakari(session: &mut Session) -> Result<BinCtx> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let rust_ver_req = tools_cfg.rust().clone();
+    let ver_req = tools_cfg.cargo_hakari().clone();
+    let toolset = session.toolset();
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), vec![]);
+    let cargo = toolset.get::<Cargo>(&cargo_deps, &rust_ver_req)?;
+    let toolchain = cargo::toolchain_vars(&cargo);
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let deps = CargoHakariDeps::new(cargo_deps, rust_ver_req);
+    let cargo_hakari = toolset.get::<CargoHakari>(&deps, &ver_req)?;
+    Ok(cargo_hakari.env(&toolchain))
+}
@@ -0,0 +1,139 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anstream::eprintln;
+use anyhow::{Context, Result};
+
+use crate::session::Session;
+use crate::tools::git::{self, Git};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Marks the Git hooks written by Prep, so that other hooks are never touched.
+const MARKER: &str = "# Installed by Prep, remove with `prep hooks uninstall`.";
+
+/// Installs the Git pre-commit and pre-push hooks which run the configured Prep commands.
+///
+/// Existing hooks not installed by Prep are skipped, unless `force` is set,
+/// in which case they are backed up with a `.bak` extension first.
+/// Hooks installed by Prep which no longer have any commands configured are removed.
+pub fn install(session: &mut Session, force: bool) -> Result<()> {
+    let hooks_dir = hooks_dir(session)?;
+    fs::create_dir_all(&hooks_dir).context(format!(
+        "failed to create Git hooks directory '{}'",
+        hooks_dir.display()
+    ))?;
+
+    let git_hooks_cfg = session.config().git_hooks();
+    let hooks = [
+        ("pre-commit", git_hooks_cfg.pre_commit()),
+        ("pre-push", git_hooks_cfg.pre_push()),
+    ];
+
+    let h = HEADER;
+    for (name, commands) in hooks {
+        let path = hooks_dir.join(name);
+        let exists = path.exists();
+        let foreign = exists && !installed_by_prep(&path)?;
+        if commands.is_empty() {
+            // The hook is no longer configured, so it must not keep running the old commands.
+            if exists && !foreign {
+                fs::remove_file(&path)
+                    .context(format!("failed to remove Git hook '{}'", path.display()))?;
+                eprintln!("     {h}Removed{h:#} Git hook '{}'", path.display());
+            }
+            continue;
+        }
+        if foreign {
+            if !force {
+                ui::print_warn(&format!(
+                    "Git hook '{}' already exists and was not installed by Prep, skipping it.\n\
+                    Use --force if you intended to overwrite it.",
+                    path.display()
+                ));
+                continue;
+            }
+            let backup = path.with_extension("bak");
+            fs::copy(&path, &backup).context(format!(
+                "failed to back up Git hook '{}' to '{}'",
+                path.display(),
+                backup.display()
+            ))?;
+            eprintln!("   {h}Backed up{h:#} Git hook '{}'", backup.display());
+        }
+
+        let mut script = format!("#!/bin/sh\n{MARKER}\nset -e\n");
+        for command in commands {
+            script.push_str(&format!("prep {command}\n"));
+        }
+        fs::write(&path, script)
+            .context(format!("failed to write Git hook '{}'", path.display()))?;
+        make_executable(&path)?;
+
+        eprintln!("   {h}Installed{h:#} Git hook '{}'", path.display());
+    }
+
+    Ok(())
+}
+
+/// Removes the Git hooks installed by Prep.
+pub fn uninstall(session: &mut Session) -> Result<()> {
+    let hooks_dir = hooks_dir(session)?;
+
+    let h = HEADER;
+    for name in ["pre-commit", "pre-push"] {
+        let path = hooks_dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        if !installed_by_prep(&path)? {
+            eprintln!(
+                "     {h}Skipped{h:#} Git hook '{}' not installed by Prep",
+                path.display()
+            );
+            continue;
+        }
+        fs::remove_file(&path)
+            .context(format!("failed to remove Git hook '{}'", path.display()))?;
+        eprintln!("     {h}Removed{h:#} Git hook '{}'", path.display());
+    }
+
+    Ok(())
+}
+
+/// Returns the Git hooks directory, respecting the `core.hooksPath` setting.
+fn hooks_dir(session: &mut Session) -> Result<PathBuf> {
+    let toolset = session.toolset();
+    let git = toolset.get::<Git>(&(), None)?;
+    git::hooks_dir(&git)
+}
+
+/// Returns `true` if the hook at `path` was installed by Prep.
+fn installed_by_prep(path: &Path) -> Result<bool> {
+    let script = fs::read_to_string(path)
+        .context(format!("failed to read Git hook '{}'", path.display()))?;
+    Ok(script.lines().any(|line| line == MARKER))
+}
+
+/// Makes the file at `path` executable by everyone who can read it.
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata =
+        fs::metadata(path).context(format!("failed to read metadata of '{}'", path.display()))?;
+    let mut permissions = metadata.permissions();
+    permissions.set_mode(permissions.mode() | ((permissions.mode() & 0o444) >> 2));
+    fs::set_permissions(path, permissions)
+        .context(format!("failed to make '{}' executable", path.display()))?;
+    Ok(())
+}
+
+/// Git for Windows runs hooks with its own shell, so no permissions are needed.
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -1,24 +1,409 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::Result;
+use std::fs;
 
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use semver::VersionReq;
+
+use crate::cmd::tools::outdated;
+use crate::config::{self, CiStep, Config};
+use crate::metadata;
 use crate::session::Session;
+use crate::toolchain_file::{self, ToolchainFile};
+use crate::tools::Tool;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::taplo::Taplo;
 use crate::ui;
+use crate::ui::prompt;
+use crate::ui::style::HEADER;
+
+/// The GitHub Actions workflow path, relative to the project root.
+const WORKFLOW_PATH: &str = ".github/workflows/ci.yml";
+
+/// The built-in CI steps offered by the wizard, and whether they are in the default pipeline.
+const CI_STEPS: [(&str, bool); 4] = [
+    ("copyright", true),
+    ("format", true),
+    ("clippy", true),
+    ("check", false),
+];
 
 /// Initialize the prep configuration
-pub fn run(session: &Session, force: bool) -> Result<()> {
-    if !force && session.config_path().exists() {
-        ui::print_err(
-            "Prep configuration already exists, aborting.\n\
+///
+/// Set `github` to instead generate a GitHub Actions workflow that runs `prep ci`.
+///
+/// Set `toolchain_file` to instead generate a `rust-toolchain.toml` with the configured Rust version.
+///
+/// Set `scaffold` to instead generate `rustfmt.toml`, `clippy.toml`, and `deny.toml`.
+pub fn run(
+    session: &mut Session,
+    force: bool,
+    github: bool,
+    toolchain_file: bool,
+    scaffold: bool,
+) -> Result<()> {
+    if github {
+        return write_workflow(session, force);
+    }
+    if toolchain_file {
+        return write_toolchain_file(session, force);
+    }
+    if scaffold {
+        return write_scaffold(session, force);
+    }
+
+    if session.config_path().exists() {
+        if !force {
+            ui::print_err(
+                "Prep configuration already exists, aborting.\n\
 			Use --force if you intended to overwrite the previous config.",
-        );
+            );
+            return Ok(());
+        }
+        if !prompt::confirm("Overwrite the existing Prep configuration?", true)? {
+            let h = HEADER;
+            eprintln!(
+                "     {h}Skipped{h:#} keeping Prep configuration '{}'",
+                session.config_path().display()
+            );
+            return Ok(());
+        }
+    }
+
+    // Only the project's own values are written out, not the user config, profile, or overrides.
+    let mut config = session.project_config()?;
+    detect_project(session, &mut config);
+
+    if prompt::will_ask() {
+        run_wizard(session, &mut config)?;
+    } else {
+        // Write down the Rust version even if it came from the toolchain file or the defaults.
+        config.tools_mut().pin_rust();
+    }
+
+    session.save_config(&config)?;
+
+    let h = HEADER;
+    eprintln!(
+        "     {h}Created{h:#} Prep configuration '{}'",
+        session.config_path().display()
+    );
+
+    Ok(())
+}
+
+/// Interactively chooses the `config` values, proposing detected ones as defaults.
+fn run_wizard(session: &mut Session, config: &mut Config) -> Result<()> {
+    eprintln!("Configure Prep for this workspace. Press enter to accept the proposed value.\n");
+
+    let project_cfg = config.project();
+    let name = prompt::text("Project name", project_cfg.name())?;
+    let license = prompt::text("Project license", project_cfg.license())?;
+
+    let rust = stable_rust(session, config)?;
+    let rust = prompt_ver_req("Rust toolchain version", &rust)?;
+    let ripgrep = config.tools().ripgrep().to_string();
+    let ripgrep = latest_or(session, Ripgrep::PACKAGE, ripgrep);
+    let ripgrep = prompt_ver_req("ripgrep version", &ripgrep)?;
+    let taplo = config.tools().taplo().to_string();
+    let taplo = latest_or(session, Taplo::PACKAGE, taplo);
+    let taplo = prompt_ver_req("Taplo version", &taplo)?;
+
+    let options = CI_STEPS.map(|(step, _)| step);
+    let defaults = (0..CI_STEPS.len())
+        .filter(|&idx| CI_STEPS[idx].1)
+        .collect::<Vec<_>>();
+    let chosen = prompt::multiselect("CI steps to run", &options, &defaults)?;
+    let steps = chosen.iter().map(|&idx| options[idx]).collect::<Vec<_>>();
+    let default_steps = defaults.iter().map(|&idx| options[idx]).collect::<Vec<_>>();
+
+    config.project_mut().set_name(name);
+    config.project_mut().set_license(license);
+    let tools_cfg = config.tools_mut();
+    tools_cfg.set_rust(rust);
+    tools_cfg.set_ripgrep(ripgrep);
+    tools_cfg.set_taplo(taplo);
+    // The default pipeline adapts to extended mode, so only write out customized pipelines.
+    if steps != default_steps {
+        let steps = steps.into_iter().map(|s| CiStep::new(s.into())).collect();
+        config.ci_mut().set_steps(steps);
+    }
+
+    eprintln!();
+    Ok(())
+}
+
+/// Sets the project name and license in `config` from the Cargo metadata of the workspace.
+///
+/// They are taken from the workspace root package. For virtual workspaces,
+/// the license is taken from the members if they all share the same one.
+/// The configured values are kept when they can't be detected.
+fn detect_project(session: &mut Session, config: &mut Config) {
+    let metadata = match metadata::fetch(session) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            ui::print_warn(&format!(
+                "failed to detect the project name and license: {e:#}"
+            ));
+            return;
+        }
+    };
+
+    let (name, license) = match metadata.root_package() {
+        Some(package) => (Some(package.name.to_string()), package.license.clone()),
+        None => {
+            let mut licenses = metadata
+                .workspace_packages()
+                .into_iter()
+                .map(|p| p.license.clone())
+                .collect::<Vec<_>>();
+            licenses.dedup();
+            let license = match licenses.as_slice() {
+                [license] => license.clone(),
+                _ => None,
+            };
+            (None, license)
+        }
+    };
+
+    let project_cfg = config.project_mut();
+    if let Some(name) = name {
+        project_cfg.set_name(name);
+    }
+    if let Some(license) = license {
+        project_cfg.set_license(license);
+    }
+}
+
+/// Returns the `=MAJOR.MINOR` version of the default Rust toolchain,
+/// falling back to the configured version.
+fn stable_rust(session: &mut Session, config: &Config) -> Result<String> {
+    let toolset = session.toolset();
+    let cargo = toolset.get::<Cargo>(&CargoDeps::default(), None)?;
+    let version = match toolset.version::<Cargo>(&cargo)? {
+        Some(version) if version.pre.is_empty() => {
+            format!("={}.{}", version.major, version.minor)
+        }
+        _ => config.tools().rust().to_string(),
+    };
+    Ok(version)
+}
+
+/// Returns the exact latest version of `package` on crates.io, falling back to `configured`.
+fn latest_or(session: &mut Session, package: &str, configured: String) -> String {
+    match outdated::latest_version(session, package) {
+        Ok(Some(latest)) => format!("={latest}"),
+        Ok(None) => configured,
+        Err(e) => {
+            ui::print_warn(&format!(
+                "failed to check the latest {package} version: {e:#}"
+            ));
+            configured
+        }
+    }
+}
+
+/// Asks for a version requirement until a valid one is given.
+///
+/// Plain versions like `1.93` are treated as exact requirements.
+fn prompt_ver_req(question: &str, default: &str) -> Result<VersionReq> {
+    loop {
+        let answer = prompt::text(question, default)?;
+        match config::parse_ver_req(&answer) {
+            Ok(ver_req) => return Ok(ver_req),
+            Err(e) => ui::print_err(&format!("invalid version requirement '{answer}': {e}")),
+        }
+    }
+}
+
+/// Writes lint and format configuration files matching the strictness of Prep.
+///
+/// Existing files are skipped unless `force` is set.
+fn write_scaffold(session: &Session, force: bool) -> Result<()> {
+    let rustfmt = "\
+# Only stable options, so that formatting works with the locked stable toolchain.
+newline_style = \"Unix\"
+use_field_init_shorthand = true
+use_try_shorthand = true
+";
+
+    let clippy = "\
+# Lints are configured in prep.toml, this file only tunes their behavior.
+allow-unwrap-in-tests = true
+allow-expect-in-tests = true
+allow-print-in-tests = true
+";
+
+    // Allow the licenses of the project itself, along with the common permissive ones.
+    let mut licenses = vec!["Apache-2.0", "MIT", "Unicode-3.0"];
+    let project_license = session.config().project().license();
+    for license in project_license.split(['(', ')', ' ']) {
+        if !license.is_empty()
+            && !["AND", "OR", "WITH"].contains(&license)
+            && !licenses.contains(&license)
+        {
+            licenses.push(license);
+        }
+    }
+    let licenses = licenses
+        .iter()
+        .map(|l| format!("    \"{l}\","))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let deny = format!(
+        "\
+[graph]
+all-features = true
+
+[advisories]
+version = 2
+yanked = \"deny\"
+
+[licenses]
+version = 2
+allow = [
+{licenses}
+]
+
+[bans]
+multiple-versions = \"warn\"
+wildcards = \"deny\"
+
+[sources]
+unknown-registry = \"deny\"
+unknown-git = \"deny\"
+"
+    );
+
+    let h = HEADER;
+    for (name, contents) in [
+        ("rustfmt.toml", rustfmt),
+        ("clippy.toml", clippy),
+        ("deny.toml", deny.as_str()),
+    ] {
+        let path = session.root_dir().join(name);
+        if !force && path.exists() {
+            eprintln!(
+                "     {h}Skipped{h:#} existing '{}', use --force to overwrite it",
+                path.display()
+            );
+            continue;
+        }
+        fs::write(&path, contents).context(format!("failed to write '{}'", path.display()))?;
+        eprintln!("     {h}Created{h:#} '{}'", path.display());
+    }
+
+    Ok(())
+}
+
+/// Writes a `rust-toolchain.toml` with the configured Rust version and components.
+fn write_toolchain_file(session: &Session, force: bool) -> Result<()> {
+    let path = session.root_dir().join(toolchain_file::FILE_NAME);
+    if !force && path.exists() {
+        ui::print_err(&format!(
+            "Rust toolchain file '{}' already exists, aborting.\n\
+            Use --force if you intended to overwrite it.",
+            path.display()
+        ));
         return Ok(());
     }
 
-    // TODO: Instead of just saving the session's config values,
-    //       run an interactive TUI for choosing overrides.
-    session.save_config()?;
+    let tools_cfg = session.config().tools();
+    let components = tools_cfg.rust_components(&["clippy", "rustfmt"]);
+    ToolchainFile::new(tools_cfg.rust(), components).save(session.root_dir())?;
+
+    let h = HEADER;
+    eprintln!(
+        "     {h}Created{h:#} Rust toolchain file '{}'",
+        path.display()
+    );
+
+    Ok(())
+}
+
+/// Writes a GitHub Actions workflow that runs `prep ci` with the configured Rust version.
+fn write_workflow(session: &Session, force: bool) -> Result<()> {
+    let path = session.root_dir().join(WORKFLOW_PATH);
+    if !force && path.exists() {
+        ui::print_err(&format!(
+            "GitHub Actions workflow '{}' already exists, aborting.\n\
+            Use --force if you intended to overwrite it.",
+            path.display()
+        ));
+        return Ok(());
+    }
+
+    let rust = session.config().tools().rust().to_string();
+    let rust = rust.trim_start_matches('=');
+    let version = env!("CARGO_PKG_VERSION");
+    let workflow = format!(
+        "\
+env:
+  RUST_STABLE_VER: \"{rust}\"
+  PREP_VER: \"{version}\"
+
+name: CI
+
+on:
+  pull_request:
+  merge_group:
+  push:
+    branches:
+      - main
+
+jobs:
+  verify:
+    name: verify
+    runs-on: ubuntu-latest
+    steps:
+      - name: Checkout repository
+        uses: actions/checkout@v6
+
+      - name: Install Rust ${{{{ env.RUST_STABLE_VER }}}}
+        uses: dtolnay/rust-toolchain@master
+        with:
+          toolchain: ${{{{ env.RUST_STABLE_VER }}}}
+          components: rustfmt,clippy
+
+      - name: Cache Prep tools and Cargo registry
+        uses: actions/cache@v4
+        with:
+          path: |
+            ~/.cargo/bin/prep
+            ~/.cargo/registry/index
+            ~/.cargo/registry/cache
+            ~/.cargo/git/db
+            ~/.local/share/prep
+          key: prep-${{{{ runner.os }}}}-${{{{ env.PREP_VER }}}}-${{{{ hashFiles('.prep/prep.toml', '**/Cargo.lock') }}}}
+          restore-keys: |
+            prep-${{{{ runner.os }}}}-${{{{ env.PREP_VER }}}}-
+
+      - name: Install Prep ${{{{ env.PREP_VER }}}}
+        run: cargo install prep --locked --version ${{{{ env.PREP_VER }}}}
+
+      - name: Verify workspace
+        run: prep ci --extended
+"
+    );
+
+    let dir = path.parent().expect("workflow path must have a parent");
+    fs::create_dir_all(dir).context(format!(
+        "failed to create workflow directory '{}'",
+        dir.display()
+    ))?;
+    fs::write(&path, workflow).context(format!(
+        "failed to write GitHub Actions workflow '{}'",
+        path.display()
+    ))?;
+
+    let h = HEADER;
+    eprintln!(
+        "     {h}Created{h:#} GitHub Actions workflow '{}'",
+        path.display()
+    );
 
     Ok(())
 }
@@ -0,0 +1,100 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::io;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::cmd::OutputFormat;
+use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_deny::{CargoDeny, CargoDenyDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// The cargo-deny config files, relative to the project root, in the order cargo-deny finds them.
+const CONFIG_FILES: [&str; 3] = ["deny.toml", ".deny.toml", ".cargo/deny.toml"];
+
+/// Reports the licenses of all the dependencies of the workspace with the locked cargo-deny.
+///
+/// The licenses are taken from the `license` field of the package manifests,
+/// or detected from the license files of packages which only have a `license-file`.
+///
+/// If there is a cargo-deny config, e.g. `deny.toml` from `prep init --scaffold`,
+/// then the licenses are also verified against its `[licenses]` policy.
+///
+/// With the JSON `format` the inventory is printed to stdout.
+pub fn run(session: &mut Session, format: OutputFormat) -> Result<()> {
+    let cargo_deny = cargo_deny(session)?;
+    let manifest_path = session.root_dir().join("Cargo.toml");
+
+    let mut cmd = cargo_deny.cmd();
+    cmd.args(["deny", "--locked", "--manifest-path"])
+        .arg(&manifest_path)
+        .args(["list", "--layout", "license", "--format"])
+        .arg(match format {
+            OutputFormat::Text => "human",
+            OutputFormat::Json => "json",
+        });
+    // Only the machine readable output goes to stdout.
+    if format == OutputFormat::Text {
+        cmd.stdout(io::stderr());
+    }
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", CargoDeny::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", CargoDeny::NAME);
+
+    let h = HEADER;
+    let has_policy = CONFIG_FILES
+        .iter()
+        .any(|file| session.root_dir().join(file).is_file());
+    if !has_policy {
+        eprintln!(
+            "      {h}Listed{h:#} licenses without a policy, add one with `prep init --scaffold`"
+        );
+        return Ok(());
+    }
+
+    let mut cmd = cargo_deny.cmd();
+    cmd.args(["deny", "--locked", "--manifest-path"])
+        .arg(&manifest_path)
+        .args(["check", "licenses"]);
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", CargoDeny::NAME))?;
+    ensure!(
+        status.success(),
+        "dependencies with licenses that are not allowed, {} failed: {status}",
+        CargoDeny::NAME
+    );
+
+    eprintln!("    {h}Verified{h:#} licenses of all dependencies are allowed");
+    Ok(())
+}
+
+/// Returns the locked cargo-deny, which uses the locked Rust toolchain for Cargo metadata.
+///
+/// The binary is run directly instead of as a Cargo subcommand, so the first argument must be `deny`.
+fn cargo_deny(session: &mut Session) -> Result<BinCtx> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let rust_ver_req = tools_cfg.rust().clone();
+    let ver_req = tools_cfg.cargo_deny().clone();
+    let toolset = session.toolset();
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), vec![]);
+    let cargo = toolset.get::<Cargo>(&cargo_deps, &rust_ver_req)?;
+    let toolchain = cargo::toolchain_vars(&cargo);
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let deps = CargoDenyDeps::new(cargo_deps, rust_ver_req);
+    let cargo_deny = toolset.get::<CargoDeny>(&deps, &ver_req)?;
+    Ok(cargo_deny.env(&toolchain))
+}
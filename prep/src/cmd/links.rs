@@ -0,0 +1,87 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::files;
+use crate::session::Session;
+use crate::tools::cargo::CargoDeps;
+use crate::tools::lychee::{Lychee, LycheeDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Globs of the files whose links are checked.
+///
+/// Rust source files are included for the links in their doc comments.
+const GLOBS: [&str; 2] = ["*.md", "*.rs"];
+
+/// Verifies that the links in the Markdown and Rust source files of the project are valid.
+///
+/// In `strict` mode Lychee version is locked.
+///
+/// In `offline` mode only the links to files of the project are checked,
+/// so that it works without network access and can't fail due to a flaky website.
+pub fn run(session: &mut Session, strict: bool, offline: bool) -> Result<()> {
+    let lychee = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let cargo_ver_req = tools_cfg.rust().clone();
+        let ver_req = tools_cfg.lychee().clone();
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+        let deps = LycheeDeps::new(cargo_deps, cargo_ver_req);
+        toolset.get::<Lychee>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(None, vec![]);
+        let deps = LycheeDeps::new(cargo_deps, None);
+        toolset.get::<Lychee>(&deps, None)?
+    };
+
+    let files = files(session)?;
+    let h = HEADER;
+    if files.is_empty() {
+        eprintln!("     {h}Skipped{h:#} no Markdown or Rust source files to check");
+        return Ok(());
+    }
+
+    let mut cmd = cmd(session, &lychee, &files, offline);
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", Lychee::NAME))?;
+    ensure!(status.success(), "found broken links");
+
+    if offline {
+        eprintln!("    {h}Verified{h:#} all links to project files are valid");
+    } else {
+        eprintln!("    {h}Verified{h:#} all links are valid");
+    }
+    Ok(())
+}
+
+/// Returns the Markdown and Rust source files whose links are checked, relative to the root.
+pub fn files(session: &Session) -> Result<Vec<PathBuf>> {
+    let globs = GLOBS.map(String::from);
+    files::collect(session.root_dir(), &globs)
+}
+
+pub fn cmd(session: &Session, lychee: &BinCtx, files: &[PathBuf], offline: bool) -> Command {
+    let mut cmd = lychee.cmd();
+    cmd.args(["--no-progress", "--root-dir"])
+        .arg(session.root_dir());
+    if offline {
+        cmd.arg("--offline");
+    }
+    for exclude in session.config().links().exclude() {
+        cmd.args(["--exclude", exclude]);
+    }
+    cmd.arg("--").args(files);
+    cmd
+}
@@ -0,0 +1,56 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::process::{Command, Stdio};
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+
+use crate::session::Session;
+use crate::tools::BinCtx;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Verifies that `Cargo.lock` is up to date with the manifests of the workspace.
+///
+/// Cargo resolves the dependencies with `--locked`, which fails if the lockfile
+/// would need to change, e.g. after a dependency was added to a `Cargo.toml`.
+pub fn run(session: &mut Session) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let ver_req = tools_cfg.rust().clone();
+    let toolset = session.toolset();
+    let deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let cargo = toolset.get::<Cargo>(&deps, &ver_req)?;
+
+    let mut cmd = cmd(&cargo);
+    cmd.stdout(Stdio::null());
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd.output().context("failed to run cargo metadata")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("--locked") {
+            bail!(
+                "Cargo.lock is out of date with the manifests, \
+                run `cargo update --workspace` and commit the updated Cargo.lock"
+            );
+        }
+        bail!("cargo metadata failed: {}\n{stderr}", output.status);
+    }
+
+    let h = HEADER;
+    eprintln!("    {h}Verified{h:#} Cargo.lock is up to date");
+    Ok(())
+}
+
+/// Returns the Cargo command which fails if `Cargo.lock` is out of date with the manifests.
+pub fn cmd(cargo: &BinCtx) -> Command {
+    let mut cmd = cargo.cmd();
+    cmd.arg("metadata")
+        .arg("--locked")
+        .args(["--format-version", "1"]);
+    cmd
+}
@@ -0,0 +1,60 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+
+use crate::session::Session;
+use crate::ui::log::{self, Event, Record};
+use crate::ui::style::{ERROR, HEADER, INFO};
+
+/// Prints the log of the last run.
+pub fn tail(session: &mut Session) -> Result<()> {
+    let logs_dir = session.prep_dir().join(log::DIR_NAME);
+    let Some(path) = log::list(&logs_dir)?.pop() else {
+        bail!("no logs found in '{}'", logs_dir.display());
+    };
+
+    let jsonl =
+        fs::read_to_string(&path).context(format!("failed to read log '{}'", path.display()))?;
+    let (h, i, e) = (HEADER, INFO, ERROR);
+    eprintln!("     {h}Showing{h:#} log '{}'", path.display());
+    let mut finished = false;
+    for line in jsonl.lines() {
+        let record: Record = serde_json::from_str(line)
+            .context(format!("failed to parse log '{}'", path.display()))?;
+        let at = format!("[{:.2}s]", record.elapsed_secs);
+        match record.event {
+            Event::Start {
+                time,
+                version,
+                args,
+            } => eprintln!(
+                "     {h}Started{h:#} `prep {}` at {time} UTC with Prep {version}",
+                args.join(" ")
+            ),
+            Event::Command { command } => eprintln!("     {h}Running{h:#} `{command}` {at}"),
+            Event::Exit {
+                command,
+                duration_secs,
+                status,
+            } => eprintln!("      {h}Exited{h:#} `{command}` {status} in {duration_secs:.2}s {at}"),
+            Event::Debug { message } => eprintln!("       {i}Debug{i:#} {message} {at}"),
+            Event::Finish { error: None } => {
+                finished = true;
+                eprintln!("    {h}Finished{h:#} successfully {at}");
+            }
+            Event::Finish { error: Some(error) } => {
+                finished = true;
+                eprintln!("      {e}Failed{e:#} {error} {at}");
+            }
+        }
+    }
+    if !finished {
+        eprintln!("      {e}Failed{e:#} without finishing, it may have been interrupted");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::Path;
+use std::process::Command;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::session::Session;
+use crate::tools::cargo::CargoDeps;
+use crate::tools::cargo_sort::{CargoSort, CargoSortDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Verifies that the dependency tables of every workspace `Cargo.toml` are sorted and normalized.
+///
+/// The locked cargo-sort is used, which respects the formatting options of `tomlfmt.toml`.
+///
+/// In `fix` mode the manifests are rewritten instead.
+pub fn run(session: &mut Session, fix: bool) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let cargo_ver_req = tools_cfg.rust().clone();
+    let ver_req = tools_cfg.cargo_sort().clone();
+    let toolset = session.toolset();
+    let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let deps = CargoSortDeps::new(cargo_deps, cargo_ver_req);
+    let cargo_sort = toolset.get::<CargoSort>(&deps, &ver_req)?;
+
+    let mut cmd = cmd(&cargo_sort, session.root_dir(), fix);
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", CargoSort::NAME))?;
+    if !fix {
+        ensure!(
+            status.success(),
+            "manifests are not sorted, run `prep manifest-lint --fix` to sort them"
+        );
+    }
+    ensure!(status.success(), "{} failed: {status}", CargoSort::NAME);
+
+    let h = HEADER;
+    if fix {
+        eprintln!("      {h}Sorted{h:#} workspace manifests");
+    } else {
+        eprintln!("    {h}Verified{h:#} workspace manifests are sorted");
+    }
+    Ok(())
+}
+
+/// Returns the cargo-sort command for the manifests of the workspace in `root`.
+///
+/// Without `fix` the manifests are only checked.
+pub fn cmd(cargo_sort: &BinCtx, root: &Path, fix: bool) -> Command {
+    let mut cmd = cargo_sort.cmd();
+    cmd.arg("--workspace");
+    if !fix {
+        cmd.arg("--check");
+    }
+    cmd.arg(root);
+    cmd
+}
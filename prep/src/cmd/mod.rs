@@ -1,14 +1,54 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use clap::ValueEnum;
+use anyhow::{Context, Result, bail, ensure};
+use clap::{Args, ValueEnum};
 
+use crate::config::Config;
+use crate::session::Session;
+use crate::{host, ui};
+
+pub mod bench;
+pub mod bloat;
+pub mod changelog;
+pub mod check;
 pub mod ci;
+pub mod clean;
 pub mod clippy;
+pub mod completions;
+pub mod config;
 pub mod copyright;
+pub mod cross;
+pub mod doctor;
+pub mod duplicates;
+pub mod external;
 pub mod format;
+pub mod graph;
+pub mod hakari;
+pub mod hooks;
 pub mod init;
+pub mod licenses;
+pub mod links;
+pub mod lockfile;
+pub mod logs;
+pub mod manifest_lint;
+pub mod msrv;
+pub mod no_std;
+pub mod publish;
+pub mod readme;
+pub mod release;
+pub mod run;
+pub mod sbom;
+pub mod secrets;
+pub mod self_update;
+pub mod status;
+pub mod test;
+pub mod timings;
 pub mod tools;
+pub mod unsafe_report;
+pub mod vet;
+pub mod wasm;
+pub mod watch;
 
 /// Cargo targets.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
@@ -34,3 +74,117 @@ impl CargoTargets {
         }
     }
 }
+
+/// Output format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text.
+    Text,
+    /// Machine readable JSON.
+    Json,
+}
+
+/// Cargo package selection.
+#[derive(Clone, Debug, Default, Args)]
+pub struct Packages {
+    /// Packages to include, all workspace packages if empty.
+    #[arg(short = 'p', long = "package")]
+    include: Vec<String>,
+    /// Workspace packages to exclude.
+    #[arg(long)]
+    exclude: Vec<String>,
+}
+
+impl Packages {
+    /// Creates a new selection of only the `include` packages, or all if it's empty.
+    pub fn new(include: Vec<String>) -> Self {
+        Self {
+            include,
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if all the workspace packages are selected.
+    pub fn is_all(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Returns `true` if the package with the given `name` is selected.
+    pub fn contains(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p == name))
+            && !self.exclude.iter().any(|p| p == name)
+    }
+
+    /// Returns the Cargo package selection arguments corresponding to `self`.
+    ///
+    /// Fails if all the included packages are excluded too, instead of selecting the whole workspace.
+    pub fn as_args(&self) -> Result<Vec<&str>> {
+        // Cargo only supports --exclude together with --workspace,
+        // so we filter the included packages ourselves.
+        let included = self
+            .include
+            .iter()
+            .filter(|p| self.contains(p))
+            .collect::<Vec<_>>();
+        ensure!(
+            self.include.is_empty() || !included.is_empty(),
+            "no workspace packages selected, all the included packages are excluded"
+        );
+        let mut args = Vec::new();
+        if included.is_empty() {
+            args.push("--workspace");
+            for package in &self.exclude {
+                args.extend(["--exclude", package]);
+            }
+        } else {
+            for package in included {
+                args.extend(["--package", package]);
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Runs the shell `command` in the toolset working directory, which defaults to the project root.
+pub fn run_shell(session: &mut Session, command: &str) -> Result<()> {
+    let (shell, shell_args) = host::shell();
+    let mut cmd = session.toolset().binctx(shell.into()).cmd();
+    cmd.args(shell_args);
+    host::shell_arg(&mut cmd, command);
+
+    ui::print_cmd(&cmd);
+
+    let status = ui::capture::status(&mut cmd).context(format!("failed to run {shell}"))?;
+    ensure!(status.success(), "`{command}` failed: {status}");
+
+    Ok(())
+}
+
+/// Verifies that all the configured hooks are for one of the `commands`.
+pub fn verify_hooks(config: &Config, commands: &[String]) -> Result<()> {
+    for hook in config.hooks().keys() {
+        let command = hook
+            .strip_prefix("pre-")
+            .or_else(|| hook.strip_prefix("post-"));
+        if !command.is_some_and(|c| commands.iter().any(|n| n == c)) {
+            bail!(
+                "unknown hook `{hook}`, expected `pre-<command>` or `post-<command>` \
+                with one of the commands: {}",
+                commands.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Runs the hooks configured for the `stage` of the `command`, e.g. `pre` and `ci`.
+pub fn run_hooks(session: &mut Session, stage: &str, command: &str) -> Result<()> {
+    let hook = format!("{stage}-{command}");
+    let Some(hook_commands) = session.config().hooks().get(&hook).cloned() else {
+        return Ok(());
+    };
+    for hook_command in hook_commands {
+        run_shell(session, &hook_command).context(format!("{hook} hook failed"))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+use semver::Version;
+use toml_edit::{DocumentMut, TableLike};
+
+use crate::cmd::config;
+use crate::session::Session;
+use crate::tools::cargo::CargoDeps;
+use crate::tools::cargo_msrv::{CargoMsrv, CargoMsrvDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Finds the minimum supported Rust version of the workspace with the locked cargo-msrv,
+/// which bisects the Rust releases by checking the workspace with them.
+///
+/// Set `write` to record the version as `rust-version` in the root `Cargo.toml`
+/// and as `msrv` in `[project]` of `prep.toml`.
+pub fn find(session: &mut Session, write: bool) -> Result<()> {
+    let manifest_path = session.root_dir().join("Cargo.toml");
+    ensure!(
+        manifest_path.is_file(),
+        "no Cargo.toml found in '{}'",
+        session.root_dir().display()
+    );
+    let cargo_msrv = cargo_msrv(session)?;
+
+    // The binary is run directly instead of as a Cargo subcommand,
+    // so the first argument must be `msrv`.
+    let mut cmd = cargo_msrv.cmd();
+    cmd.args(["msrv", "--output-format", "minimal", "--manifest-path"])
+        .arg(&manifest_path)
+        .arg("find")
+        .stderr(Stdio::inherit());
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd
+        .output()
+        .context(format!("failed to run {}", CargoMsrv::NAME))?;
+    ensure!(
+        output.status.success(),
+        "{} failed: {}",
+        CargoMsrv::NAME,
+        output.status
+    );
+    // The minimal output is just the version, on the last line.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .and_then(|line| Version::parse(line).ok())
+        .context(format!(
+            "failed to parse {} output: {}",
+            CargoMsrv::NAME,
+            stdout.trim()
+        ))?;
+    // Patch releases don't change the language, so they don't matter for the MSRV.
+    let msrv = format!("{}.{}", version.major, version.minor);
+
+    let h = HEADER;
+    eprintln!("       {h}Found{h:#} minimum supported Rust version {msrv}");
+    if !write {
+        return Ok(());
+    }
+
+    write_rust_version(&manifest_path, &msrv)?;
+    eprintln!(
+        "     {h}Updated{h:#} `rust-version` in '{}'",
+        manifest_path.display()
+    );
+    config::set(session, "project.msrv", &format!("\"{msrv}\""))
+}
+
+/// Sets the `rust-version` in the root manifest at `manifest_path` to `msrv`.
+///
+/// A workspace gets it in `[workspace.package]`, for its packages to inherit,
+/// unless the root package declares its own.
+/// The file is edited in place, so comments and formatting are preserved.
+fn write_rust_version(manifest_path: &Path, msrv: &str) -> Result<()> {
+    let manifest = fs::read_to_string(manifest_path).context(format!(
+        "failed to read manifest '{}'",
+        manifest_path.display()
+    ))?;
+    let mut doc = manifest.parse::<DocumentMut>().context(format!(
+        "failed to parse manifest '{}'",
+        manifest_path.display()
+    ))?;
+
+    // An inherited version is a table with `workspace = true`, not a string.
+    let own_version = doc
+        .get("package")
+        .and_then(|package| package.get("rust-version"))
+        .is_some_and(|version| version.is_str());
+    let parent = if doc.contains_key("workspace") && !own_version {
+        doc["workspace"]
+            .as_table_like_mut()
+            .context("`workspace` of the manifest is not a table")?
+    } else {
+        doc.as_table_mut() as &mut dyn TableLike
+    };
+    let package = parent
+        .entry("package")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .context("`package` of the manifest is not a table")?;
+    package.insert("rust-version", toml_edit::value(msrv));
+
+    fs::write(manifest_path, doc.to_string()).context(format!(
+        "failed to write manifest '{}'",
+        manifest_path.display()
+    ))?;
+    Ok(())
+}
+
+/// Returns the locked cargo-msrv.
+///
+/// It's not given the locked Rust toolchain, as it selects each toolchain that it checks with.
+fn cargo_msrv(session: &mut Session) -> Result<BinCtx> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let rust_ver_req = tools_cfg.rust().clone();
+    let ver_req = tools_cfg.cargo_msrv().clone();
+    let toolset = session.toolset();
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let deps = CargoMsrvDeps::new(cargo_deps, rust_ver_req);
+    toolset.get::<CargoMsrv>(&deps, &ver_req)
+}
@@ -0,0 +1,79 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::process::Command;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+
+use crate::session::Session;
+use crate::tools::BinCtx;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::rustup;
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Builds the packages configured in `prep.toml` without default features
+/// for a target without `std`, to catch accidental `std` usage.
+///
+/// The target is added to the locked Rust toolchain with Rustup if it is missing.
+/// Every package is built before reporting, to show every breakage at once.
+pub fn run(session: &mut Session) -> Result<()> {
+    let no_std_cfg = session.config().no_std();
+    let target = no_std_cfg.target().to_string();
+    let packages = no_std_cfg.packages().clone();
+    let h = HEADER;
+    if packages.is_empty() {
+        eprintln!("     {h}Skipped{h:#} no_std check as no packages are configured");
+        return Ok(());
+    }
+
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let ver_req = tools_cfg.rust().clone();
+    let rust_components = tools_cfg.rust_components(&[]);
+    let toolset = session.toolset();
+    let deps = CargoDeps::new(rustup_ver_req.clone(), rust_components);
+    let cargo = toolset.get::<Cargo>(&deps, &ver_req)?;
+    rustup::add_targets(
+        toolset,
+        Some(&rustup_ver_req),
+        &cargo,
+        std::slice::from_ref(&target),
+    )?;
+
+    let mut failed = Vec::new();
+    for (package, features) in &packages {
+        eprintln!("    {h}Building{h:#} {package} without std for {target}");
+        let mut cmd = cmd(&cargo, package, &target, features);
+
+        ui::print_cmd(&cmd);
+
+        let status = ui::capture::status(&mut cmd).context("failed to run cargo build")?;
+        if !status.success() {
+            ui::print_err(&format!("package '{package}' failed to build: {status}"));
+            failed.push(package.as_str());
+        }
+    }
+    if !failed.is_empty() {
+        bail!("no_std build failed for packages: {}", failed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Returns the command which builds the `package` for the `target` without default features,
+/// but with the given `features`.
+pub fn cmd(cargo: &BinCtx, package: &str, target: &str, features: &[String]) -> Command {
+    let mut cmd = cargo.cmd();
+    cmd.arg("build")
+        .args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .args(["--package", package])
+        .args(["--target", target])
+        .arg("--no-default-features");
+    if !features.is_empty() {
+        cmd.args(["--features", &features.join(",")]);
+    }
+    cmd
+}
@@ -0,0 +1,102 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Result, bail, ensure};
+use cargo_metadata::DependencyKind;
+
+use crate::cmd::ci;
+use crate::metadata;
+use crate::session::Session;
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Verifies that every publishable workspace package can be packaged for crates.io.
+///
+/// The packages are verified with `cargo publish --dry-run` in dependency order.
+/// Cargo checks that all the files are included, that path dependencies also have versions,
+/// and that the package builds from the packaged sources.
+///
+/// Nothing is ever published, so `dry_run` must be set.
+pub fn run(session: &mut Session, dry_run: bool) -> Result<()> {
+    ensure!(
+        dry_run,
+        "publishing is not supported, use `--dry-run` to verify the release readiness"
+    );
+
+    let packages = publish_order(session)?;
+    if packages.is_empty() {
+        let h = HEADER;
+        eprintln!("     {h}Skipped{h:#} publish verification as no packages are publishable");
+        return Ok(());
+    }
+
+    let h = HEADER;
+    let mut failed = Vec::new();
+    for package in &packages {
+        eprintln!("   {h}Verifying{h:#} package {package}");
+        let args = ["publish", "--dry-run", "--locked", "--package", package].map(String::from);
+        if let Err(e) = ci::run_cargo(session, &args) {
+            ui::print_err(&format!("package '{package}': {e:#}"));
+            failed.push(package.as_str());
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "publish verification failed for packages: {}",
+            failed.join(", ")
+        );
+    }
+
+    eprintln!(
+        "    {h}Verified{h:#} {} packages are ready for publishing",
+        packages.len()
+    );
+    Ok(())
+}
+
+/// Returns the names of the publishable workspace packages, dependencies before dependents.
+///
+/// Development dependencies are ignored, as they aren't needed for publishing.
+pub fn publish_order(session: &mut Session) -> Result<Vec<String>> {
+    let metadata = metadata::fetch(session)?;
+    let mut remaining = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|p| {
+            p.publish
+                .as_ref()
+                .is_none_or(|registries| !registries.is_empty())
+        })
+        .map(|p| {
+            let deps = p
+                .dependencies
+                .iter()
+                .filter(|d| d.kind != DependencyKind::Development && d.path.is_some())
+                .map(|d| d.name.clone())
+                .collect::<Vec<_>>();
+            (p.name.to_string(), deps)
+        })
+        .collect::<Vec<_>>();
+
+    let mut order: Vec<String> = Vec::new();
+    while !remaining.is_empty() {
+        let names = remaining
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        // Ready packages only depend on packages that are either already ordered or unpublishable.
+        let (ready, blocked): (Vec<_>, Vec<_>) = remaining
+            .into_iter()
+            .partition(|(_, deps)| deps.iter().all(|d| !names.contains(d)));
+        if ready.is_empty() {
+            bail!(
+                "workspace packages have cyclic dependencies: {}",
+                names.join(", ")
+            );
+        }
+        order.extend(ready.into_iter().map(|(name, _)| name));
+        remaining = blocked;
+    }
+    Ok(order)
+}
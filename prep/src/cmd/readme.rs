@@ -0,0 +1,233 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::path::PathBuf;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+use cargo_metadata::Package;
+
+use crate::metadata;
+use crate::session::Session;
+use crate::ui::style::{ERROR, GOOD, HEADER, HELP, LITERAL};
+
+/// Marks the start of the README section which is generated from the crate docs.
+///
+/// This is the same marker as used by cargo-rdme, so existing READMEs work as is.
+const START_MARKER: &str = "<!-- cargo-rdme start -->";
+/// Marks the end of the README section which is generated from the crate docs.
+const END_MARKER: &str = "<!-- cargo-rdme end -->";
+
+/// Verifies that the README of every configured package matches its crate-level docs.
+///
+/// The crate docs are rendered between the markers of the README,
+/// with hidden lines removed from the Rust code blocks.
+/// If no packages are configured in `[readme]` of `prep.toml`,
+/// all workspace packages with a README that contains the markers are verified.
+///
+/// In `fix` mode the READMEs are regenerated instead.
+pub fn run(session: &mut Session, fix: bool) -> Result<()> {
+    let configured = session.config().readme().packages().to_vec();
+    let metadata = metadata::fetch(session)?;
+
+    let mut packages = Vec::new();
+    for name in &configured {
+        let Some(package) = metadata
+            .workspace_packages()
+            .into_iter()
+            .find(|p| p.name.as_str() == name)
+        else {
+            bail!("package `{name}` in [readme] of prep.toml is not a workspace package");
+        };
+        packages.push(package);
+    }
+    if configured.is_empty() {
+        for package in metadata.workspace_packages() {
+            let readme = fs::read_to_string(readme_path(package)).unwrap_or_default();
+            if readme.contains(START_MARKER) {
+                packages.push(package);
+            }
+        }
+    }
+
+    let (h, e, g, l) = (HEADER, ERROR, GOOD, LITERAL);
+    let mut outdated = Vec::new();
+    for package in &packages {
+        let path = readme_path(package);
+        let readme = fs::read_to_string(&path)
+            .context(format!("failed to read README '{}'", path.display()))?;
+        let docs = crate_docs(package)?;
+        let synced = sync(&readme, &docs).context(format!(
+            "failed to sync README '{}' of package `{}`",
+            path.display(),
+            package.name
+        ))?;
+        if synced == readme {
+            continue;
+        }
+        if fix {
+            fs::write(&path, &synced)
+                .context(format!("failed to write README '{}'", path.display()))?;
+            eprintln!(
+                "   {h}Generated{h:#} {l}{}{l:#} from the crate docs of {}",
+                path.display(),
+                package.name
+            );
+            continue;
+        }
+        eprintln!(
+            "{e}The README {l}{}{l:#}{e} doesn't match the crate docs of {}:{e:#}",
+            path.display(),
+            package.name
+        );
+        let (removed, added) = changed_lines(&readme, &synced);
+        for line in removed {
+            eprintln!("{e}-{line}{e:#}");
+        }
+        for line in added {
+            eprintln!("{g}+{line}{g:#}");
+        }
+        outdated.push(package.name.to_string());
+    }
+
+    if !outdated.is_empty() {
+        let h = HELP;
+        eprintln!("{h}help:{h:#} run `prep readme --fix` to regenerate the READMEs");
+        bail!(
+            "READMEs out of sync with crate docs: {}",
+            outdated.join(", ")
+        );
+    }
+
+    if packages.is_empty() {
+        eprintln!("     {h}Skipped{h:#} no READMEs contain the {l}{START_MARKER}{l:#} marker");
+    } else if !fix {
+        eprintln!(
+            "    {h}Verified{h:#} {} READMEs match the crate docs",
+            packages.len()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the path of the README of `package`.
+fn readme_path(package: &Package) -> PathBuf {
+    let dir = package
+        .manifest_path
+        .parent()
+        .expect("manifest path must have a parent");
+    let readme = package
+        .readme
+        .as_deref()
+        .map_or("README.md", |r| r.as_str());
+    dir.join(readme).into_std_path_buf()
+}
+
+/// Returns the crate-level docs of `package` rendered as Markdown.
+///
+/// The docs are taken from the library target, or the binary target if there is no library.
+fn crate_docs(package: &Package) -> Result<String> {
+    let target = package
+        .targets
+        .iter()
+        .find(|t| t.is_lib())
+        .or_else(|| package.targets.iter().find(|t| t.is_bin()))
+        .context(format!("package `{}` has no crate target", package.name))?;
+    let source = fs::read_to_string(&target.src_path)
+        .context(format!("failed to read '{}'", target.src_path))?;
+
+    let mut docs = String::new();
+    let mut rust_block = false;
+    let mut in_block = false;
+    for line in source.lines() {
+        let line = line.trim_start();
+        let Some(doc) = line.strip_prefix("//!") else {
+            // Crate docs must precede the items, so they end with the first item.
+            if line.is_empty() || line.starts_with("//") || line.starts_with("#![") {
+                continue;
+            }
+            break;
+        };
+        let doc = doc.strip_prefix(' ').unwrap_or(doc);
+        if let Some(info) = doc.trim_start().strip_prefix("```") {
+            if in_block {
+                in_block = false;
+                rust_block = false;
+            } else {
+                in_block = true;
+                rust_block = is_rust_block(info);
+                if rust_block {
+                    docs.push_str("```rust\n");
+                    continue;
+                }
+            }
+        } else if rust_block {
+            // Lines starting with `#` are hidden in the rendered docs.
+            let trimmed = doc.trim_start();
+            if trimmed == "#" || trimmed.starts_with("# ") {
+                continue;
+            }
+        }
+        docs.push_str(doc);
+        docs.push('\n');
+    }
+
+    if docs.trim().is_empty() {
+        bail!(
+            "package `{}` has no crate docs in '{}'",
+            package.name,
+            target.src_path
+        );
+    }
+    Ok(docs)
+}
+
+/// Returns `true` if the code block with the given `info` string is Rust code.
+///
+/// Rustdoc treats code blocks as Rust unless they declare another language.
+fn is_rust_block(info: &str) -> bool {
+    info.split(',').map(str::trim).all(|attr| {
+        matches!(
+            attr,
+            "" | "rust"
+                | "ignore"
+                | "should_panic"
+                | "no_run"
+                | "compile_fail"
+                | "test_harness"
+                | "standalone_crate"
+        ) || attr.starts_with("edition")
+    })
+}
+
+/// Returns `readme` with the section between the markers replaced by `docs`.
+fn sync(readme: &str, docs: &str) -> Result<String> {
+    let (Some(start), Some(end)) = (readme.find(START_MARKER), readme.find(END_MARKER)) else {
+        bail!("missing the `{START_MARKER}` and `{END_MARKER}` markers");
+    };
+    if end < start {
+        bail!("the `{END_MARKER}` marker precedes the `{START_MARKER}` marker");
+    }
+    let before = &readme[..start + START_MARKER.len()];
+    let after = &readme[end..];
+    Ok(format!("{before}\n\n{}\n\n{after}", docs.trim()))
+}
+
+/// Returns the lines of `old` and `new` that differ,
+/// skipping the common lines at the start and end.
+fn changed_lines<'a>(old: &'a str, new: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let prefix = old.iter().zip(&new).take_while(|(o, n)| o == n).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+    (
+        old[prefix..old.len() - suffix].to_vec(),
+        new[prefix..new.len() - suffix].to_vec(),
+    )
+}
@@ -0,0 +1,531 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use clap::ValueEnum;
+use regex::Regex;
+use semver::Version;
+use time::UtcDateTime;
+use toml_edit::{DocumentMut, Item};
+
+use crate::cmd::{ci, publish};
+use crate::metadata;
+use crate::session::Session;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::git::{self, Git};
+use crate::ui;
+use crate::ui::prompt;
+use crate::ui::style::{HEADER, LITERAL};
+
+/// The number of times to try publishing a package when hitting the crates.io rate limit.
+const PUBLISH_ATTEMPTS: u32 = 5;
+
+/// How long to wait before retrying after hitting the crates.io rate limit.
+const RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
+
+/// The changelog file name, in the workspace root and in the package directories.
+const CHANGELOG_NAME: &str = "CHANGELOG.md";
+
+/// The dependency tables of a manifest, directly in it or in its `[target.<cfg>]` tables.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// The version component to increment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Level {
+    /// Incompatible changes, e.g. `1.2.3` -> `2.0.0`.
+    Major,
+    /// Compatible additions, e.g. `1.2.3` -> `1.3.0`.
+    Minor,
+    /// Compatible fixes, e.g. `1.2.3` -> `1.2.4`.
+    Patch,
+}
+
+/// A version bump of a single package.
+struct Bump {
+    dir: PathBuf,
+    old: Version,
+    new: Version,
+}
+
+/// Releases the publishable workspace packages with their versions bumped by `level`.
+///
+/// Without a `level` it is chosen interactively.
+///
+/// The versions are bumped in all the workspace manifests, including the requirements of
+/// the dependencies between the workspace packages. The `[Unreleased]` sections of the
+/// changelogs become the new versions. The changes are committed and a signed tag is created,
+/// named `v<version>`, or `<package>-v<version>` if the package versions differ.
+///
+/// Set `publish` to also publish the packages to crates.io in dependency order,
+/// waiting out the crates.io rate limit if needed. This is confirmed first in a terminal.
+pub fn run(session: &mut Session, level: Option<Level>, publish: bool) -> Result<()> {
+    let git = session.toolset().get::<Git>(&(), None)?;
+    let changes = git::output(&git, &["status", "--porcelain", "--untracked-files=no"])?;
+    ensure!(
+        changes.trim().is_empty(),
+        "the working tree has uncommitted changes, commit or stash them before releasing"
+    );
+
+    let order = publish::publish_order(session)?;
+    ensure!(
+        !order.is_empty(),
+        "no publishable workspace packages to release"
+    );
+    let metadata = metadata::fetch(session)?;
+    let level = match level {
+        Some(level) => level,
+        None => {
+            let version = metadata
+                .workspace_packages()
+                .into_iter()
+                .find(|p| p.name.as_str() == order[0])
+                .map(|p| p.version.clone())
+                .context(format!("package '{}' is not in the workspace", order[0]))?;
+            choose_level(&version)?
+        }
+    };
+    let publish = publish
+        && prompt::confirm(
+            &format!("Publish {} packages to crates.io?", order.len()),
+            true,
+        )?;
+    let root_dir = metadata.workspace_root.clone().into_std_path_buf();
+    let mut bumps = BTreeMap::new();
+    let mut manifests = vec![root_dir.join("Cargo.toml")];
+    for package in metadata.workspace_packages() {
+        let manifest = package.manifest_path.clone().into_std_path_buf();
+        if order.contains(&package.name.to_string()) {
+            let dir = manifest.parent().unwrap_or(&root_dir).to_path_buf();
+            let bump = Bump {
+                dir,
+                old: package.version.clone(),
+                new: bump(&package.version, level),
+            };
+            bumps.insert(package.name.to_string(), bump);
+        }
+        if !manifests.contains(&manifest) {
+            manifests.push(manifest);
+        }
+    }
+
+    // Update the manifests.
+    let new_versions = bumps
+        .iter()
+        .map(|(name, bump)| (name.clone(), bump.new.clone()))
+        .collect::<BTreeMap<_, _>>();
+    for manifest in &manifests {
+        let package = bumps
+            .values()
+            .find(|b| b.dir.join("Cargo.toml") == *manifest)
+            .map(|b| &b.new);
+        let toml = fs::read_to_string(manifest)
+            .context(format!("failed to read '{}'", manifest.display()))?;
+        let bumped = bump_manifest(&toml, package, level, &new_versions).context(format!(
+            "failed to bump the versions in '{}'",
+            manifest.display()
+        ))?;
+        if bumped != toml {
+            fs::write(manifest, bumped)
+                .context(format!("failed to write '{}'", manifest.display()))?;
+        }
+    }
+    // Refresh the versions of the workspace packages in the lockfile.
+    ci::run_cargo(session, &["update".into(), "--workspace".into()])?;
+
+    let h = HEADER;
+    for (name, bump) in &bumps {
+        eprintln!("      {h}Bumped{h:#} {name} {} -> {}", bump.old, bump.new);
+    }
+
+    // Update the changelogs.
+    let shared = bumps
+        .values()
+        .all(|b| b.new == bumps.values().next().unwrap().new);
+    let mut changelogs = BTreeMap::new();
+    if shared {
+        let bump = bumps.values().next().unwrap();
+        changelogs.insert(root_dir.join(CHANGELOG_NAME), (bump, "v".to_string()));
+    }
+    for (name, bump) in &bumps {
+        let prefix = if shared {
+            "v".to_string()
+        } else {
+            format!("{name}-v")
+        };
+        changelogs.insert(bump.dir.join(CHANGELOG_NAME), (bump, prefix));
+    }
+    let today = UtcDateTime::now().date();
+    let date = format!(
+        "{:04}-{:02}-{:02}",
+        today.year(),
+        u8::from(today.month()),
+        today.day()
+    );
+    for (path, (bump, prefix)) in changelogs {
+        if path.exists() {
+            update_changelog(&path, bump, &prefix, &date)?;
+            eprintln!("     {h}Updated{h:#} changelog '{}'", path.display());
+        }
+    }
+
+    // Commit and tag.
+    let tags = if shared {
+        vec![format!("v{}", bumps.values().next().unwrap().new)]
+    } else {
+        bumps
+            .iter()
+            .map(|(name, bump)| format!("{name}-v{}", bump.new))
+            .collect()
+    };
+    let message = format!("Release {}", tags.join(", "));
+    git::output(&git, &["commit", "--all", "--message", &message])?;
+    for tag in &tags {
+        git::output(&git, &["tag", "--sign", "--message", &message, tag]).context(format!(
+            "failed to create signed tag '{tag}', the release commit is already created"
+        ))?;
+        eprintln!("      {h}Tagged{h:#} {tag}");
+    }
+
+    if publish {
+        for package in &order {
+            publish_package(session, package)?;
+            eprintln!("   {h}Published{h:#} {package} {}", bumps[package].new);
+        }
+    }
+
+    let l = LITERAL;
+    eprintln!("Push the release with {l}git push --follow-tags{l:#}");
+    Ok(())
+}
+
+/// Asks which level to release, showing how it bumps the `version`.
+fn choose_level(version: &Version) -> Result<Level> {
+    let levels = [Level::Patch, Level::Minor, Level::Major];
+    let options = levels.map(|level| {
+        let name = level
+            .to_possible_value()
+            .expect("release levels are not skipped");
+        format!(
+            "{:<5}  {version} -> {}",
+            name.get_name(),
+            bump(version, level)
+        )
+    });
+    let options = options.iter().map(String::as_str).collect::<Vec<_>>();
+    let idx = prompt::select("Release level", &options, None)
+        .context("specify the release level, e.g. `prep release patch`")?;
+    Ok(levels[idx])
+}
+
+/// Returns the `version` with the `level` component incremented.
+fn bump(version: &Version, level: Level) -> Version {
+    match level {
+        Level::Major => Version::new(version.major + 1, 0, 0),
+        Level::Minor => Version::new(version.major, version.minor + 1, 0),
+        Level::Patch => Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+/// Returns the manifest `toml` with the versions bumped.
+///
+/// The `[package]` version is set to `package`, the `[workspace.package]` version is bumped
+/// by `level`, and the requirements of the path dependencies in `versions` are set to their
+/// versions, including in `[workspace.dependencies]`. Dependencies renamed with `package`
+/// are matched by their package name. Versions inherited with `workspace = true` are kept.
+/// The manifest is edited in place, so comments and formatting are preserved.
+fn bump_manifest(
+    toml: &str,
+    package: Option<&Version>,
+    level: Level,
+    versions: &BTreeMap<String, Version>,
+) -> Result<String> {
+    let mut doc = toml.parse::<DocumentMut>()?;
+
+    if let Some(new) = package
+        && let Some(version) = doc
+            .get_mut("package")
+            .and_then(|package| package.get_mut("version"))
+            .filter(|version| version.is_str())
+    {
+        set_version(version, new);
+    }
+
+    if let Some(version) = doc
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("package"))
+        .and_then(|package| package.get_mut("version"))
+        .filter(|version| version.is_str())
+    {
+        let old = version.as_str().unwrap_or_default();
+        let old =
+            Version::parse(old).context(format!("failed to parse workspace version '{old}'"))?;
+        set_version(version, &bump(&old, level));
+    }
+
+    let mut tables = Vec::new();
+    for (key, item) in doc.as_table_mut().iter_mut() {
+        match key.get() {
+            "workspace" => tables.extend(item.get_mut("dependencies")),
+            "target" => {
+                let targets = item.as_table_like_mut().into_iter();
+                for (_, target) in targets.flat_map(|targets| targets.iter_mut()) {
+                    let deps = target.as_table_like_mut().into_iter();
+                    for (key, item) in deps.flat_map(|target| target.iter_mut()) {
+                        if DEPENDENCY_TABLES.contains(&key.get()) {
+                            tables.push(item);
+                        }
+                    }
+                }
+            }
+            key if DEPENDENCY_TABLES.contains(&key) => tables.push(item),
+            _ => {}
+        }
+    }
+
+    for deps in tables.into_iter().filter_map(Item::as_table_like_mut) {
+        for (key, dep) in deps.iter_mut() {
+            // Only path dependencies are on the workspace packages, the others are on crates.io.
+            let Some(dep) = dep
+                .as_table_like_mut()
+                .filter(|dep| dep.contains_key("path"))
+            else {
+                continue;
+            };
+            let name = dep
+                .get("package")
+                .and_then(Item::as_str)
+                .unwrap_or(key.get());
+            let Some(new) = versions.get(name) else {
+                continue;
+            };
+            if let Some(version) = dep.get_mut("version").filter(|version| version.is_str()) {
+                set_version(version, new);
+            }
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Sets the version string `item` to `new`, keeping its formatting.
+///
+/// The operator of requirements is kept too, e.g. `=1.2.3`.
+fn set_version(item: &mut Item, new: &Version) {
+    let Some(value) = item.as_value_mut() else {
+        return;
+    };
+    let op = value
+        .as_str()
+        .unwrap_or_default()
+        .chars()
+        .take_while(|c| !c.is_ascii_digit())
+        .collect::<String>();
+    let decor = value.decor().clone();
+    *value = format!("{op}{new}").into();
+    *value.decor_mut() = decor;
+}
+
+/// Turns the `[Unreleased]` section of the changelog at `path` into the new version section.
+///
+/// Comparison links in the style of Keep a Changelog are updated as well,
+/// assuming the tags are named with the `tag_prefix` followed by the version.
+fn update_changelog(path: &Path, bump: &Bump, tag_prefix: &str, date: &str) -> Result<()> {
+    const UNRELEASED: &str = "## [Unreleased]";
+
+    let changelog =
+        fs::read_to_string(path).context(format!("failed to read '{}'", path.display()))?;
+    if !changelog.contains(UNRELEASED) {
+        bail!(
+            "changelog '{}' has no `{UNRELEASED}` section",
+            path.display()
+        );
+    }
+    let (old, new) = (&bump.old, &bump.new);
+    let mut updated = changelog.replacen(
+        UNRELEASED,
+        &format!("{UNRELEASED}\n\n## [{new}] - {date}"),
+        1,
+    );
+
+    // e.g. `[Unreleased]: https://github.com/o/r/compare/v0.2.0...HEAD`
+    let link_re = Regex::new(r"(?m)^\[Unreleased\]: (\S+)/compare/\S+\.\.\.HEAD$")
+        .expect("Changelog link regex was incorrect");
+    if let Some(c) = link_re.captures(&updated) {
+        let base = c[1].to_string();
+        let links = format!(
+            "[Unreleased]: {base}/compare/{tag_prefix}{new}...HEAD\n\
+            [{new}]: {base}/compare/{tag_prefix}{old}...{tag_prefix}{new}"
+        );
+        updated = link_re.replace(&updated, links.as_str()).into_owned();
+    }
+
+    fs::write(path, updated).context(format!("failed to write '{}'", path.display()))?;
+    Ok(())
+}
+
+/// Publishes the `package` to crates.io, retrying when hitting the rate limit.
+fn publish_package(session: &mut Session, package: &str) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let ver_req = tools_cfg.rust().clone();
+    let rust_components = tools_cfg.rust_components(&[]);
+    let toolset = session.toolset();
+    let deps = CargoDeps::new(rustup_ver_req, rust_components);
+    let cargo = toolset.get::<Cargo>(&deps, &ver_req)?;
+
+    for attempt in 1..=PUBLISH_ATTEMPTS {
+        let mut cmd = cargo.cmd();
+        cmd.arg("publish")
+            .args(ui::cargo_verbosity_args())
+            .arg("--locked")
+            .args(["--package", package]);
+
+        ui::print_cmd(&cmd);
+
+        let output = cmd.output().context("failed to run cargo publish")?;
+        ui::capture::eprint(&String::from_utf8_lossy(&output.stdout));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        ui::capture::eprint(&stderr);
+        if output.status.success() {
+            return Ok(());
+        }
+        let rate_limited = stderr.contains("429") || stderr.contains("Too Many Requests");
+        if !rate_limited || attempt == PUBLISH_ATTEMPTS {
+            bail!("cargo publish failed: {}", output.status);
+        }
+        ui::print_warn(&format!(
+            "crates.io rate limit reached, retrying in {}s",
+            RATE_LIMIT_DELAY.as_secs()
+        ));
+        thread::sleep(RATE_LIMIT_DELAY);
+    }
+    unreachable!("the last publish attempt always returns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the versions of the workspace packages `foo` and `bar` after the release.
+    fn versions() -> BTreeMap<String, Version> {
+        BTreeMap::from([
+            ("foo".to_string(), Version::new(0, 3, 0)),
+            ("bar".to_string(), Version::new(1, 1, 0)),
+        ])
+    }
+
+    #[test]
+    fn bump_package_and_dependencies() {
+        let toml = r#"[package]
+name = "foo"
+version = "0.2.0" # The release version.
+
+[dependencies]
+bar = { version = "=1.0.0", path = "../bar" }
+serde = "1.0.0"
+
+[dev-dependencies.bar]
+version = "1.0.0"
+path = "../bar"
+
+[target.'cfg(unix)'.build-dependencies]
+bar = { version = "1.0.0", path = "../bar" }
+"#;
+        let bumped = bump_manifest(
+            toml,
+            Some(&Version::new(0, 3, 0)),
+            Level::Minor,
+            &versions(),
+        );
+        assert_eq!(
+            bumped.unwrap(),
+            r#"[package]
+name = "foo"
+version = "0.3.0" # The release version.
+
+[dependencies]
+bar = { version = "=1.1.0", path = "../bar" }
+serde = "1.0.0"
+
+[dev-dependencies.bar]
+version = "1.1.0"
+path = "../bar"
+
+[target.'cfg(unix)'.build-dependencies]
+bar = { version = "1.1.0", path = "../bar" }
+"#
+        );
+    }
+
+    #[test]
+    fn bump_workspace_inherited_versions() {
+        let root = r#"[workspace]
+members = ["foo", "bar"]
+
+[workspace.package]
+version = "1.0.0"
+
+[workspace.dependencies]
+bar = { version = "1.0.0", path = "bar" }
+"#;
+        let bumped = bump_manifest(root, None, Level::Minor, &versions());
+        assert_eq!(
+            bumped.unwrap(),
+            r#"[workspace]
+members = ["foo", "bar"]
+
+[workspace.package]
+version = "1.1.0"
+
+[workspace.dependencies]
+bar = { version = "1.1.0", path = "bar" }
+"#
+        );
+
+        let member = r#"[package]
+name = "bar"
+version.workspace = true
+
+[dependencies]
+foo = { workspace = true }
+"#;
+        let bumped = bump_manifest(
+            member,
+            Some(&Version::new(1, 1, 0)),
+            Level::Minor,
+            &versions(),
+        );
+        assert_eq!(bumped.unwrap(), member);
+    }
+
+    #[test]
+    fn bump_renamed_dependencies() {
+        let toml = r#"[package]
+name = "baz"
+version = "0.1.0"
+
+[dependencies]
+bar = { package = "foo", version = "0.2.0", path = "../foo" }
+old-bar = { package = "bar", version = "0.9.0" }
+"#;
+        let bumped = bump_manifest(toml, None, Level::Minor, &versions());
+        assert_eq!(
+            bumped.unwrap(),
+            r#"[package]
+name = "baz"
+version = "0.1.0"
+
+[dependencies]
+bar = { package = "foo", version = "0.3.0", path = "../foo" }
+old-bar = { package = "bar", version = "0.9.0" }
+"#
+        );
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+
+use crate::cmd::ci::{self, StepFn};
+use crate::cmd::run_shell;
+use crate::config::{Config, TaskStep};
+use crate::session::Session;
+use crate::ui::style::{HEADER, LITERAL};
+
+/// Runs the task configured in `prep.toml` with the given `name`.
+///
+/// The steps run in order with the task's environment variables and working directory,
+/// stopping at the first failure.
+///
+/// Without a `name`, the configured tasks are listed instead.
+pub fn run(session: &mut Session, name: Option<&str>) -> Result<()> {
+    let tasks = session.config().tasks();
+    let Some(name) = name else {
+        let (h, l) = (HEADER, LITERAL);
+        if tasks.is_empty() {
+            eprintln!("No tasks configured, add them to {l}[tasks]{l:#} in {l}prep.toml{l:#}.");
+        } else {
+            eprintln!("{h}Tasks:{h:#}");
+            for name in tasks.keys() {
+                eprintln!("  {l}{name}{l:#}");
+            }
+        }
+        return Ok(());
+    };
+    let Some(task) = tasks.get(name).cloned() else {
+        let available = tasks.keys().cloned().collect::<Vec<_>>().join(", ");
+        bail!("unknown task '{name}', available tasks: {available}");
+    };
+
+    let steps = task
+        .steps()
+        .iter()
+        .map(|s| step(session.config(), s))
+        .collect::<Result<Vec<_>>>()
+        .context(format!("invalid task '{name}'"))?;
+
+    let toolset = session.toolset();
+    let environment = toolset.environment().clone();
+    let working_dir = toolset.working_dir().to_path_buf();
+    let task_dir = match task.dir() {
+        Some(dir) => session.root_dir().join(dir),
+        None => working_dir.clone(),
+    };
+    ensure!(
+        task_dir.is_dir(),
+        "task '{name}' working directory '{}' does not exist",
+        task_dir.display()
+    );
+
+    let toolset = session.toolset();
+    toolset.set_environment(environment.clone().custom(task.env()));
+    toolset.set_working_dir(task_dir);
+
+    let h = HEADER;
+    eprintln!("     {h}Running{h:#} task '{name}'");
+    let result = steps.iter().try_for_each(|step| step(session));
+
+    // Restore the defaults, so that the post-run hooks are unaffected by the task.
+    let toolset = session.toolset();
+    toolset.set_environment(environment);
+    toolset.set_working_dir(working_dir);
+
+    result.context(format!("task '{name}' failed"))
+}
+
+/// Returns the function that runs the task `step`.
+fn step(config: &Config, step: &TaskStep) -> Result<StepFn> {
+    let run: StepFn = match (step.builtin(), step.cargo(), step.command()) {
+        (Some(builtin), None, None) => ci::builtin_step(config, builtin)?,
+        (None, Some(args), None) => {
+            let args = args.to_vec();
+            Box::new(move |s| ci::run_cargo(s, &args))
+        }
+        (None, None, Some(command)) => {
+            let command = command.to_string();
+            Box::new(move |s| run_shell(s, &command))
+        }
+        _ => bail!("task steps must set exactly one of builtin, cargo, and command"),
+    };
+    Ok(run)
+}
@@ -0,0 +1,301 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use cargo_metadata::{Metadata, Package, PackageId};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use time::UtcDateTime;
+
+use crate::metadata;
+use crate::session::Session;
+use crate::ui::style::HEADER;
+
+/// Software bill of materials format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON.
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+    /// SPDX 2.3 JSON.
+    Spdx,
+}
+
+/// A managed tool installation from the toolset manifest.
+struct ManagedTool {
+    name: String,
+    version: String,
+}
+
+/// Generates a software bill of materials of the workspace.
+///
+/// It covers all the packages of `Cargo.lock`, their dependency relationships,
+/// and the tools installed in the managed toolset.
+///
+/// The document is written to `output`, or printed to stdout if there is none.
+pub fn run(session: &mut Session, format: SbomFormat, output: Option<&Path>) -> Result<()> {
+    let name = session.config().project().name().to_string();
+
+    let metadata = metadata::fetch_graph(session)?;
+
+    let tools = session
+        .toolset()
+        .manifest()
+        .entries()
+        .into_iter()
+        .map(|(name, version)| ManagedTool {
+            name,
+            version: version.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let timestamp = timestamp();
+    let document = match format {
+        SbomFormat::CycloneDx => cyclonedx(&name, &timestamp, &metadata, &tools),
+        SbomFormat::Spdx => spdx(&name, &timestamp, &metadata, &tools),
+    };
+    let json = serde_json::to_string_pretty(&document).context("failed to generate SBOM JSON")?;
+
+    let Some(output) = output else {
+        println!("{json}");
+        return Ok(());
+    };
+    fs::write(output, format!("{json}\n"))
+        .context(format!("failed to write SBOM '{}'", output.display()))?;
+
+    let h = HEADER;
+    eprintln!(
+        "   {h}Generated{h:#} SBOM of {} packages and {} tools '{}'",
+        metadata.packages.len(),
+        tools.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Returns the CycloneDX document.
+fn cyclonedx(name: &str, timestamp: &str, metadata: &Metadata, tools: &[ManagedTool]) -> Value {
+    let mut components = metadata
+        .packages
+        .iter()
+        .map(|package| {
+            let kind = if package.targets.iter().any(|t| t.is_bin()) {
+                "application"
+            } else {
+                "library"
+            };
+            let mut component = json!({
+                "type": kind,
+                "bom-ref": purl(package),
+                "name": package.name.to_string(),
+                "version": package.version.to_string(),
+                "purl": purl(package),
+            });
+            if let Some(license) = &package.license {
+                component["licenses"] = json!([{ "expression": license }]);
+            }
+            if metadata.workspace_members.contains(&package.id) {
+                component["scope"] = json!("required");
+            }
+            component
+        })
+        .collect::<Vec<_>>();
+    components.extend(tools.iter().map(|tool| {
+        json!({
+            "type": "application",
+            "bom-ref": tool_purl(tool),
+            "name": tool.name,
+            "version": tool.version,
+            "purl": tool_purl(tool),
+            "scope": "excluded",
+            "properties": [{ "name": "prep:managed-tool", "value": "true" }],
+        })
+    }));
+
+    let dependencies = dependencies(metadata)
+        .into_iter()
+        .map(|(package, deps)| {
+            json!({
+                "ref": purl(package),
+                "dependsOn": deps.into_iter().map(purl).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "prep",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": name,
+                "name": name,
+            },
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// Returns the SPDX document.
+fn spdx(name: &str, timestamp: &str, metadata: &Metadata, tools: &[ManagedTool]) -> Value {
+    let mut packages = metadata
+        .packages
+        .iter()
+        .map(|package| {
+            json!({
+                "SPDXID": spdx_id(&package.name, &package.version.to_string()),
+                "name": package.name.to_string(),
+                "versionInfo": package.version.to_string(),
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": package.license.as_deref().unwrap_or("NOASSERTION"),
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": purl(package),
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+    packages.extend(tools.iter().map(|tool| {
+        json!({
+            "SPDXID": spdx_id(&tool.name, &tool.version),
+            "name": tool.name,
+            "versionInfo": tool.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "comment": "Managed tool installed by Prep.",
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": tool_purl(tool),
+            }],
+        })
+    }));
+
+    let package_id = |p: &Package| spdx_id(&p.name, &p.version.to_string());
+    let mut relationships = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|package| {
+            json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": package_id(package),
+            })
+        })
+        .collect::<Vec<_>>();
+    for (package, deps) in dependencies(metadata) {
+        relationships.extend(deps.into_iter().map(|dep| {
+            json!({
+                "spdxElementId": package_id(package),
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": package_id(dep),
+            })
+        }));
+    }
+    for tool in tools {
+        relationships.push(json!({
+            "spdxElementId": spdx_id(&tool.name, &tool.version),
+            "relationshipType": "BUILD_TOOL_OF",
+            "relatedSpdxElement": "SPDXRef-DOCUMENT",
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": name,
+        "documentNamespace": format!("https://spdx.org/spdxdocs/{name}-{timestamp}"),
+        "creationInfo": {
+            "created": timestamp,
+            "creators": [format!("Tool: prep-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// Returns every resolved package with its direct dependencies, in package ID order.
+fn dependencies(metadata: &Metadata) -> Vec<(&Package, Vec<&Package>)> {
+    let packages = metadata
+        .packages
+        .iter()
+        .map(|p| (&p.id, p))
+        .collect::<BTreeMap<&PackageId, &Package>>();
+    let Some(resolve) = &metadata.resolve else {
+        return Vec::new();
+    };
+    let mut deps = resolve
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let package = *packages.get(&node.id)?;
+            let deps = node
+                .dependencies
+                .iter()
+                .filter_map(|id| packages.get(id).copied())
+                .collect::<Vec<_>>();
+            Some((package, deps))
+        })
+        .collect::<Vec<_>>();
+    deps.sort_by(|(a, _), (b, _)| a.id.cmp(&b.id));
+    deps
+}
+
+/// Returns the package URL of the Cargo `package`.
+fn purl(package: &Package) -> String {
+    format!("pkg:cargo/{}@{}", package.name, package.version)
+}
+
+/// Returns the package URL of the managed `tool`.
+fn tool_purl(tool: &ManagedTool) -> String {
+    format!("pkg:generic/{}@{}", tool.name, tool.version)
+}
+
+/// Returns an SPDX element ID, which may only contain letters, numbers, `.` and `-`.
+fn spdx_id(name: &str, version: &str) -> String {
+    let id = format!("SPDXRef-Package-{name}-{version}");
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Returns the current time in the ISO 8601 format used by both SBOM formats.
+fn timestamp() -> String {
+    let now = UtcDateTime::now();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
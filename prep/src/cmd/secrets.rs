@@ -0,0 +1,143 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::io::ErrorKind;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+
+use crate::files;
+use crate::session::Session;
+use crate::ui::style::{HEADER, LITERAL};
+
+/// Built-in secret detection rules, as the description and the regular expression.
+const RULES: [(&str, &str); 10] = [
+    ("AWS access key ID", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "AWS secret access key",
+        r#"(?i)aws_?secret_?access_?key\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}\b"#,
+    ),
+    ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,255}\b"),
+    (
+        "GitHub fine-grained token",
+        r"\bgithub_pat_[A-Za-z0-9_]{82}\b",
+    ),
+    ("GitLab token", r"\bglpat-[A-Za-z0-9_-]{20}\b"),
+    ("Slack token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    ("crates.io token", r"\bcio[A-Za-z0-9]{32}\b"),
+    ("Google API key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    ("Stripe secret key", r"\b[rs]k_live_[0-9A-Za-z]{24,}\b"),
+    (
+        "Private key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY(?: BLOCK)?-----",
+    ),
+];
+
+/// Marker which allows the findings on its line, e.g. in a trailing comment.
+const ALLOW_MARKER: &str = "prep:allow-secret";
+
+/// A potential secret found in a file.
+struct Finding {
+    path: String,
+    line: usize,
+    rule: &'static str,
+    secret: String,
+}
+
+/// Scans the files of the repository for committed credentials.
+///
+/// All the project files are scanned, including hidden ones, except ignored and binary files.
+/// Findings can be allowed with the `allow` regexes and files skipped with the `exclude` globs
+/// in `[secrets]` of `prep.toml`, or a single line with a `prep:allow-secret` comment.
+pub fn run(session: &mut Session) -> Result<()> {
+    let rules = RULES
+        .iter()
+        .map(|(rule, re)| {
+            let re = Regex::new(re).expect("secret rule regex was incorrect");
+            (*rule, re)
+        })
+        .collect::<Vec<_>>();
+    let secrets_cfg = session.config().secrets();
+    let allow = compile(secrets_cfg.allow())?;
+    let exclude = secrets_cfg
+        .exclude()
+        .iter()
+        .map(|glob| format!("!{glob}"))
+        .collect::<Vec<_>>();
+
+    let root = session.root_dir();
+    let mut findings = Vec::new();
+    let mut scanned = 0;
+    for path in files::collect_with_hidden(root, &exclude)? {
+        let full_path = root.join(&path);
+        let bytes = match fs::read(&full_path) {
+            Ok(bytes) => bytes,
+            // Files can disappear at any time, e.g. editor swap files.
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(e).context(format!("failed to read file '{}'", full_path.display()));
+            }
+        };
+        if bytes.contains(&0) {
+            continue;
+        }
+        scanned += 1;
+        let text = String::from_utf8_lossy(&bytes);
+        for (i, line) in text.lines().enumerate() {
+            if line.contains(ALLOW_MARKER) {
+                continue;
+            }
+            for (rule, re) in &rules {
+                for m in re.find_iter(line) {
+                    if allow.iter().any(|re| re.is_match(m.as_str())) {
+                        continue;
+                    }
+                    findings.push(Finding {
+                        path: path.display().to_string().replace('\\', "/"),
+                        line: i + 1,
+                        rule,
+                        secret: redact(m.as_str()),
+                    });
+                }
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        let h = HEADER;
+        eprintln!("    {h}Verified{h:#} {scanned} files contain no secrets");
+        return Ok(());
+    }
+
+    let l = LITERAL;
+    let mut msg = String::new();
+    for finding in &findings {
+        msg.push_str(&format!(
+            "\n{l}{}:{}{l:#}: {} {}",
+            finding.path, finding.line, finding.rule, finding.secret
+        ));
+    }
+    bail!(
+        "found {} potential secrets:{msg}\n\
+        Revoke real credentials, or allow false positives in [secrets] of prep.toml",
+        findings.len()
+    )
+}
+
+/// Compiles the configured regular expressions `res` of `[secrets] allow`.
+fn compile(res: &[String]) -> Result<Vec<Regex>> {
+    res.iter()
+        .map(|re| Regex::new(re).context(format!("invalid regex '{re}' in [secrets] allow")))
+        .collect()
+}
+
+/// Returns the `secret` with all but its first four characters hidden.
+fn redact(secret: &str) -> String {
+    let visible = secret.chars().take(4).collect::<String>();
+    format!(
+        "{visible}{}",
+        "*".repeat(secret.chars().count().saturating_sub(4).min(16))
+    )
+}
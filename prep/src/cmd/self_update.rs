@@ -0,0 +1,181 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo::empty_dir;
+use crate::tools::curl::{self, Curl};
+use crate::tools::prep::Prep;
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// The GitHub API endpoint of the latest Prep release.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Nevermore/prep/releases/latest";
+
+/// A GitHub release.
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// Update the running Prep executable to the latest release on GitHub.
+///
+/// In `check` mode only the availability of a newer release is reported.
+///
+/// The executable is replaced atomically, so an interrupted update leaves the old one intact.
+pub fn run(session: &mut Session, check: bool) -> Result<()> {
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))
+        .context("failed to parse the current Prep version")?;
+    let latest = latest_version(session)?;
+
+    let h = HEADER;
+    if latest <= current {
+        eprintln!("    {h}Verified{h:#} Prep {current} is the latest release");
+        return Ok(());
+    }
+    if check {
+        eprintln!("   {h}Available{h:#} Prep {latest}, currently running {current}");
+        return Ok(());
+    }
+
+    let exe = env::current_exe().context("failed to get the current executable path")?;
+    let exe = exe
+        .canonicalize()
+        .context(format!("failed to canonicalize '{}'", exe.display()))?;
+
+    // Download and verify the release into the tools directory first.
+    let toolset = session.toolset();
+    let ver_req = VersionReq::parse(&format!("={latest}")).context(format!(
+        "failed to convert version '{latest}' to a version requirement"
+    ))?;
+    let (binctx, version) = Prep::set_up(toolset, &(), &ver_req)?;
+    let install_dir = toolset.install_dir(Prep::NAME, &version);
+
+    let result = replace_exe(binctx.path(), &exe);
+    // The downloaded binary is only needed until it has replaced the executable.
+    let cleanup = remove_download(binctx.path(), &install_dir);
+    result?;
+    cleanup?;
+
+    eprintln!(
+        "     {h}Updated{h:#} Prep {current} -> {version} at '{}'",
+        exe.display()
+    );
+    Ok(())
+}
+
+/// Removes the executable that was replaced by an update, which Windows keeps around until restart.
+///
+/// This is done on every start, and failures are ignored as the file may still be in use.
+pub fn remove_replaced() {
+    if let Ok(exe) = env::current_exe() {
+        let old = replaced_path(&exe);
+        if old.exists() {
+            _ = fs::remove_file(old);
+        }
+    }
+}
+
+/// Returns the latest Prep version released on GitHub.
+fn latest_version(session: &mut Session) -> Result<Version> {
+    let toolset = session.toolset();
+    let curl = toolset.get::<Curl>(&(), None)?;
+    let mut cmd = curl::download_cmd(&curl, toolset.network(), LATEST_RELEASE_URL);
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd.output().context("failed to run curl")?;
+    ensure!(
+        output.status.success(),
+        "failed to fetch the latest Prep release from GitHub: {}",
+        output.status
+    );
+    let release: Release = serde_json::from_slice(&output.stdout)
+        .context("failed to parse the latest Prep release from GitHub")?;
+    let tag = release.tag_name.trim_start_matches('v');
+    Version::parse(tag).context(format!("failed to parse Prep release tag '{tag}'"))
+}
+
+/// Atomically replaces the executable at `exe` with a copy of `new`.
+///
+/// The copy is made next to `exe` first, so that the final rename stays on the same file system.
+/// Windows doesn't allow replacing a running executable, but it does allow renaming it,
+/// so there the old executable is moved aside for [`remove_replaced`] to clean up later.
+fn replace_exe(new: &Path, exe: &Path) -> Result<()> {
+    let temp = exe.with_extension("new");
+    fs::copy(new, &temp).context(format!(
+        "failed to copy '{}' to '{}'",
+        new.display(),
+        temp.display()
+    ))?;
+    // Keep the permissions of the current executable, e.g. who can run it.
+    let permissions = fs::metadata(exe)
+        .context(format!("failed to read '{}'", exe.display()))?
+        .permissions();
+    fs::set_permissions(&temp, permissions).context(format!(
+        "failed to set the permissions of '{}'",
+        temp.display()
+    ))?;
+
+    if cfg!(target_os = "windows") {
+        let old = replaced_path(exe);
+        if old.exists() {
+            fs::remove_file(&old).context(format!("failed to remove '{}'", old.display()))?;
+        }
+        fs::rename(exe, &old).context(format!(
+            "failed to move '{}' to '{}'",
+            exe.display(),
+            old.display()
+        ))?;
+        if let Err(e) = fs::rename(&temp, exe) {
+            // Put the old executable back, so that Prep keeps working.
+            _ = fs::rename(&old, exe);
+            _ = fs::remove_file(&temp);
+            return Err(e).context(format!("failed to replace '{}'", exe.display()));
+        }
+    } else if let Err(e) = fs::rename(&temp, exe) {
+        _ = fs::remove_file(&temp);
+        return Err(e).context(format!("failed to replace '{}'", exe.display()));
+    }
+    Ok(())
+}
+
+/// Returns the path that the replaced executable `exe` is moved to on Windows.
+fn replaced_path(exe: &Path) -> PathBuf {
+    exe.with_extension("old")
+}
+
+/// Removes the downloaded binary at `bin` and its `install_dir`, along with the tool directory if empty.
+fn remove_download(bin: &Path, install_dir: &Path) -> Result<()> {
+    if bin.parent() != Some(install_dir) {
+        bail!(
+            "downloaded {} binary '{}' is unexpectedly not in '{}'",
+            Prep::NAME,
+            bin.display(),
+            install_dir.display()
+        );
+    }
+    fs::remove_file(bin).context(format!("failed to remove '{}'", bin.display()))?;
+    fs::remove_dir(install_dir).context(format!(
+        "failed to remove directory '{}'",
+        install_dir.display()
+    ))?;
+    if let Some(tool_dir) = install_dir.parent()
+        && empty_dir(tool_dir)?
+    {
+        fs::remove_dir(tool_dir).context(format!(
+            "failed to remove directory '{}'",
+            tool_dir.display()
+        ))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,171 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::path::Path;
+
+use anstream::eprintln;
+use anyhow::{Context, Result};
+
+use crate::cmd::ci;
+use crate::cmd::tools::list;
+use crate::config::{self, CONFIG_VERSION};
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::git::{self, Git};
+use crate::tools::rustup::Rustup;
+use crate::ui::style::{ERROR, GOOD, HEADER, TABLE_HEADER, WARN};
+
+/// Show an overview of the project without running any checks.
+///
+/// This covers where the project and its config were found, pending config migrations,
+/// the Git status, the required and installed tool versions, and the last CI run.
+pub fn run(session: &mut Session) -> Result<()> {
+    let h = HEADER;
+    eprintln!(
+        "     {h}Project{h:#} '{}' found by {}",
+        session.root_dir().display(),
+        session.root_discovery().description()
+    );
+    let project_cfg = session.config().project();
+    eprintln!(
+        "        {h}Name{h:#} {} licensed {}",
+        project_cfg.name(),
+        project_cfg.license()
+    );
+    if let Some(msrv) = project_cfg.msrv() {
+        eprintln!("        {h}MSRV{h:#} Rust {msrv}");
+    }
+    for (label, path) in [
+        ("Config", session.config_path().to_path_buf()),
+        ("User config", session.user_config_path()),
+    ] {
+        eprintln!("{h}{label:>12}{h:#} {}", config_status(&path)?);
+    }
+    eprintln!("         {h}Git{h:#} {}", git_status(session));
+
+    print_tools(session);
+    print_ci(session)?;
+    Ok(())
+}
+
+/// Returns the location and the migration status of the config file at `path`.
+fn config_status(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Ok(format!("'{}' not found", path.display()));
+    }
+    let config_toml = fs::read_to_string(path)
+        .context(format!("failed to read config file '{}'", path.display()))?;
+    let (g, w) = (GOOD, WARN);
+    let migration = match config::migrate(&config_toml) {
+        Ok(None) => format!("{g}up to date{g:#}"),
+        Ok(Some(_)) => format!(
+            "{w}older than version {CONFIG_VERSION}{w:#}, run `prep config migrate` to upgrade it"
+        ),
+        Err(e) => format!("{w}{e:#}{w:#}"),
+    };
+    Ok(format!("'{}' {migration}", path.display()))
+}
+
+/// Returns the current Git branch and the number of uncommitted changes.
+fn git_status(session: &mut Session) -> String {
+    let status = session.toolset().get::<Git>(&(), None).and_then(|git| {
+        // The branch of a repository without commits is still known, unlike with `rev-parse`.
+        let branch = git::output(&git, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .map(|b| b.trim().to_string())
+            .unwrap_or_else(|_| "detached HEAD".into());
+        let changes = git::output(&git, &["status", "--porcelain"])?;
+        Ok((branch, changes.lines().count()))
+    });
+    let (g, w) = (GOOD, WARN);
+    match status {
+        Ok((branch, 0)) => format!("{branch}, {g}clean{g:#}"),
+        Ok((branch, changes)) => format!("{branch}, {w}{changes} uncommitted changes{w:#}"),
+        Err(_) => "not a Git repository".into(),
+    }
+}
+
+/// Prints the required tool versions and the managed installations that satisfy them.
+fn print_tools(session: &mut Session) {
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    let tools = list::tools(session);
+    let manifest = session.toolset().manifest();
+    let rows = tools
+        .into_iter()
+        .filter_map(|(name, required, _)| {
+            let required = required?;
+            // Rustup and the Rust toolchains are never in the tool manifest.
+            let installed = if name == Rustup::NAME || name == "rust" {
+                "managed by rustup".into()
+            } else {
+                let (g, w) = (GOOD, WARN);
+                match manifest
+                    .versions(name)
+                    .into_iter()
+                    .rfind(|v| required.matches(v))
+                {
+                    Some(version) => format!("{g}{version}{g:#}"),
+                    None => format!("{w}not installed{w:#}"),
+                }
+            };
+            let required = required.to_string().trim_start_matches('=').to_string();
+            Some((name, required, installed))
+        })
+        .collect::<Vec<_>>();
+
+    let nlen = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+    let rlen = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(8);
+    let t = TABLE_HEADER;
+    eprintln!();
+    eprintln!(
+        "{t}{}{t:#}  {t}{}{t:#}  {t}Installed{t:#}",
+        cell("Tool", nlen),
+        cell("Required", rlen)
+    );
+    for (name, required, installed) in rows {
+        eprintln!(
+            "{}  {}  {installed}",
+            cell(name, nlen),
+            cell(&required, rlen)
+        );
+    }
+}
+
+/// Prints the outcome of every step in the last CI run.
+fn print_ci(session: &mut Session) -> Result<()> {
+    eprintln!();
+    let h = HEADER;
+    let Some(last_run) = ci::last_run(session)? else {
+        eprintln!("          {h}CI{h:#} no recorded run, run `prep ci` in a Git repository");
+        return Ok(());
+    };
+    let tree = if last_run.current {
+        "the current working tree"
+    } else {
+        "an older working tree"
+    };
+    eprintln!("          {h}CI{h:#} last run on {tree}");
+
+    let mut names = ci::step_names(session, false)?;
+    for name in last_run.passed.iter().chain(&last_run.failed) {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    let (g, e, w) = (GOOD, ERROR, WARN);
+    for name in names {
+        if last_run.passed.contains(&name) {
+            eprintln!("      {g}Passed{g:#} {name}");
+        } else if last_run.failed.contains(&name) {
+            eprintln!("      {e}Failed{e:#} {name}");
+        } else {
+            eprintln!("     {w}Not run{w:#} {name}");
+        }
+    }
+    Ok(())
+}
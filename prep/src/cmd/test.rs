@@ -0,0 +1,295 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use regex::Regex;
+
+use crate::cmd::Packages;
+use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_nextest::{CargoNextest, CargoNextestDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+use crate::{github, metadata};
+
+/// The cargo-nextest tool config file name, in the target directory.
+pub const NEXTEST_CONFIG_NAME: &str = "prep-nextest.toml";
+
+/// The cargo-nextest JUnit report file name, in the profile's directory.
+const JUNIT_NAME: &str = "prep-junit.xml";
+
+/// A partition of the tests, e.g. `2/4` for the second quarter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+    /// One-based index of the partition.
+    index: u32,
+    /// Number of partitions, the `[test]` configuration if `None`.
+    count: Option<u32>,
+}
+
+impl FromStr for Partition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = match s.split_once('/') {
+            Some((index, count)) => (index, Some(count)),
+            None => (s, None),
+        };
+        let index = index
+            .parse()
+            .map_err(|_| format!("invalid partition index '{index}'"))?;
+        let count = count
+            .map(|c| {
+                c.parse()
+                    .map_err(|_| format!("invalid partition count '{c}'"))
+            })
+            .transpose()?;
+        Ok(Self { index, count })
+    }
+}
+
+/// Runs `cargo test` on the selected `packages`.
+///
+/// In `strict` mode Cargo and cargo-nextest versions are locked.
+///
+/// Set `doc` to only run the documentation tests, which only library targets can have.
+///
+/// With a `partition` only that part of the tests is run with cargo-nextest,
+/// to split the tests across multiple CI runners.
+///
+/// cargo-nextest is also used when retries are configured in `[test]` of `prep.toml`.
+/// Tests which only passed on retry are reported, also in the GitHub Actions job summary.
+pub fn run(
+    session: &mut Session,
+    strict: bool,
+    doc: bool,
+    partition: Option<Partition>,
+    packages: &Packages,
+) -> Result<()> {
+    if doc {
+        ensure!(
+            partition.is_none(),
+            "doc tests can't be partitioned, as cargo-nextest doesn't run them"
+        );
+        let metadata = metadata::fetch(session)?;
+        let has_lib = metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|p| packages.contains(&p.name))
+            .any(|p| p.targets.iter().any(|t| t.is_lib() || t.is_proc_macro()));
+        if !has_lib {
+            let h = HEADER;
+            eprintln!("     {h}Skipped{h:#} doc tests as no packages have a library target");
+            return Ok(());
+        }
+    }
+
+    let cargo = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&[]);
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(rustup_ver_req, rust_components);
+        toolset.get::<Cargo>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(None, vec![]);
+        toolset.get::<Cargo>(&deps, None)?
+    };
+
+    if !doc && (partition.is_some() || session.config().test().has_retries()) {
+        let partition = match partition {
+            Some(partition) => {
+                let Some(count) = partition.count.or(session.config().test().partitions()) else {
+                    bail!(
+                        "the partition count is unknown, use `--partition <index>/<count>` \
+                        or configure `partitions` in `[test]` of prep.toml"
+                    );
+                };
+                ensure!(
+                    (1..=count).contains(&partition.index),
+                    "partition index {} is out of range 1..={count}",
+                    partition.index
+                );
+                Some(format!("count:{}/{count}", partition.index))
+            }
+            None => None,
+        };
+
+        let nextest = if strict {
+            let tools_cfg = session.config().tools();
+            let rustup_ver_req = tools_cfg.rustup().clone();
+            let cargo_ver_req = tools_cfg.rust().clone();
+            let ver_req = tools_cfg.cargo_nextest().clone();
+            let toolset = session.toolset();
+            let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+            let deps = CargoNextestDeps::new(cargo_deps, cargo_ver_req);
+            toolset.get::<CargoNextest>(&deps, &ver_req)?
+        } else {
+            let toolset = session.toolset();
+            let cargo_deps = CargoDeps::new(None, vec![]);
+            let deps = CargoNextestDeps::new(cargo_deps, None);
+            toolset.get::<CargoNextest>(&deps, None)?
+        };
+
+        let target_dir = metadata::fetch(session)?
+            .target_directory
+            .into_std_path_buf();
+        let config_path = target_dir.join(NEXTEST_CONFIG_NAME);
+        write_nextest_config(session, &config_path)?;
+        let junit_path = target_dir.join("nextest").join("default").join(JUNIT_NAME);
+        if junit_path.exists() {
+            fs::remove_file(&junit_path)
+                .context(format!("failed to remove '{}'", junit_path.display()))?;
+        }
+
+        let nextest = nextest.env(&cargo::toolchain_vars(&cargo));
+        let mut cmd = nextest_cmd(&nextest, packages, &config_path, partition.as_deref())?;
+
+        ui::print_cmd(&cmd);
+
+        let status = ui::capture::status(&mut cmd)
+            .context(format!("failed to run {}", CargoNextest::NAME))?;
+
+        report_flaky(&junit_path)?;
+        ensure!(status.success(), "{} failed: {status}", CargoNextest::NAME);
+
+        return Ok(());
+    }
+
+    let mut cmd = cargo_cmd(&cargo, doc, packages)?;
+
+    ui::print_cmd(&cmd);
+
+    let status = ui::capture::status(&mut cmd).context("failed to run cargo test")?;
+    ensure!(status.success(), "cargo test failed: {status}");
+
+    Ok(())
+}
+
+/// Returns the `cargo test` command for the selected `packages`, only for doc tests if `doc`.
+pub fn cargo_cmd(cargo: &BinCtx, doc: bool, packages: &Packages) -> Result<Command> {
+    let mut cmd = cargo.cmd();
+    cmd.arg("test")
+        .args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .args(packages.as_args()?)
+        .arg("--all-features");
+    if doc {
+        cmd.arg("--doc");
+    }
+    Ok(cmd)
+}
+
+/// Returns the cargo-nextest command for the selected `packages` with the tool config at `config_path`.
+///
+/// The `partition` is given in the cargo-nextest format, e.g. `count:2/4`.
+pub fn nextest_cmd(
+    nextest: &BinCtx,
+    packages: &Packages,
+    config_path: &Path,
+    partition: Option<&str>,
+) -> Result<Command> {
+    // The binary is run directly instead of as a Cargo subcommand,
+    // so the first argument must be `nextest`.
+    let mut cmd = nextest.cmd();
+    cmd.arg("nextest")
+        .arg("run")
+        .arg("--locked")
+        .args(packages.as_args()?)
+        .arg("--all-features")
+        .arg("--tool-config-file")
+        .arg(format!("prep:{}", config_path.display()));
+    if let Some(partition) = partition {
+        cmd.args(["--partition", partition]);
+    }
+    Ok(cmd)
+}
+
+/// Writes the cargo-nextest tool config with the retry policy and the JUnit report path.
+///
+/// Tool configs have a lower precedence than the project's own `.config/nextest.toml`.
+fn write_nextest_config(session: &Session, path: &Path) -> Result<()> {
+    let test_cfg = session.config().test();
+    let mut profile = toml::Table::new();
+    if let Some(retries) = test_cfg.retries() {
+        profile.insert("retries".into(), i64::from(retries).into());
+    }
+    let overrides = test_cfg
+        .overrides()
+        .iter()
+        .map(|o| {
+            let mut table = toml::Table::new();
+            table.insert("filter".into(), o.filter().into());
+            table.insert("retries".into(), i64::from(o.retries()).into());
+            toml::Value::Table(table)
+        })
+        .collect::<Vec<_>>();
+    if !overrides.is_empty() {
+        profile.insert("overrides".into(), overrides.into());
+    }
+    let mut junit = toml::Table::new();
+    junit.insert("path".into(), JUNIT_NAME.into());
+    profile.insert("junit".into(), junit.into());
+
+    let mut profiles = toml::Table::new();
+    profiles.insert("default".into(), profile.into());
+    let mut config = toml::Table::new();
+    config.insert("profile".into(), profiles.into());
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context(format!("failed to create '{}'", dir.display()))?;
+    }
+    fs::write(path, config.to_string()).context(format!("failed to write '{}'", path.display()))?;
+    Ok(())
+}
+
+/// Reports the tests which only passed on retry, according to the JUnit report at `path`.
+fn report_flaky(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let junit = fs::read_to_string(path).context(format!("failed to read '{}'", path.display()))?;
+    let testcase = Regex::new(r"(?s)<testcase ([^>]*[^/>])>(.*?)</testcase>").unwrap();
+    let name = Regex::new(r#"\bname="([^"]*)""#).unwrap();
+    let classname = Regex::new(r#"\bclassname="([^"]*)""#).unwrap();
+
+    let mut flaky = Vec::new();
+    for caps in testcase.captures_iter(&junit) {
+        let (attrs, body) = (&caps[1], &caps[2]);
+        // Tests that failed every attempt have a failure or error in addition to the flaky ones.
+        let passed_on_retry = (body.contains("<flakyFailure") || body.contains("<flakyError"))
+            && !body.contains("<failure")
+            && !body.contains("<error");
+        if !passed_on_retry {
+            continue;
+        }
+        let class = classname.captures(attrs).map(|c| c[1].to_string());
+        let test = name.captures(attrs).map(|c| c[1].to_string());
+        flaky.push(match (class, test) {
+            (Some(class), Some(test)) => format!("{class} {test}"),
+            (_, test) => test.unwrap_or_default(),
+        });
+    }
+    if flaky.is_empty() {
+        return Ok(());
+    }
+
+    ui::print_warn(&format!(
+        "tests that only passed on retry:\n{}",
+        flaky.join("\n")
+    ));
+    let mut markdown = String::from("### Flaky tests\n\nThese tests only passed on retry:\n\n");
+    for test in &flaky {
+        markdown.push_str(&format!("- `{test}`\n"));
+    }
+    github::append_step_summary(&markdown)
+}
@@ -0,0 +1,286 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::metadata;
+use crate::session::Session;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::ui;
+use crate::ui::style::{HEADER, TABLE_HEADER};
+
+/// The directory where the timings of previous runs are stored, inside the prep directory.
+const TIMINGS_DIR: &str = "timings";
+
+/// Number of previous runs which are kept.
+const HISTORY: usize = 20;
+
+/// A unit becomes a regression when it's slower than in the previous run by both of these.
+const REGRESSION_SECS: f64 = 1.0;
+const REGRESSION_RATIO: f64 = 1.25;
+
+/// The HTML report written by `cargo build --timings`, relative to the target directory.
+const REPORT_FILE: &str = "cargo-timings/cargo-timing.html";
+
+/// The start of the unit data JSON array embedded in the HTML report.
+const UNIT_DATA_START: &str = "const UNIT_DATA = ";
+
+/// The parts of a unit in the Cargo timing report which are used.
+#[derive(Deserialize)]
+struct UnitData {
+    name: String,
+    version: String,
+    /// Empty for libraries, otherwise e.g. ` prep "bin"` or ` build-script (run)`.
+    target: String,
+    duration: f64,
+}
+
+/// Compile times of a single run.
+#[derive(Serialize, Deserialize)]
+struct Run {
+    /// Milliseconds since the Unix epoch when the run finished.
+    finished: u64,
+    /// Wall time of the whole build in seconds.
+    total: f64,
+    /// Compile times of the units in seconds, keyed by a readable unit name.
+    units: BTreeMap<String, f64>,
+}
+
+/// Profile the build of the workspace and report the slowest crates and regressions.
+///
+/// The compile times come from the Cargo timing report.
+///
+/// In `strict` mode Cargo version is locked.
+///
+/// The per-crate compile times of the last runs are kept in the `timings` directory,
+/// and the `count` slowest crates are compared to the previous run and the average.
+/// Only the crates which were actually compiled have timings, so this is most useful
+/// on clean builds, e.g. in CI.
+pub fn run(session: &mut Session, strict: bool, release: bool, count: usize) -> Result<()> {
+    let cargo = if strict {
+        let tools_cfg = session.config().tools();
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let ver_req = tools_cfg.rust().clone();
+        let rust_components = tools_cfg.rust_components(&[]);
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(rustup_ver_req, rust_components);
+        toolset.get::<Cargo>(&deps, &ver_req)?
+    } else {
+        let toolset = session.toolset();
+        let deps = CargoDeps::new(None, vec![]);
+        toolset.get::<Cargo>(&deps, None)?
+    };
+
+    let metadata = metadata::fetch(session)?;
+    let report_path = metadata.target_directory.join(REPORT_FILE);
+
+    let mut cmd = cargo.cmd();
+    cmd.arg("build").args(ui::cargo_verbosity_args()).args([
+        "--locked",
+        "--workspace",
+        "--all-features",
+        "--timings",
+    ]);
+    if release {
+        cmd.arg("--release");
+    }
+
+    ui::print_cmd(&cmd);
+
+    let start = Instant::now();
+    let status = ui::capture::status(&mut cmd).context("failed to run cargo build")?;
+    ensure!(status.success(), "cargo build failed: {status}");
+
+    // The machine readable timings are unstable, but the HTML report embeds them as JSON.
+    let html = fs::read_to_string(&report_path)
+        .context(format!("failed to read timing report '{report_path}'"))?;
+    let mut units = BTreeMap::new();
+    // Units which were up to date have no duration.
+    for unit in parse_units(&html)?.into_iter().filter(|u| u.duration > 0.0) {
+        *units.entry(unit_name(&unit)).or_default() += unit.duration;
+    }
+
+    let finished = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let run = Run {
+        finished,
+        total: start.elapsed().as_secs_f64(),
+        units,
+    };
+
+    let timings_dir = session.prep_dir().join(TIMINGS_DIR);
+    let history = load_runs(&timings_dir)?;
+    print_report(&run, &history, count);
+
+    session.ensure_prep_dir()?;
+    save_run(&timings_dir, &run)?;
+    prune_runs(&timings_dir)?;
+    Ok(())
+}
+
+/// Parses the units from the Cargo timing report `html`.
+fn parse_units(html: &str) -> Result<Vec<UnitData>> {
+    let start = html
+        .find(UNIT_DATA_START)
+        .context("timing report has no unit data")?
+        + UNIT_DATA_START.len();
+    let len = html[start..]
+        .find("\n];")
+        .context("timing report unit data is not terminated")?
+        + "\n]".len();
+    serde_json::from_str(&html[start..start + len]).context("failed to parse timing report JSON")
+}
+
+/// Returns a readable name of the `unit`, e.g. `serde 1.0.228 build-script (run)`.
+fn unit_name(unit: &UnitData) -> String {
+    format!(
+        "{} {}{}",
+        unit.name,
+        unit.version,
+        unit.target.replace('"', "")
+    )
+}
+
+/// Prints the `count` slowest units of the `run`, compared to the previous runs in `history`.
+fn print_report(run: &Run, history: &[Run], count: usize) {
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+    let secs = |s: Option<f64>| s.map_or_else(|| "-".into(), |s| format!("{s:.2}s"));
+
+    let previous = history.last();
+    let average = |unit: &str| {
+        let times = history
+            .iter()
+            .filter_map(|r| r.units.get(unit))
+            .collect::<Vec<_>>();
+        (!times.is_empty()).then(|| times.iter().copied().sum::<f64>() / times.len() as f64)
+    };
+
+    let mut slowest = run.units.iter().collect::<Vec<_>>();
+    slowest.sort_by(|a, b| b.1.total_cmp(a.1).then(a.0.cmp(b.0)));
+    slowest.truncate(count);
+
+    let nlen = slowest
+        .iter()
+        .map(|(n, _)| n.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let t = TABLE_HEADER;
+    eprintln!(
+        "{t}{}{t:#}  {t}Time{t:#}      {t}Previous{t:#}  {t}Average{t:#}",
+        cell("Unit", nlen)
+    );
+    for (unit, time) in &slowest {
+        eprintln!(
+            "{}  {}  {}  {}",
+            cell(unit, nlen),
+            cell(&secs(Some(**time)), 8),
+            cell(&secs(previous.and_then(|p| p.units.get(*unit).copied())), 8),
+            secs(average(unit))
+        );
+    }
+
+    let h = HEADER;
+    let compared = previous
+        .map(|p| format!(", {:.1}s in the previous run", p.total))
+        .unwrap_or_default();
+    eprintln!(
+        "    {h}Finished{h:#} compiling {} units in {:.1}s{compared}",
+        run.units.len(),
+        run.total
+    );
+
+    let Some(previous) = previous else {
+        return;
+    };
+    let mut regressions = run
+        .units
+        .iter()
+        .filter_map(|(unit, &time)| {
+            let before = *previous.units.get(unit)?;
+            let regressed = time - before >= REGRESSION_SECS && time >= before * REGRESSION_RATIO;
+            regressed.then_some((unit, before, time))
+        })
+        .collect::<Vec<_>>();
+    regressions.sort_by(|a, b| (b.2 - b.1).total_cmp(&(a.2 - a.1)));
+    for (unit, before, time) in regressions {
+        ui::print_warn(&format!(
+            "{unit} took {time:.2}s to compile, up from {before:.2}s in the previous run"
+        ));
+    }
+}
+
+/// Loads the previous runs from `dir`, oldest first.
+fn load_runs(dir: &Path) -> Result<Vec<Run>> {
+    let mut runs = Vec::new();
+    for path in run_files(dir)? {
+        let json =
+            fs::read(&path).context(format!("failed to read timings file '{}'", path.display()))?;
+        let run = serde_json::from_slice(&json).context("failed to parse timings JSON")?;
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+/// Saves the `run` to `dir`.
+fn save_run(dir: &Path, run: &Run) -> Result<()> {
+    fs::create_dir_all(dir).context(format!(
+        "failed to create timings directory '{}'",
+        dir.display()
+    ))?;
+    let path = dir.join(format!("{}.json", run.finished));
+    let json = serde_json::to_string_pretty(run).context("failed to generate timings JSON")?;
+    fs::write(&path, json).context(format!("failed to write timings file '{}'", path.display()))?;
+    Ok(())
+}
+
+/// Removes all but the last [`HISTORY`] runs from `dir`.
+fn prune_runs(dir: &Path) -> Result<()> {
+    let files = run_files(dir)?;
+    for path in &files[..files.len().saturating_sub(HISTORY)] {
+        fs::remove_file(path).context(format!(
+            "failed to remove timings file '{}'",
+            path.display()
+        ))?;
+    }
+    Ok(())
+}
+
+/// Returns the paths of the run files in `dir`, oldest first.
+fn run_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let read_dir = dir
+        .read_dir()
+        .context(format!("failed to read directory '{}'", dir.display()))?;
+    let mut files = Vec::new();
+    for entry in read_dir {
+        let entry = entry.context(format!("failed to read directory '{}'", dir.display()))?;
+        let path = entry.path();
+        let finished = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(finished) = finished
+            && path.extension().is_some_and(|e| e == "json")
+        {
+            files.push((finished, path));
+        }
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
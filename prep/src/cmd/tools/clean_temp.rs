@@ -0,0 +1,90 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::Result;
+
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo_bloat::CargoBloat;
+use crate::tools::cargo_deny::CargoDeny;
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_msrv::CargoMsrv;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::cargo_vet::CargoVet;
+use crate::tools::git_cliff::GitCliff;
+use crate::tools::lychee::Lychee;
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::taplo::Taplo;
+use crate::tools::wasm_bindgen::WasmBindgen;
+use crate::ui::style::HEADER;
+
+/// Remove the temporary install directories left behind by interrupted tool installations.
+///
+/// Only the files that an installation leaves behind are removed,
+/// any other files make this fail so that they can be inspected manually.
+pub fn run(session: &mut Session) -> Result<()> {
+    let toolset = session.toolset();
+
+    let cleaned = [
+        (
+            CargoGeiger::NAME,
+            toolset.clean_temp_install_dir::<CargoGeiger>()?,
+        ),
+        (
+            CargoHakari::NAME,
+            toolset.clean_temp_install_dir::<CargoHakari>()?,
+        ),
+        (
+            CargoBloat::NAME,
+            toolset.clean_temp_install_dir::<CargoBloat>()?,
+        ),
+        (
+            CargoMsrv::NAME,
+            toolset.clean_temp_install_dir::<CargoMsrv>()?,
+        ),
+        (
+            CargoDeny::NAME,
+            toolset.clean_temp_install_dir::<CargoDeny>()?,
+        ),
+        (
+            CargoNextest::NAME,
+            toolset.clean_temp_install_dir::<CargoNextest>()?,
+        ),
+        (
+            CargoSort::NAME,
+            toolset.clean_temp_install_dir::<CargoSort>()?,
+        ),
+        (
+            CargoVet::NAME,
+            toolset.clean_temp_install_dir::<CargoVet>()?,
+        ),
+        (
+            GitCliff::NAME,
+            toolset.clean_temp_install_dir::<GitCliff>()?,
+        ),
+        (Lychee::NAME, toolset.clean_temp_install_dir::<Lychee>()?),
+        (Ripgrep::NAME, toolset.clean_temp_install_dir::<Ripgrep>()?),
+        (Taplo::NAME, toolset.clean_temp_install_dir::<Taplo>()?),
+        (
+            WasmBindgen::NAME,
+            toolset.clean_temp_install_dir::<WasmBindgen>()?,
+        ),
+    ];
+
+    let h = HEADER;
+    let mut total = 0;
+    for (name, removed) in cleaned {
+        for path in &removed {
+            eprintln!("     {h}Removed{h:#} {name} leftover '{}'", path.display());
+        }
+        total += removed.len();
+    }
+    if total == 0 {
+        eprintln!("    {h}Finished{h:#} no interrupted tool installations to clean up");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::Result;
+use semver::Version;
+use time::{Date, UtcDateTime};
+
+use crate::cmd::tools::list::format_size;
+use crate::session::Session;
+use crate::ui;
+use crate::ui::style::{HEADER, TABLE_HEADER};
+
+/// Bytes in a MiB.
+const MIB: u64 = 1024 * 1024;
+
+/// Report the disk usage of every tool version in the tools directory.
+///
+/// If the total exceeds `budget` MiB, a warning is printed with the least recently used
+/// installations which would need to be pruned to get back under the budget.
+///
+/// `None` means that the configured budget will be used, if any.
+pub fn run(session: &mut Session, budget: Option<u64>) -> Result<()> {
+    let budget = budget.or(session.config().tools().disk_budget());
+
+    let toolset = session.toolset();
+    let rows = toolset
+        .disk_usage()?
+        .into_iter()
+        .map(|(name, version, size)| {
+            // Directories which aren't in the manifest have no last used date.
+            let used = version.parse::<Version>().ok().and_then(|v| {
+                let installation = toolset.manifest().tool(&name)?.get(&v)?;
+                Some(installation.used())
+            });
+            (name, version, size, used)
+        })
+        .collect::<Vec<_>>();
+
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    let nlen = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+    let vlen = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(7);
+    let h = TABLE_HEADER;
+    eprintln!(
+        "{h}{}{h:#}  {h}{}{h:#}  {h}Size{h:#}        {h}Last used{h:#}",
+        cell("Name", nlen),
+        cell("Version", vlen)
+    );
+    for (name, version, size, used) in &rows {
+        let used = used.map(|d| d.to_string());
+        eprintln!(
+            "{}  {}  {}  {}",
+            cell(name, nlen),
+            cell(version, vlen),
+            cell(&format_size(*size), 10),
+            used.as_deref().unwrap_or("Untracked")
+        );
+    }
+
+    let total = rows.iter().map(|r| r.2).sum::<u64>();
+    let h = HEADER;
+    eprintln!();
+    eprintln!(
+        "       {h}Total{h:#} {} in {} tool installations",
+        format_size(total),
+        rows.len()
+    );
+
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+    let budget_bytes = budget.saturating_mul(MIB);
+    if total <= budget_bytes {
+        eprintln!(
+            "    {h}Verified{h:#} disk usage is within the budget of {}",
+            format_size(budget_bytes)
+        );
+        return Ok(());
+    }
+
+    // Suggest the least recently used installations until the usage is back under the budget.
+    let mut tracked = rows
+        .iter()
+        .filter_map(|(name, version, size, used)| Some((used.as_ref()?, name, version, *size)))
+        .collect::<Vec<_>>();
+    tracked.sort();
+    let mut remaining = total;
+    let mut candidates = Vec::new();
+    for (used, name, version, size) in tracked {
+        if remaining <= budget_bytes {
+            break;
+        }
+        remaining -= size;
+        candidates.push((*used, name, version));
+    }
+
+    let mut msg = format!(
+        "tool installations use {}, which exceeds the budget of {}",
+        format_size(total),
+        format_size(budget_bytes)
+    );
+    if !candidates.is_empty() {
+        msg.push_str("\nleast recently used installations to prune:");
+        for (used, name, version) in &candidates {
+            msg.push_str(&format!("\n  {name} {version} (last used {used})"));
+        }
+        if let Some(days) = prune_days(&candidates) {
+            msg.push_str(&format!(
+                "\nrun `prep tools prune --days {days}` to remove them"
+            ));
+        }
+    }
+    ui::print_warn(&msg);
+
+    Ok(())
+}
+
+/// Returns the number of days for `prep tools prune` which removes all the `candidates`.
+///
+/// `None` if one of them was used today, as then it can only be uninstalled explicitly.
+fn prune_days(candidates: &[(Date, &String, &String)]) -> Option<u32> {
+    let newest = candidates.iter().map(|c| c.0).max()?;
+    let today = UtcDateTime::now().date();
+    let days = (today - newest).whole_days() - 1;
+    u32::try_from(days).ok()
+}
@@ -0,0 +1,127 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::{self, Path};
+use std::{env, fs, process};
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use semver::Version;
+
+use crate::hash;
+use crate::session::Session;
+use crate::tools::tar::Tar;
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// The name of the manifest snapshot inside the archive.
+pub const MANIFEST_NAME: &str = "manifest.toml";
+
+/// Bundle tool installations and a snapshot of their manifest entries into the gzipped tarball `archive`.
+///
+/// The `tools` are either names, which select all their installations, or `name@version`.
+/// All the installations are exported when `tools` is empty.
+/// Installations outside of the tools directory can't be exported and are skipped.
+pub fn run(session: &mut Session, archive: &Path, tools: &[String]) -> Result<()> {
+    let toolset = session.toolset();
+    let mut entries = Vec::new();
+    for (name, version) in toolset.installations() {
+        let selected = tools.is_empty()
+            || tools.iter().any(|tool| match tool.split_once('@') {
+                Some((n, v)) => n == name && Version::parse(v).is_ok_and(|v| v == version),
+                None => *tool == name,
+            });
+        if selected {
+            entries.push((name, version));
+        }
+    }
+    for tool in tools {
+        let name = tool.split_once('@').map_or(tool.as_str(), |(n, _)| n);
+        if !entries.iter().any(|(n, _)| n == name) {
+            bail!("{tool} has no managed installations");
+        }
+    }
+
+    let manifest = toolset.manifest();
+    let mut exported = Vec::new();
+    for (name, version) in entries {
+        match manifest.path(&name, &version) {
+            Some(path) if path.is_relative() => exported.push((name, version)),
+            _ => ui::print_warn(&format!(
+                "skipping {name} {version}, which is installed outside of the tools directory"
+            )),
+        }
+    }
+    if exported.is_empty() {
+        let h = HEADER;
+        eprintln!("    {h}Finished{h:#} no tool installations to export");
+        return Ok(());
+    }
+
+    // The archive must be self-verifying, so hash the installations recorded without one.
+    let mut snapshot = manifest.subset(&exported);
+    for (name, version) in &exported {
+        if manifest.sha256(name, version).is_none() {
+            let path = toolset
+                .installed_path(name, version)
+                .expect("exported installations are in the manifest");
+            snapshot.set_sha256(name, version, hash::sha256_file(&path)?);
+        }
+    }
+    let snapshot_toml =
+        toml::to_string(&snapshot).context("failed to generate tool manifest TOML")?;
+    let staging_dir = env::temp_dir().join(format!("prep-export-{}", process::id()));
+    fs::create_dir_all(&staging_dir).context(format!(
+        "failed to create directory '{}'",
+        staging_dir.display()
+    ))?;
+    let snapshot_path = staging_dir.join(MANIFEST_NAME);
+    fs::write(&snapshot_path, snapshot_toml).context(format!(
+        "failed to write tool manifest snapshot '{}'",
+        snapshot_path.display()
+    ))?;
+
+    // Tar changes the directory for the members, so the archive path must not be relative.
+    let archive = path::absolute(archive).context(format!(
+        "failed to resolve archive path '{}'",
+        archive.display()
+    ))?;
+    let tar = toolset.get::<Tar>(&(), None)?;
+    let mut cmd = tar.cmd();
+    cmd.arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&staging_dir)
+        .arg(MANIFEST_NAME)
+        .arg("-C")
+        .arg(toolset.tools_dir());
+    for (name, version) in &exported {
+        cmd.arg(format!("{name}/{version}"));
+    }
+
+    ui::print_cmd(&cmd);
+
+    let status = cmd.status().context("failed to run tar");
+    // Clean up the snapshot, even if creating the archive failed.
+    fs::remove_file(&snapshot_path).context(format!(
+        "failed to remove tool manifest snapshot '{}'",
+        snapshot_path.display()
+    ))?;
+    fs::remove_dir(&staging_dir).context(format!(
+        "failed to remove directory '{}'",
+        staging_dir.display()
+    ))?;
+    let status = status?;
+    ensure!(status.success(), "tar failed: {status}");
+
+    let h = HEADER;
+    for (name, version) in &exported {
+        eprintln!("    {h}Exported{h:#} {name} {version}");
+    }
+    eprintln!(
+        "    {h}Finished{h:#} {} tool installations in '{}'",
+        exported.len(),
+        archive.display()
+    );
+    Ok(())
+}
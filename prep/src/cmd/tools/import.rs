@@ -0,0 +1,72 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::Path;
+use std::{fs, process};
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::cmd::tools::export::MANIFEST_NAME;
+use crate::session::Session;
+use crate::tools::tar::Tar;
+use crate::toolset::Toolset;
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Unpack the tool installations from the `archive` created by `prep tools export`
+/// and register them in the tool manifest.
+///
+/// Installations which already exist are skipped.
+pub fn run(session: &mut Session, archive: &Path) -> Result<()> {
+    ensure!(
+        archive.is_file(),
+        "tool archive '{}' not found",
+        archive.display()
+    );
+    let toolset = session.toolset();
+    let tar = toolset.get::<Tar>(&(), None)?;
+
+    // Unpack inside the tools directory, so the installations can be moved into place.
+    // The directory starts with `temp-` so it's never mistaken for a tool.
+    toolset.ensure_tools_dir()?;
+    let staging_dir = toolset
+        .tools_dir()
+        .join(format!("temp-import-{}", process::id()));
+    fs::create_dir(&staging_dir).context(format!(
+        "failed to create directory '{}'",
+        staging_dir.display()
+    ))?;
+
+    let mut cmd = tar.cmd();
+    cmd.arg("-xzf").arg(archive).arg("-C").arg(&staging_dir);
+
+    ui::print_cmd(&cmd);
+
+    let result = cmd
+        .status()
+        .context("failed to run tar")
+        .and_then(|status| {
+            ensure!(status.success(), "tar failed: {status}");
+            let manifest = Toolset::load_manifest(&staging_dir.join(MANIFEST_NAME))?;
+            toolset.import(&staging_dir, &manifest)
+        });
+    // The staging directory only contains what was unpacked from the archive,
+    // and whatever wasn't moved into place is not needed anymore.
+    fs::remove_dir_all(&staging_dir).context(format!(
+        "failed to remove directory '{}'",
+        staging_dir.display()
+    ))?;
+    let imported = result?;
+
+    let h = HEADER;
+    for (name, version) in &imported {
+        eprintln!("    {h}Imported{h:#} {name} {version}");
+    }
+    eprintln!(
+        "    {h}Finished{h:#} {} tool installations from '{}'",
+        imported.len(),
+        archive.display()
+    );
+    Ok(())
+}
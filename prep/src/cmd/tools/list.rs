@@ -1,29 +1,70 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::Result;
+use std::path::PathBuf;
 
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use time::Date;
+
+use crate::cmd::OutputFormat;
 use crate::session::Session;
 use crate::tools::Tool;
 use crate::tools::cargo::Cargo;
+use crate::tools::cargo_bloat::CargoBloat;
+use crate::tools::cargo_deny::CargoDeny;
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_msrv::CargoMsrv;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::cargo_vet::CargoVet;
+use crate::tools::git_cliff::GitCliff;
+use crate::tools::lychee::Lychee;
 use crate::tools::ripgrep::Ripgrep;
 use crate::tools::rustup::Rustup;
-use crate::toolset::Toolset;
+use crate::tools::taplo::Taplo;
+use crate::tools::wasm_bindgen::WasmBindgen;
+use crate::toolset::{InstallStrategy, Toolset};
 use crate::ui::style::TABLE_HEADER;
 
-/// List information on all the tools in the toolset.
-pub fn run(session: &mut Session) -> Result<()> {
-    let tools = session.config().tools();
+/// Tool information for machine readable output.
+#[derive(Serialize)]
+struct ToolInfo {
+    name: &'static str,
+    required: Option<VersionReq>,
+    default: Option<Version>,
+    installations: Vec<InstallationInfo>,
+}
 
-    let rustup_locked = format!("{}", tools.rustup());
-    let rust_locked = format!("{}", tools.rust());
-    let rg_locked = format!("{}", tools.ripgrep());
+/// Managed tool installation information for machine readable output.
+#[derive(Serialize)]
+struct InstallationInfo {
+    version: Version,
+    path: PathBuf,
+    size: u64,
+    installed: Option<Date>,
+    used: Date,
+    strategy: Option<InstallStrategy>,
+}
 
-    let toolset = session.toolset();
+/// Finds the version of the tool that is used when no specific version is required.
+pub type DefaultFn = fn(&mut Toolset) -> Result<Option<Version>>;
+
+/// List information on all the tools in the toolset.
+///
+/// Only the managed installations of the required versions are listed, unless `all_versions` is set.
+/// The machine readable output always contains all the installations.
+pub fn run(session: &mut Session, format: OutputFormat, all_versions: bool) -> Result<()> {
+    let infos = infos(session)?;
 
-    let rustup_global = default_version::<Rustup>(toolset)?;
-    let rust_global = default_version::<Cargo>(toolset)?;
-    let rg_global = default_version::<Ripgrep>(toolset)?;
+    if format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(&infos).context("failed to generate tools JSON")?;
+        println!("{json}");
+        return Ok(());
+    }
 
     fn cell(s: &str, len: usize) -> String {
         let mut s = String::from(s);
@@ -31,41 +72,229 @@ pub fn run(session: &mut Session) -> Result<()> {
         s
     }
 
-    const NLEN: usize = 7;
-    const LLEN: usize = 16;
-    const GLEN: usize = 15;
+    let none = String::from("None");
+    let nlen = infos.iter().map(|i| i.name.len()).max().unwrap_or(0).max(4);
+    let rows = infos
+        .iter()
+        .map(|info| {
+            let required = info
+                .required
+                .as_ref()
+                .map(|r| r.to_string().trim_start_matches('=').to_string())
+                .unwrap_or_else(|| none.clone());
+            let default = info
+                .default
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| none.clone());
+            (info.name, required, default)
+        })
+        .collect::<Vec<_>>();
+    let rlen = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(16);
 
     let h = TABLE_HEADER;
-    let info = format!(
-        "\
-{h}Name{h:#}     {h}Required version{h:#}  {h}Default version{h:#}
-···{}··········  ···{}···················  ···{}··················
-···{}··········  ···{}···················  ···{}··················
-···{}··········  ···{}···················  ···{}··················
-",
-        cell("Rustup", NLEN),
-        cell(rustup_locked.trim_start_matches('='), LLEN),
-        cell(&rustup_global, GLEN),
-        cell("Rust", NLEN),
-        cell(rust_locked.trim_start_matches('='), LLEN),
-        cell(&rust_global, GLEN),
-        cell("Ripgrep", NLEN),
-        cell(rg_locked.trim_start_matches('='), LLEN),
-        cell(&rg_global, GLEN),
-    )
-    .replace("·", "");
-
-    eprint!("{}", info);
+    eprintln!(
+        "{h}{}{h:#}  {h}{}{h:#}  {h}Default version{h:#}",
+        cell("Name", nlen),
+        cell("Required version", rlen)
+    );
+    for (name, required, default) in &rows {
+        eprintln!("{}  {}  {default}", cell(name, nlen), cell(required, rlen));
+    }
+
+    let installations = infos
+        .iter()
+        .flat_map(|info| {
+            info.installations
+                .iter()
+                .filter(move |i| {
+                    all_versions
+                        || info
+                            .required
+                            .as_ref()
+                            .is_some_and(|r| r.matches(&i.version))
+                })
+                .map(move |i| (info.name, i))
+        })
+        .collect::<Vec<_>>();
+    if installations.is_empty() {
+        return Ok(());
+    }
+
+    let rows = installations
+        .into_iter()
+        .map(|(name, i)| {
+            let installed = i
+                .installed
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "Unknown".into());
+            (
+                name,
+                i.version.to_string(),
+                format_size(i.size),
+                installed,
+                i.used.to_string(),
+                i.path.display().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let vlen = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(7);
+
+    eprintln!();
+    eprintln!(
+        "{h}{}{h:#}  {h}{}{h:#}  {h}Size{h:#}        {h}Installed{h:#}   {h}Last used{h:#}   {h}Path{h:#}",
+        cell("Name", nlen),
+        cell("Version", vlen)
+    );
+    for (name, version, size, installed, used, path) in rows {
+        eprintln!(
+            "{}  {}  {}  {}  {}  {path}",
+            cell(name, nlen),
+            cell(&version, vlen),
+            cell(&size, 10),
+            cell(&installed, 10),
+            cell(&used, 10)
+        );
+    }
 
     Ok(())
 }
 
-fn default_version<T: Tool>(toolset: &mut Toolset) -> Result<String> {
+/// Returns the information on all the tools in the toolset.
+fn infos(session: &mut Session) -> Result<Vec<ToolInfo>> {
+    let tools = tools(session);
+    let toolset = session.toolset();
+    let mut infos = Vec::new();
+    for (name, required, default_fn) in tools {
+        let default = default_fn(toolset)?;
+        let mut installations = Vec::new();
+        if let Some(tool) = toolset.manifest().tool(name) {
+            for (version, installation) in tool {
+                installations.push(InstallationInfo {
+                    version: version.clone(),
+                    path: toolset
+                        .installed_path(name, version)
+                        .unwrap_or_else(|| installation.path().to_path_buf()),
+                    // The size is informational, so a broken installation shouldn't fail the listing.
+                    size: toolset.installed_size(name, version).unwrap_or(0),
+                    installed: installation.installed(),
+                    used: installation.used(),
+                    strategy: installation.strategy(),
+                });
+            }
+        }
+        infos.push(ToolInfo {
+            name,
+            required,
+            default,
+            installations,
+        });
+    }
+    Ok(infos)
+}
+
+/// Returns the names and the configured version requirements of all the tools,
+/// along with how to find their default versions.
+pub fn tools(session: &Session) -> [(&'static str, Option<VersionReq>, DefaultFn); 15] {
+    let tools_cfg = session.config().tools();
+    let wasm_cfg = session.config().wasm();
+    [
+        (
+            Rustup::NAME,
+            Some(tools_cfg.rustup().clone()),
+            find_default_version::<Rustup>,
+        ),
+        (
+            "rust",
+            Some(tools_cfg.rust().clone()),
+            find_default_version::<Cargo>,
+        ),
+        (
+            Ripgrep::NAME,
+            Some(tools_cfg.ripgrep().clone()),
+            find_default_version::<Ripgrep>,
+        ),
+        (
+            Taplo::NAME,
+            Some(tools_cfg.taplo().clone()),
+            find_default_version::<Taplo>,
+        ),
+        (
+            GitCliff::NAME,
+            Some(tools_cfg.git_cliff().clone()),
+            find_default_version::<GitCliff>,
+        ),
+        (
+            CargoVet::NAME,
+            Some(tools_cfg.cargo_vet().clone()),
+            find_default_version::<CargoVet>,
+        ),
+        (
+            CargoSort::NAME,
+            Some(tools_cfg.cargo_sort().clone()),
+            find_default_version::<CargoSort>,
+        ),
+        (
+            CargoNextest::NAME,
+            Some(tools_cfg.cargo_nextest().clone()),
+            find_default_version::<CargoNextest>,
+        ),
+        (
+            Lychee::NAME,
+            Some(tools_cfg.lychee().clone()),
+            find_default_version::<Lychee>,
+        ),
+        (
+            CargoGeiger::NAME,
+            Some(tools_cfg.cargo_geiger().clone()),
+            find_default_version::<CargoGeiger>,
+        ),
+        (
+            CargoHakari::NAME,
+            Some(tools_cfg.cargo_hakari().clone()),
+            find_default_version::<CargoHakari>,
+        ),
+        (
+            CargoBloat::NAME,
+            Some(tools_cfg.cargo_bloat().clone()),
+            find_default_version::<CargoBloat>,
+        ),
+        (
+            CargoMsrv::NAME,
+            Some(tools_cfg.cargo_msrv().clone()),
+            find_default_version::<CargoMsrv>,
+        ),
+        (
+            CargoDeny::NAME,
+            Some(tools_cfg.cargo_deny().clone()),
+            find_default_version::<CargoDeny>,
+        ),
+        (
+            WasmBindgen::NAME,
+            wasm_cfg.bindgen().cloned(),
+            find_default_version::<WasmBindgen>,
+        ),
+    ]
+}
+
+/// Returns the `bytes` formatted with a binary unit.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn find_default_version<T: Tool>(toolset: &mut Toolset) -> Result<Option<Version>> {
     let deps = T::Deps::default();
     let binctx = T::default_binctx(toolset, &deps)?;
-    let version = toolset
-        .version::<T>(&binctx)?
-        .map(|v| format!("{v}"))
-        .unwrap_or_else(|| "None".into());
-    Ok(version)
+    toolset.version::<T>(&binctx)
 }
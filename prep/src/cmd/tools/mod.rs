@@ -1,4 +1,12 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub mod clean_temp;
+pub mod du;
+pub mod export;
+pub mod import;
 pub mod list;
+pub mod outdated;
+pub mod prune;
+pub mod uninstall;
+pub mod verify;
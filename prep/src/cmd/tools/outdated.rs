@@ -0,0 +1,147 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::Result;
+use semver::Version;
+
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo;
+use crate::tools::cargo_bloat::CargoBloat;
+use crate::tools::cargo_deny::CargoDeny;
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_msrv::CargoMsrv;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::cargo_vet::CargoVet;
+use crate::tools::git_cliff::GitCliff;
+use crate::tools::lychee::Lychee;
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::taplo::Taplo;
+use crate::ui::style::{HEADER, LITERAL, NOTE, TABLE_HEADER};
+
+/// Report which of the managed tools have newer releases on crates.io than the configured versions.
+///
+/// Set `toml` to `true` to also print the config changes needed to update them.
+pub fn run(session: &mut Session, toml: bool) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let tools = [
+        (Ripgrep::NAME, Ripgrep::PACKAGE, tools_cfg.ripgrep().clone()),
+        (Taplo::NAME, Taplo::PACKAGE, tools_cfg.taplo().clone()),
+        (
+            GitCliff::NAME,
+            GitCliff::PACKAGE,
+            tools_cfg.git_cliff().clone(),
+        ),
+        (
+            CargoVet::NAME,
+            CargoVet::PACKAGE,
+            tools_cfg.cargo_vet().clone(),
+        ),
+        (
+            CargoSort::NAME,
+            CargoSort::PACKAGE,
+            tools_cfg.cargo_sort().clone(),
+        ),
+        (
+            CargoNextest::NAME,
+            CargoNextest::PACKAGE,
+            tools_cfg.cargo_nextest().clone(),
+        ),
+        (Lychee::NAME, Lychee::PACKAGE, tools_cfg.lychee().clone()),
+        (
+            CargoGeiger::NAME,
+            CargoGeiger::PACKAGE,
+            tools_cfg.cargo_geiger().clone(),
+        ),
+        (
+            CargoHakari::NAME,
+            CargoHakari::PACKAGE,
+            tools_cfg.cargo_hakari().clone(),
+        ),
+        (
+            CargoBloat::NAME,
+            CargoBloat::PACKAGE,
+            tools_cfg.cargo_bloat().clone(),
+        ),
+        (
+            CargoMsrv::NAME,
+            CargoMsrv::PACKAGE,
+            tools_cfg.cargo_msrv().clone(),
+        ),
+        (
+            CargoDeny::NAME,
+            CargoDeny::PACKAGE,
+            tools_cfg.cargo_deny().clone(),
+        ),
+    ];
+
+    let mut outdated = Vec::new();
+    let mut rows = Vec::new();
+    for (name, package, ver_req) in tools {
+        let latest = latest_version(session, package)?;
+        let is_outdated = latest.as_ref().is_some_and(|v| !ver_req.matches(v));
+        if is_outdated && let Some(latest) = &latest {
+            outdated.push((name, latest.clone()));
+        }
+        let latest = latest
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "None".into());
+        rows.push((name, ver_req, latest, is_outdated));
+    }
+
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    const NLEN: usize = 13;
+    const LLEN: usize = 16;
+
+    let (h, n) = (TABLE_HEADER, NOTE);
+    eprintln!("{h}Name{h:#}           {h}Required version{h:#}  {h}Latest version{h:#}");
+    for (name, ver_req, latest, is_outdated) in rows {
+        let ver_req = format!("{ver_req}");
+        let latest = if is_outdated {
+            format!("{n}{latest}{n:#}")
+        } else {
+            latest
+        };
+        eprintln!(
+            "{}  {}  {}",
+            cell(name, NLEN),
+            cell(ver_req.trim_start_matches('='), LLEN),
+            latest
+        );
+    }
+
+    if outdated.is_empty() {
+        let h = HEADER;
+        eprintln!("\n    {h}Verified{h:#} all tool versions are up to date.");
+    } else if toml {
+        let l = LITERAL;
+        eprintln!("\n{n}Update the [tools] section of prep.toml to:{n:#}");
+        for (name, latest) in outdated {
+            // The config keys use underscores, e.g. `git_cliff` for git-cliff.
+            let key = name.replace('-', "_");
+            eprintln!("{l}{key} = \"={latest}\"{l:#}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the latest stable non-yanked version of `package` published on crates.io.
+///
+/// Returns `None` if there are no such versions.
+pub fn latest_version(session: &mut Session, package: &str) -> Result<Option<Version>> {
+    let versions = cargo::published_versions(session.toolset(), package)?;
+    let latest = versions
+        .into_iter()
+        .filter(|version| version.pre.is_empty())
+        .max();
+    Ok(latest)
+}
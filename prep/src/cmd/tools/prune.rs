@@ -0,0 +1,58 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::Result;
+use semver::Version;
+
+use crate::session::Session;
+use crate::ui::prompt;
+use crate::ui::style::HEADER;
+
+/// The default number of unused days after which tool installations are pruned.
+pub const MAX_AGE_DEFAULT: u32 = 90;
+
+/// Remove the tool installations which haven't been used in the last `days`.
+///
+/// `None` means that the configured or default max age will be used.
+///
+/// The installations are listed and the removal is confirmed first when run in a terminal.
+pub fn run(session: &mut Session, days: Option<u32>) -> Result<()> {
+    let max_age = days
+        .or(session.config().tools().prune_after())
+        .unwrap_or(MAX_AGE_DEFAULT);
+
+    let h = HEADER;
+    let toolset = session.toolset();
+    let stale = toolset.stale(max_age);
+    if stale.is_empty() {
+        eprintln!("    {h}Finished{h:#} no tool installations unused for {max_age} days");
+        return Ok(());
+    }
+
+    if prompt::will_ask() {
+        for (name, version) in &stale {
+            eprintln!("      {h}Unused{h:#} {name} {version}");
+        }
+    }
+    let question = format!(
+        "Remove {} tool installations unused for {max_age} days?",
+        stale.len()
+    );
+    if !prompt::confirm(&question, true)? {
+        eprintln!("     {h}Skipped{h:#} keeping the tool installations");
+        return Ok(());
+    }
+
+    print_pruned(&toolset.prune(max_age)?);
+
+    Ok(())
+}
+
+/// Prints the removed tool installations.
+pub fn print_pruned(pruned: &[(String, Version)]) {
+    let h = HEADER;
+    for (name, version) in pruned {
+        eprintln!("     {h}Removed{h:#} {name} {version}");
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Result, bail};
+use semver::Version;
+
+use crate::session::Session;
+use crate::ui::style::HEADER;
+
+/// Remove the managed installation of the tool `name`.
+///
+/// `None` as the version means that all installed versions will be removed.
+///
+/// In `dry_run` mode nothing is removed, only the would-be removals are listed.
+pub fn run(
+    session: &mut Session,
+    name: &str,
+    version: Option<&Version>,
+    dry_run: bool,
+) -> Result<()> {
+    let toolset = session.toolset();
+
+    let versions = match version {
+        Some(version) => vec![version.clone()],
+        None => toolset.installed_versions(name),
+    };
+    if versions.is_empty() {
+        bail!("{name} has no managed installations");
+    }
+
+    let h = HEADER;
+    for version in versions {
+        let Some(path) = toolset.installed_path(name, &version) else {
+            bail!("{name} {version} has no managed installation");
+        };
+        if dry_run {
+            eprintln!(
+                "{h}Would remove{h:#} {name} {version} at '{}'",
+                path.display()
+            );
+        } else {
+            toolset.uninstall(name, &version)?;
+            eprintln!(
+                "     {h}Removed{h:#} {name} {version} at '{}'",
+                path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
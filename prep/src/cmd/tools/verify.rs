@@ -0,0 +1,79 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Result, bail};
+
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo_bloat::CargoBloat;
+use crate::tools::cargo_deny::CargoDeny;
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_msrv::CargoMsrv;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::cargo_vet::CargoVet;
+use crate::tools::git_cliff::GitCliff;
+use crate::tools::lychee::Lychee;
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::taplo::Taplo;
+use crate::tools::wasm_bindgen::WasmBindgen;
+use crate::toolset::InstallStatus;
+use crate::ui;
+use crate::ui::style::{ERROR, HEADER};
+
+/// Verify the integrity of all the tool installations in the manifest.
+///
+/// In `repair` mode broken installations are removed.
+pub fn run(session: &mut Session, repair: bool) -> Result<()> {
+    let toolset = session.toolset();
+
+    let (h, e) = (HEADER, ERROR);
+    let mut broken = 0;
+    for (name, version) in toolset.installations() {
+        let status = match name.as_str() {
+            CargoGeiger::NAME => toolset.check::<CargoGeiger>(&version)?,
+            CargoHakari::NAME => toolset.check::<CargoHakari>(&version)?,
+            CargoBloat::NAME => toolset.check::<CargoBloat>(&version)?,
+            CargoMsrv::NAME => toolset.check::<CargoMsrv>(&version)?,
+            CargoDeny::NAME => toolset.check::<CargoDeny>(&version)?,
+            CargoNextest::NAME => toolset.check::<CargoNextest>(&version)?,
+            CargoSort::NAME => toolset.check::<CargoSort>(&version)?,
+            CargoVet::NAME => toolset.check::<CargoVet>(&version)?,
+            GitCliff::NAME => toolset.check::<GitCliff>(&version)?,
+            Lychee::NAME => toolset.check::<Lychee>(&version)?,
+            Ripgrep::NAME => toolset.check::<Ripgrep>(&version)?,
+            Taplo::NAME => toolset.check::<Taplo>(&version)?,
+            WasmBindgen::NAME => toolset.check::<WasmBindgen>(&version)?,
+            _ => {
+                ui::print_warn(&format!("unknown tool {name} {version} in the manifest"));
+                continue;
+            }
+        };
+        let problem = match status {
+            InstallStatus::Valid => {
+                eprintln!("    {h}Verified{h:#} {name} {version}");
+                continue;
+            }
+            InstallStatus::Missing => "binary not found".to_string(),
+            InstallStatus::WrongVersion(actual) => format!("binary reports version {actual}"),
+            InstallStatus::HashMismatch(actual) => {
+                format!("binary SHA-256 hash changed to {actual}")
+            }
+        };
+        eprintln!("      {e}Broken{e:#} {name} {version}: {problem}");
+        if repair {
+            toolset.uninstall(&name, &version)?;
+            eprintln!("     {h}Removed{h:#} {name} {version}");
+        } else {
+            broken += 1;
+        }
+    }
+
+    if broken > 0 {
+        bail!("found {broken} broken tool installations, use --repair to remove them");
+    }
+
+    Ok(())
+}
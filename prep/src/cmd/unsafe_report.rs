@@ -0,0 +1,319 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, bail, ensure};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::metadata;
+use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_geiger::{CargoGeiger, CargoGeigerDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::{HEADER, LITERAL, NOTE, TABLE_HEADER};
+
+/// The file where the unsafe code baseline is stored, inside the prep directory.
+const BASELINE_FILE: &str = "unsafe-baseline.json";
+
+/// The parts of the cargo-geiger JSON report which are used.
+#[derive(Deserialize)]
+struct GeigerReport {
+    packages: Vec<GeigerEntry>,
+}
+
+/// The unsafe code usage of a single package.
+#[derive(Deserialize)]
+struct GeigerEntry {
+    package: GeigerPackage,
+    unsafety: Unsafety,
+}
+
+/// Package information of a cargo-geiger report entry.
+#[derive(Deserialize)]
+struct GeigerPackage {
+    id: GeigerPackageId,
+}
+
+/// Package identifier of a cargo-geiger report entry.
+#[derive(Deserialize)]
+struct GeigerPackageId {
+    name: String,
+    version: Version,
+}
+
+/// Unsafe code counters of a package.
+#[derive(Deserialize)]
+struct Unsafety {
+    /// Counters of the code used by the build.
+    used: Counters,
+    /// Counters of the code not used by the build, e.g. due to disabled features.
+    unused: Counters,
+    /// Whether the crate has `#![forbid(unsafe_code)]`.
+    forbids_unsafe: bool,
+}
+
+/// Counters of the code items of a package.
+#[derive(Deserialize)]
+struct Counters {
+    functions: Count,
+    exprs: Count,
+    item_impls: Count,
+    item_traits: Count,
+    methods: Count,
+}
+
+impl Counters {
+    /// Returns the total number of unsafe items.
+    fn unsafe_total(&self) -> u64 {
+        [
+            &self.functions,
+            &self.exprs,
+            &self.item_impls,
+            &self.item_traits,
+            &self.methods,
+        ]
+        .iter()
+        .map(|c| c.unsafe_)
+        .sum()
+    }
+}
+
+/// Safe and unsafe counts of a code item kind.
+#[derive(Deserialize)]
+struct Count {
+    unsafe_: u64,
+}
+
+/// Recorded unsafe item counts of the workspace packages, keyed by the package name.
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    packages: BTreeMap<String, u64>,
+}
+
+/// A row of the unsafe code report.
+struct Row {
+    name: String,
+    version: Version,
+    used: u64,
+    unused: u64,
+    forbids: bool,
+    workspace: bool,
+}
+
+/// Report the unsafe code usage of the workspace packages and their dependencies with cargo-geiger.
+///
+/// In `strict` mode Cargo and cargo-geiger versions are locked.
+///
+/// If `deny_new` is set in `[unsafe]` of `prep.toml`, this fails when a workspace package
+/// uses more unsafe code than recorded in the baseline.
+/// Set `update_baseline` to record the current usage as the new baseline.
+pub fn run(session: &mut Session, strict: bool, update_baseline: bool) -> Result<()> {
+    let tools_cfg = session.config().tools();
+    let (cargo, geiger) = if strict {
+        let rustup_ver_req = tools_cfg.rustup().clone();
+        let cargo_ver_req = tools_cfg.rust().clone();
+        let ver_req = tools_cfg.cargo_geiger().clone();
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+        let cargo = toolset.get::<Cargo>(&cargo_deps, &cargo_ver_req)?;
+        let deps = CargoGeigerDeps::new(cargo_deps, cargo_ver_req);
+        (cargo, toolset.get::<CargoGeiger>(&deps, &ver_req)?)
+    } else {
+        let toolset = session.toolset();
+        let cargo_deps = CargoDeps::new(None, vec![]);
+        let cargo = toolset.get::<Cargo>(&cargo_deps, None)?;
+        let deps = CargoGeigerDeps::new(cargo_deps, None);
+        (cargo, toolset.get::<CargoGeiger>(&deps, None)?)
+    };
+
+    let metadata = metadata::fetch(session)?;
+    let members = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|p| (p.name.to_string(), p.manifest_path.clone()))
+        .collect::<Vec<_>>();
+
+    // cargo-geiger only scans a single package, so the reports are merged.
+    let mut rows: BTreeMap<(String, Version), Row> = BTreeMap::new();
+    for (_, manifest_path) in &members {
+        let geiger = geiger.clone().env(&cargo::toolchain_vars(&cargo));
+        let mut cmd = cmd(&geiger, manifest_path.as_std_path());
+
+        ui::print_cmd(&cmd);
+
+        let output = cmd
+            .output()
+            .context(format!("failed to run {}", CargoGeiger::NAME))?;
+        ensure!(
+            output.status.success(),
+            "{} failed: {}\n{}",
+            CargoGeiger::NAME,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: GeigerReport = serde_json::from_slice(&output.stdout)
+            .context(format!("failed to parse {} JSON", CargoGeiger::NAME))?;
+        for entry in report.packages {
+            let id = entry.package.id;
+            let workspace = members.iter().any(|(name, _)| *name == id.name);
+            rows.entry((id.name.clone(), id.version.clone()))
+                .or_insert(Row {
+                    name: id.name,
+                    version: id.version,
+                    used: entry.unsafety.used.unsafe_total(),
+                    unused: entry.unsafety.unused.unsafe_total(),
+                    forbids: entry.unsafety.forbids_unsafe,
+                    workspace,
+                });
+        }
+    }
+
+    print_report(rows.values());
+
+    let counts = rows
+        .values()
+        .filter(|r| r.workspace)
+        .map(|r| (r.name.clone(), r.used))
+        .collect::<BTreeMap<_, _>>();
+    let baseline_path = session.prep_dir().join(BASELINE_FILE);
+    if update_baseline {
+        session.ensure_prep_dir()?;
+        save_baseline(&baseline_path, Baseline { packages: counts })?;
+        let h = HEADER;
+        eprintln!(
+            "     {h}Updated{h:#} unsafe code baseline '{}'",
+            baseline_path.display()
+        );
+    } else if session.config().unsafe_code().deny_new() {
+        let baseline = load_baseline(&baseline_path)?.unwrap_or(Baseline {
+            packages: BTreeMap::new(),
+        });
+        check_baseline(&baseline, &counts)?;
+        let h = HEADER;
+        eprintln!("    {h}Verified{h:#} workspace packages add no unsafe code over the baseline");
+    }
+
+    Ok(())
+}
+
+/// Returns the cargo-geiger command for the JSON report of the package at `manifest_path`.
+pub fn cmd(geiger: &BinCtx, manifest_path: &Path) -> Command {
+    // The binary is run directly instead of as a Cargo subcommand,
+    // so the first argument must be `geiger`.
+    let mut cmd = geiger.cmd();
+    cmd.args(["geiger", "--locked", "--output-format", "Json"])
+        .arg("--manifest-path")
+        .arg(manifest_path);
+    cmd
+}
+
+/// Prints the unsafe code usage table of the `rows`.
+///
+/// Workspace packages are always listed, dependencies only if they use unsafe code.
+fn print_report<'a>(rows: impl Iterator<Item = &'a Row>) {
+    let mut rows = rows
+        .filter(|r| r.workspace || r.used > 0)
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| {
+        b.workspace
+            .cmp(&a.workspace)
+            .then(b.used.cmp(&a.used))
+            .then(a.name.cmp(&b.name))
+    });
+
+    fn cell(s: &str, len: usize) -> String {
+        let mut s = String::from(s);
+        s.push_str(&" ".repeat(len.saturating_sub(s.len())));
+        s
+    }
+
+    let nlen = rows.iter().map(|r| r.name.len()).max().unwrap_or(0).max(7);
+    let vlen = rows
+        .iter()
+        .map(|r| r.version.to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    let (h, n) = (TABLE_HEADER, NOTE);
+    eprintln!(
+        "{h}{}{h:#}  {h}{}{h:#}  {h}Used{h:#}    {h}Unused{h:#}  {h}Forbids{h:#}",
+        cell("Package", nlen),
+        cell("Version", vlen)
+    );
+    for row in &rows {
+        let name = cell(&row.name, nlen);
+        let name = if row.workspace {
+            format!("{n}{name}{n:#}")
+        } else {
+            name
+        };
+        eprintln!(
+            "{name}  {}  {}  {}  {}",
+            cell(&row.version.to_string(), vlen),
+            cell(&row.used.to_string(), 6),
+            cell(&row.unused.to_string(), 6),
+            if row.forbids { "yes" } else { "no" }
+        );
+    }
+    let total = rows.iter().map(|r| r.used).sum::<u64>();
+    let with_unsafe = rows.iter().filter(|r| r.used > 0).count();
+    eprintln!(
+        "\n{with_unsafe} packages use {total} unsafe items in total, workspace packages are highlighted."
+    );
+}
+
+/// Loads the unsafe code baseline from `path`, if it exists.
+fn load_baseline(path: &Path) -> Result<Option<Baseline>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read(path).context(format!(
+        "failed to read unsafe code baseline file '{}'",
+        path.display()
+    ))?;
+    let baseline =
+        serde_json::from_slice(&json).context("failed to parse unsafe code baseline JSON")?;
+    Ok(Some(baseline))
+}
+
+/// Saves the unsafe code `baseline` to `path`.
+fn save_baseline(path: &Path, baseline: Baseline) -> Result<()> {
+    let json = serde_json::to_string_pretty(&baseline)
+        .context("failed to generate unsafe code baseline JSON")?;
+    fs::write(path, json).context(format!(
+        "failed to write unsafe code baseline file '{}'",
+        path.display()
+    ))?;
+    Ok(())
+}
+
+/// Verifies that no workspace package in `counts` uses more unsafe items than in the `baseline`.
+///
+/// Packages missing from the baseline are not allowed any unsafe items.
+fn check_baseline(baseline: &Baseline, counts: &BTreeMap<String, u64>) -> Result<()> {
+    let l = LITERAL;
+    let mut msg = String::new();
+    for (name, &count) in counts {
+        let allowed = baseline.packages.get(name).copied().unwrap_or(0);
+        if count > allowed {
+            msg.push_str(&format!(
+                "\n{l}{name}{l:#}: {count} unsafe items, {allowed} in the baseline"
+            ));
+        }
+    }
+    if msg.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "workspace packages introduced new unsafe code:{msg}\n\
+        Review it and run `prep unsafe-report --update-baseline` to accept it"
+    )
+}
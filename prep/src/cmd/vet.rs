@@ -0,0 +1,71 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+
+use crate::session::Session;
+use crate::tools::cargo::{self, Cargo, CargoDeps};
+use crate::tools::cargo_vet::{CargoVet, CargoVetDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Verifies that all the third-party dependencies are audited with cargo-vet.
+///
+/// cargo-vet runs in `--locked` mode, so the imported audits aren't fetched
+/// and the result only depends on the committed `supply-chain` directory.
+pub fn run(session: &mut Session) -> Result<()> {
+    let cargo_vet = cargo_vet(session)?;
+
+    let mut cmd = cargo_vet.cmd();
+    cmd.arg("vet").arg("--locked");
+
+    ui::print_cmd(&cmd);
+
+    let status =
+        ui::capture::status(&mut cmd).context(format!("failed to run {}", CargoVet::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", CargoVet::NAME);
+
+    let h = HEADER;
+    eprintln!("    {h}Verified{h:#} all dependencies are audited");
+    Ok(())
+}
+
+/// Records an audit with `cargo vet certify`, passing `args` through as is.
+pub fn certify(session: &mut Session, args: &[String]) -> Result<()> {
+    let cargo_vet = cargo_vet(session)?;
+
+    let mut cmd = cargo_vet.cmd();
+    cmd.arg("vet").arg("certify").args(args);
+
+    ui::print_cmd(&cmd);
+
+    // Certifying is interactive, so the output isn't captured.
+    let status = cmd
+        .status()
+        .context(format!("failed to run {}", CargoVet::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", CargoVet::NAME);
+
+    Ok(())
+}
+
+/// Returns the locked cargo-vet, which uses the locked Rust toolchain for Cargo metadata.
+///
+/// The binary is run directly instead of as a Cargo subcommand, so the first argument must be `vet`.
+fn cargo_vet(session: &mut Session) -> Result<BinCtx> {
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let rust_ver_req = tools_cfg.rust().clone();
+    let ver_req = tools_cfg.cargo_vet().clone();
+    let toolset = session.toolset();
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), vec![]);
+    let cargo = toolset.get::<Cargo>(&cargo_deps, &rust_ver_req)?;
+    let toolchain = cargo::toolchain_vars(&cargo);
+
+    let cargo_deps = CargoDeps::new(rustup_ver_req, vec![]);
+    let deps = CargoVetDeps::new(cargo_deps, rust_ver_req);
+    let cargo_vet = toolset.get::<CargoVet>(&deps, &ver_req)?;
+    Ok(cargo_vet.env(&toolchain))
+}
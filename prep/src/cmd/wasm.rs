@@ -0,0 +1,115 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::PathBuf;
+
+use anstream::eprintln;
+use anyhow::{Context, Result, ensure};
+use cargo_metadata::Message;
+
+use crate::cmd::Packages;
+use crate::session::Session;
+use crate::tools::Tool;
+use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::rustup;
+use crate::tools::wasm_bindgen::{WasmBindgen, WasmBindgenDeps};
+use crate::ui;
+use crate::ui::style::HEADER;
+
+/// Builds the selected `packages` for the WebAssembly target configured in `prep.toml`.
+///
+/// If no `packages` are selected, then the packages configured in `prep.toml` are built.
+/// The target is added to the locked Rust toolchain with Rustup if it is missing.
+///
+/// When a wasm-bindgen CLI version is configured, the JavaScript bindings are also generated
+/// for every built module, next to it in a `bindgen` directory.
+pub fn run(session: &mut Session, packages: &Packages) -> Result<()> {
+    let wasm_cfg = session.config().wasm();
+    let packages = if packages.is_all() {
+        Packages::new(wasm_cfg.packages().to_vec())
+    } else {
+        packages.clone()
+    };
+    let target = wasm_cfg.target().to_string();
+    let bindgen_ver_req = wasm_cfg.bindgen().cloned();
+
+    let tools_cfg = session.config().tools();
+    let rustup_ver_req = tools_cfg.rustup().clone();
+    let cargo_ver_req = tools_cfg.rust().clone();
+    let rust_components = tools_cfg.rust_components(&[]);
+    let toolset = session.toolset();
+    let cargo_deps = CargoDeps::new(rustup_ver_req.clone(), rust_components.clone());
+    let cargo = toolset.get::<Cargo>(&cargo_deps, &cargo_ver_req)?;
+    rustup::add_targets(
+        toolset,
+        Some(&rustup_ver_req),
+        &cargo,
+        std::slice::from_ref(&target),
+    )?;
+
+    let mut cmd = cargo.cmd();
+    cmd.arg("build")
+        .args(ui::cargo_verbosity_args())
+        .arg("--locked")
+        .args(packages.as_args()?)
+        .args(["--target", &target])
+        .arg("--message-format=json-render-diagnostics");
+
+    ui::print_cmd(&cmd);
+
+    let mut modules = Vec::new();
+    let status = ui::capture::status_lines(&mut cmd, |line| {
+        let Ok(message) = serde_json::from_str::<Message>(line) else {
+            ui::capture::eprint(&format!("{line}\n"));
+            return;
+        };
+        if let Message::CompilerArtifact(artifact) = message {
+            modules.extend(
+                artifact
+                    .filenames
+                    .into_iter()
+                    .filter(|f| f.extension() == Some("wasm"))
+                    .map(PathBuf::from),
+            );
+        }
+    })
+    .context("failed to run cargo build")?;
+    ensure!(status.success(), "cargo build failed: {status}");
+
+    let h = HEADER;
+    eprintln!(
+        "       {h}Built{h:#} {} WebAssembly modules for {target}",
+        modules.len()
+    );
+
+    let Some(bindgen_ver_req) = bindgen_ver_req else {
+        return Ok(());
+    };
+    let cargo_deps = CargoDeps::new(rustup_ver_req, rust_components);
+    let deps = WasmBindgenDeps::new(cargo_deps, cargo_ver_req);
+    let bindgen = session
+        .toolset()
+        .get::<WasmBindgen>(&deps, &bindgen_ver_req)?;
+    for module in modules {
+        let out_dir = module.with_file_name("bindgen");
+        let mut cmd = bindgen.cmd();
+        cmd.args(["--target", "web"])
+            .arg("--out-dir")
+            .arg(&out_dir)
+            .arg(&module);
+
+        ui::print_cmd(&cmd);
+
+        let status = ui::capture::status(&mut cmd)
+            .context(format!("failed to run {}", WasmBindgen::NAME))?;
+        ensure!(
+            status.success(),
+            "{} failed for '{}': {status}",
+            WasmBindgen::NAME,
+            module.display()
+        );
+        eprintln!("   {h}Generated{h:#} bindings in '{}'", out_dir.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use anstream::{eprint, eprintln};
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use notify::{Event, RecursiveMode, Watcher};
+
+use crate::cmd::{CargoTargets, Packages, check, clippy, copyright, format};
+use crate::session::Session;
+use crate::ui;
+use crate::ui::style::HEADER;
+use crate::{files, metadata};
+
+/// How long the files must stop changing before the commands are run.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Commands that can be re-run on changes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WatchCommand {
+    /// Check with Cargo.
+    Check,
+    /// Analyze with Clippy.
+    Clippy,
+    /// Verify formatting.
+    Format,
+    /// Verify copyright headers.
+    Copyright,
+}
+
+/// Watches the project files and re-runs the `commands` whenever they change.
+///
+/// Without any `commands`, `check` and `clippy` are run.
+/// Cargo commands only target the packages with changed files.
+///
+/// Changes are debounced, so that saving many files at once only triggers a single run.
+/// This never returns, unless an error occurs while watching.
+pub fn run(session: &mut Session, commands: &[WatchCommand]) -> Result<()> {
+    let commands = if commands.is_empty() {
+        &[WatchCommand::Check, WatchCommand::Clippy][..]
+    } else {
+        commands
+    };
+    let metadata = metadata::fetch(session)?;
+    let packages = metadata::package_dirs(&metadata)
+        .into_iter()
+        .map(|(package, dir)| (package.name.to_string(), dir))
+        .collect::<Vec<_>>();
+    let root = session.root_dir().to_path_buf();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("failed to start the file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .context(format!("failed to watch '{}'", root.display()))?;
+
+    // Start with a full run, so that the current state is known.
+    let mut files = project_files(&root)?;
+    run_commands(session, commands, &Packages::default());
+
+    loop {
+        let paths = next_changes(&rx)?;
+
+        // Only the project files matter, which includes the ones that were just removed.
+        let current = project_files(&root)?;
+        let changed = paths
+            .into_iter()
+            .filter(|path| contains(&current, path) || contains(&files, path))
+            .collect::<Vec<_>>();
+        files = current;
+        if changed.is_empty() {
+            continue;
+        }
+        let selection = touched_packages(&packages, &changed);
+
+        // Clear the screen and move the cursor to the top left.
+        eprint!("\x1b[2J\x1b[H");
+        let h = HEADER;
+        eprintln!("    {h}Detected{h:#} changes in {} files", changed.len());
+        run_commands(session, commands, &selection);
+
+        // Ignore the changes made by the commands themselves.
+        while rx.try_recv().is_ok() {}
+        files = project_files(&root)?;
+    }
+}
+
+/// Waits for changes and returns the changed paths, once there are none for [`DEBOUNCE_TIMEOUT`].
+///
+/// Files being only read, e.g. by the commands, doesn't count as a change.
+fn next_changes(rx: &Receiver<notify::Result<Event>>) -> Result<BTreeSet<PathBuf>> {
+    let mut paths = BTreeSet::new();
+    loop {
+        // Block until the first change, then until there are no more.
+        let result = if paths.is_empty() {
+            rx.recv().ok()
+        } else {
+            match rx.recv_timeout(DEBOUNCE_TIMEOUT) {
+                Ok(result) => Some(result),
+                Err(RecvTimeoutError::Timeout) => return Ok(paths),
+                Err(RecvTimeoutError::Disconnected) => None,
+            }
+        };
+        let Some(result) = result else {
+            bail!("the file watcher stopped unexpectedly");
+        };
+        let event = result.context("failed to watch for changes")?;
+        if !event.kind.is_access() {
+            paths.extend(event.paths);
+        }
+    }
+}
+
+/// Runs the `commands` on the selected `packages`, reporting errors without stopping.
+fn run_commands(session: &mut Session, commands: &[WatchCommand], packages: &Packages) {
+    for command in commands {
+        let result = match command {
+            WatchCommand::Check => check::run(session, false, false, packages, CargoTargets::All),
+            WatchCommand::Clippy => clippy::run(
+                session,
+                false,
+                packages,
+                CargoTargets::All,
+                &clippy::Options::default(),
+            ),
+            WatchCommand::Format => format::run(session, false, true, false, packages, None),
+            WatchCommand::Copyright => copyright::run(session, false, false),
+        };
+        if let Err(e) = result {
+            ui::print_err(&format!("{e:#}"));
+        }
+    }
+    let h = HEADER;
+    eprintln!("    {h}Watching{h:#} for changes, press Ctrl-C to stop");
+}
+
+/// Returns the selection of the packages which contain any of the `changed` files.
+///
+/// All packages are selected if any file is outside of all of the packages.
+fn touched_packages(packages: &[(String, PathBuf)], changed: &[PathBuf]) -> Packages {
+    let mut touched = Vec::new();
+    for file in changed {
+        let Some((name, _)) = packages.iter().find(|(_, dir)| file.starts_with(dir)) else {
+            return Packages::default();
+        };
+        if !touched.contains(name) {
+            touched.push(name.clone());
+        }
+    }
+    Packages::new(touched)
+}
+
+/// Returns the absolute paths of all the project files in `root`, as found by [`files::collect`].
+fn project_files(root: &Path) -> Result<BTreeSet<PathBuf>> {
+    let files = files::collect(root, &[])?;
+    Ok(files.into_iter().map(|file| root.join(file)).collect())
+}
+
+/// Returns whether `path` is one of the `files`, or a removed directory which contained any of them.
+fn contains(files: &BTreeSet<PathBuf>, path: &Path) -> bool {
+    if files.contains(path) {
+        return true;
+    }
+    // Parent directories get events for the changes of their files, so only removed ones count.
+    !path.exists()
+        && files
+            .range::<Path, _>((Bound::Included(path), Bound::Unbounded))
+            .next()
+            .is_some_and(|file| file.starts_with(path))
+}
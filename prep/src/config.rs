@@ -1,22 +1,128 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use regex::Regex;
 use semver::VersionReq;
 use serde::{Deserialize, Serialize};
+use toml_edit::DocumentMut;
+
+/// Current version of the config layout.
+///
+/// Bump it together with adding a migration to [`MIGRATIONS`] when the layout changes.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Config layout migrations, the one at index `i` upgrades version `i + 1` to version `i + 2`.
+const MIGRATIONS: [fn(&mut DocumentMut); CONFIG_VERSION as usize - 1] = [];
 
 /// Prep configuration.
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    /// Version of the config layout, see [`CONFIG_VERSION`].
+    #[serde(default = "config_version_default", rename = "config-version")]
+    config_version: u32,
     /// Project configuration.
     #[serde(default = "Project::new")]
     project: Project,
+    /// Copyright configuration.
+    #[serde(
+        default = "Copyright::new",
+        skip_serializing_if = "Copyright::is_default"
+    )]
+    copyright: Copyright,
     /// Tools configuration.
     #[serde(default = "Tools::new")]
     tools: Tools,
+    /// Network configuration.
+    #[serde(default = "Network::new", skip_serializing_if = "Network::is_default")]
+    network: Network,
+    /// CI configuration.
+    #[serde(default = "Ci::new", skip_serializing_if = "Ci::is_default")]
+    ci: Ci,
+    /// Test configuration.
+    #[serde(default = "Test::new", skip_serializing_if = "Test::is_default")]
+    test: Test,
+    /// Benchmark configuration.
+    #[serde(default = "Bench::new", skip_serializing_if = "Bench::is_default")]
+    bench: Bench,
+    /// Cross-compilation configuration.
+    #[serde(default = "Cross::new", skip_serializing_if = "Cross::is_default")]
+    cross: Cross,
+    /// WebAssembly configuration.
+    #[serde(default = "Wasm::new", skip_serializing_if = "Wasm::is_default")]
+    wasm: Wasm,
+    /// `no_std` compatibility configuration.
+    #[serde(default = "NoStd::new", skip_serializing_if = "NoStd::is_default")]
+    no_std: NoStd,
+    /// Duplicate dependency policy configuration.
+    #[serde(
+        default = "Duplicates::new",
+        skip_serializing_if = "Duplicates::is_default"
+    )]
+    duplicates: Duplicates,
+    /// Clippy configuration.
+    #[serde(default = "Clippy::new", skip_serializing_if = "Clippy::is_default")]
+    clippy: Clippy,
+    /// README synchronization configuration.
+    #[serde(default = "Readme::new", skip_serializing_if = "Readme::is_default")]
+    readme: Readme,
+    /// Link checking configuration.
+    #[serde(default = "Links::new", skip_serializing_if = "Links::is_default")]
+    links: Links,
+    /// Unsafe code policy configuration.
+    #[serde(
+        default = "UnsafeCode::new",
+        skip_serializing_if = "UnsafeCode::is_default",
+        rename = "unsafe"
+    )]
+    unsafe_code: UnsafeCode,
+    /// Secret scanning configuration.
+    #[serde(default = "Secrets::new", skip_serializing_if = "Secrets::is_default")]
+    secrets: Secrets,
+    /// Binary size configuration.
+    #[serde(default = "Bloat::new", skip_serializing_if = "Bloat::is_default")]
+    bloat: Bloat,
+    /// Git hooks configuration.
+    #[serde(
+        default = "GitHooks::new",
+        skip_serializing_if = "GitHooks::is_default"
+    )]
+    git_hooks: GitHooks,
+    /// Shell commands to run before or after commands, keyed by e.g. `pre-ci` or `post-format`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    hooks: BTreeMap<String, Vec<String>>,
+    /// Named tasks which can be run with `prep run <task>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tasks: BTreeMap<String, Task>,
+    /// Custom checks, which are CI steps just like the built-in ones.
+    #[serde(default, rename = "check", skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<Check>,
+    /// Named profiles which override parts of the configuration.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profile: BTreeMap<String, Profile>,
+}
+
+/// Configuration profile, which overrides the matching keys of the base configuration.
+///
+/// Its contents are validated once merged into the base configuration.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Tools configuration overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tools: Option<toml::Table>,
+    /// CI configuration overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ci: Option<toml::Table>,
 }
 
 /// Project configuration.
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Project {
     /// Project name.
     #[serde(default = "name_default")]
@@ -24,42 +130,561 @@ pub struct Project {
     /// Project License SPDX identifier.
     #[serde(default = "license_default")]
     license: String,
+    /// Minimum supported Rust version, e.g. `1.85` as found by `prep msrv find`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    msrv: Option<String>,
+}
+
+/// Copyright configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Copyright {
+    /// Globs of files to exclude from the copyright header check.
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Network configuration.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Network {
+    /// HTTP(S) proxy URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proxy: Option<String>,
+    /// Mirror base URLs keyed by the original base URL they replace.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    mirrors: BTreeMap<String, String>,
+    /// Timeout in seconds for a single download.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    /// Number of times to retry a failed download.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+}
+
+/// Clippy configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Clippy {
+    /// Whether to analyze with all features enabled, as opposed to the default features.
+    #[serde(default = "all_features_default")]
+    all_features: bool,
+    /// Lints to warn about.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warn: Vec<String>,
+    /// Lints to allow.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allow: Vec<String>,
+    /// Lints to deny.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    deny: Vec<String>,
+    /// Named sets of features to analyze in feature matrix mode, in addition to the built-in ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    feature_sets: BTreeMap<String, Vec<String>>,
+}
+
+/// Git hooks configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitHooks {
+    /// Prep commands to run before committing, e.g. `format --check`.
+    #[serde(default = "pre_commit_default")]
+    pre_commit: Vec<String>,
+    /// Prep commands to run before pushing.
+    #[serde(default = "pre_push_default")]
+    pre_push: Vec<String>,
+}
+
+/// CI configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ci {
+    /// Custom CI pipeline steps, which replace the default pipeline when not empty.
+    #[serde(default, rename = "step", skip_serializing_if = "Vec::is_empty")]
+    steps: Vec<CiStep>,
+    /// Whether to finish with verifying that no step modified the working tree.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    verify_clean_tree: bool,
+}
+
+/// CI pipeline step configuration.
+///
+/// At most one of `builtin`, `cargo`, and `command` can be set.
+/// If none of them are set, then the `name` is used as the built-in step name.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CiStep {
+    /// Step name.
+    name: String,
+    /// Built-in step name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    builtin: Option<String>,
+    /// Cargo arguments to run with the locked Rust toolchain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cargo: Option<Vec<String>>,
+    /// Shell command to run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    /// Whether the step only runs in extended mode.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    extended: bool,
+}
+
+/// Test configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Test {
+    /// Number of partitions that `--partition` splits the tests into, if it only has the index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partitions: Option<u32>,
+    /// Number of times that failing tests are retried with cargo-nextest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+    /// Retry counts of specific tests, which take precedence over `retries`.
+    #[serde(default, rename = "override", skip_serializing_if = "Vec::is_empty")]
+    overrides: Vec<TestOverride>,
+}
+
+/// Benchmark configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bench {
+    /// Maximum allowed slowdown in percent compared to a stored baseline.
+    #[serde(default = "max_regression_default")]
+    max_regression: f64,
+}
+
+/// Test retry configuration of the tests matching a cargo-nextest filterset.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestOverride {
+    /// cargo-nextest filterset, e.g. `test(/^network::/)`.
+    filter: String,
+    /// Number of times that the matching failing tests are retried.
+    retries: u32,
+}
+
+/// Cross-compilation configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cross {
+    /// Target triples to check, e.g. `aarch64-unknown-linux-gnu`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    targets: Vec<String>,
+}
+
+/// WebAssembly configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wasm {
+    /// Packages which support WebAssembly, all workspace packages if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<String>,
+    /// WebAssembly target triple.
+    #[serde(default = "wasm_target_default")]
+    target: String,
+    /// wasm-bindgen CLI version for generating the JavaScript bindings of the built modules.
+    ///
+    /// It must match the version of the `wasm-bindgen` crate in `Cargo.lock`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bindgen: Option<VersionReq>,
+}
+
+/// `no_std` compatibility configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoStd {
+    /// Target triple without `std`, e.g. `thumbv7em-none-eabihf`.
+    #[serde(default = "no_std_target_default")]
+    target: String,
+    /// Packages which must build without `std`, keyed by name,
+    /// with the features to enable in addition to `--no-default-features`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    packages: BTreeMap<String, Vec<String>>,
+}
+
+/// Duplicate dependency policy configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Duplicates {
+    /// Names of the packages which are allowed to be in the dependency graph in multiple versions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allow: Vec<String>,
+}
+
+/// README synchronization configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Readme {
+    /// Names of the packages whose README is generated from the crate docs,
+    /// all packages with README markers if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<String>,
+}
+
+/// Link checking configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Links {
+    /// Regular expressions of the URLs which are not checked, e.g. because they require a login.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exclude: Vec<String>,
+}
+
+/// Unsafe code policy configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnsafeCode {
+    /// Whether to fail when workspace packages use more unsafe code than in the baseline.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    deny_new: bool,
+}
+
+/// Secret scanning configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Secrets {
+    /// Whether `prep ci --extended` also scans for secrets.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    ci: bool,
+    /// Regular expressions of the findings which are not secrets, e.g. documented example keys.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allow: Vec<String>,
+    /// Globs of the files which are not scanned, e.g. test fixtures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exclude: Vec<String>,
+}
+
+/// Binary size configuration.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bloat {
+    /// File size budgets of the release binaries in KiB, keyed by the binary name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    budgets: BTreeMap<String, u64>,
+    /// Maximum file size growth of a release binary over the baseline, in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_growth: Option<f64>,
+}
+
+/// Task configuration.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Task {
+    /// Steps to run in order.
+    #[serde(default, rename = "step", skip_serializing_if = "Vec::is_empty")]
+    steps: Vec<TaskStep>,
+    /// Additional environment variables for all the steps.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// Working directory of the steps, relative to the project root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dir: Option<PathBuf>,
+}
+
+/// Task step configuration.
+///
+/// Exactly one of `builtin`, `cargo`, and `command` must be set.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskStep {
+    /// Built-in CI step name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    builtin: Option<String>,
+    /// Cargo arguments to run with the locked Rust toolchain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cargo: Option<Vec<String>>,
+    /// Shell command to run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+}
+
+/// Custom check configuration.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Check {
+    /// Check name, which is also its CI step name.
+    name: String,
+    /// Shell command to run, which fails the check with a non-zero exit status.
+    command: String,
+    /// Message explaining the failure, e.g. how to fix it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    /// Globs of the files that trigger the check, which is skipped when none of them exist.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    files: Vec<String>,
 }
 
 // TODO: Refactor these away from VersionReq, as Rust toolchain specification is needed instead.
 
 /// Tools configuration.
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Tools {
     /// Rustup configuration.
     #[serde(default = "rustup_default")]
     rustup: VersionReq,
     /// Stable Rust toolchain configuration.
-    #[serde(default = "rust_default")]
-    rust: VersionReq,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rust: Option<VersionReq>,
+    /// Stable Rust toolchain version used when `rust` is not configured.
+    #[serde(skip, default = "rust_default")]
+    rust_fallback: VersionReq,
+    /// Additional Rust toolchain components, e.g. from `rust-toolchain.toml`.
+    #[serde(skip)]
+    rust_components: Vec<String>,
+    /// Nightly Rust toolchain channel, for commands which require nightly features.
+    #[serde(
+        default = "rust_nightly_default",
+        rename = "rust-nightly",
+        skip_serializing_if = "is_rust_nightly_default"
+    )]
+    rust_nightly: String,
     /// Ripgrep configuration.
     #[serde(default = "ripgrep_default")]
     ripgrep: VersionReq,
+    /// Taplo configuration.
+    #[serde(default = "taplo_default")]
+    taplo: VersionReq,
+    /// git-cliff configuration.
+    #[serde(default = "git_cliff_default")]
+    git_cliff: VersionReq,
+    /// cargo-vet configuration.
+    #[serde(default = "cargo_vet_default")]
+    cargo_vet: VersionReq,
+    /// cargo-sort configuration.
+    #[serde(default = "cargo_sort_default")]
+    cargo_sort: VersionReq,
+    /// cargo-nextest configuration.
+    #[serde(default = "cargo_nextest_default")]
+    cargo_nextest: VersionReq,
+    /// Lychee configuration.
+    #[serde(default = "lychee_default")]
+    lychee: VersionReq,
+    /// cargo-geiger configuration.
+    #[serde(default = "cargo_geiger_default")]
+    cargo_geiger: VersionReq,
+    /// cargo-hakari configuration.
+    #[serde(default = "cargo_hakari_default")]
+    cargo_hakari: VersionReq,
+    /// cargo-bloat configuration.
+    #[serde(default = "cargo_bloat_default")]
+    cargo_bloat: VersionReq,
+    /// cargo-msrv configuration.
+    #[serde(default = "cargo_msrv_default")]
+    cargo_msrv: VersionReq,
+    /// cargo-deny configuration.
+    #[serde(default = "cargo_deny_default")]
+    cargo_deny: VersionReq,
+    /// Number of unused days after which `prep tools prune` removes tool installations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prune_after: Option<u32>,
+    /// Disk usage budget of the tools directory in MiB, above which `prep tools du` warns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disk_budget: Option<u64>,
+    /// Whether to verify the SHA-256 hashes of managed tool binaries when they are first used.
+    #[serde(default = "verify_hashes_default")]
+    verify_hashes: bool,
+    /// Directory for tool installations, instead of the OS specific data directory.
+    ///
+    /// This is machine specific, so it is never written to the project config.
+    #[serde(default, skip_serializing)]
+    dir: Option<PathBuf>,
+    /// Additional environment variables for specific tools, keyed by the tool name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, BTreeMap<String, String>>,
 }
 
 impl Config {
     /// Creates a new [`Config`] with default values.
     pub fn new() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             project: Project::new(),
+            copyright: Copyright::new(),
             tools: Tools::new(),
+            network: Network::new(),
+            ci: Ci::new(),
+            test: Test::new(),
+            bench: Bench::new(),
+            cross: Cross::new(),
+            wasm: Wasm::new(),
+            no_std: NoStd::new(),
+            duplicates: Duplicates::new(),
+            clippy: Clippy::new(),
+            readme: Readme::new(),
+            links: Links::new(),
+            unsafe_code: UnsafeCode::new(),
+            secrets: Secrets::new(),
+            bloat: Bloat::new(),
+            git_hooks: GitHooks::new(),
+            hooks: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+            checks: Vec::new(),
+            profile: BTreeMap::new(),
         }
     }
 
+    /// Parses the configuration from `config_toml`.
+    ///
+    /// Unknown keys are rejected with a suggestion of the nearest valid key.
+    pub fn parse(config_toml: &str) -> Result<Self> {
+        toml::from_str(config_toml).map_err(|e| describe_error(config_toml, &e))
+    }
+
+    /// Parses the configuration from TOML files, given in increasing order of precedence.
+    ///
+    /// Tables are merged key by key, while any other value completely replaces the lower one.
+    /// Every file is validated on its own, so that errors point to the correct file.
+    ///
+    /// The `profile` with the given name is merged on top of the result.
+    pub fn parse_layers(files: &[(&Path, &str)], profile: Option<&str>) -> Result<Self> {
+        let mut merged = toml::Table::new();
+        for (path, config_toml) in files {
+            let context = format!("failed to parse config file '{}'", path.display());
+            Self::parse(config_toml).context(context.clone())?;
+            let table = toml::from_str(config_toml).context(context)?;
+            merge(&mut merged, table);
+        }
+        if let Some(profile) = profile {
+            let profiles = merged.get("profile").and_then(|p| p.as_table());
+            let Some(overrides) = profiles
+                .and_then(|p| p.get(profile))
+                .and_then(|p| p.as_table())
+                .cloned()
+            else {
+                let available = profiles
+                    .map(|p| p.keys().cloned().collect::<Vec<_>>().join(", "))
+                    .unwrap_or_default();
+                bail!("config profile '{profile}' not found, available profiles: {available}");
+            };
+            merge(&mut merged, overrides);
+        }
+        let config = toml::Value::Table(merged)
+            .try_into()
+            .context("failed to merge config files and profile")?;
+        Ok(config)
+    }
+
     /// Returns the project configuration.
     pub fn project(&self) -> &Project {
         &self.project
     }
 
+    /// Returns the mutable project configuration.
+    pub fn project_mut(&mut self) -> &mut Project {
+        &mut self.project
+    }
+
+    /// Returns the copyright configuration.
+    pub fn copyright(&self) -> &Copyright {
+        &self.copyright
+    }
+
     /// Returns the tools configuration.
     pub fn tools(&self) -> &Tools {
         &self.tools
     }
+
+    /// Returns the mutable tools configuration.
+    pub fn tools_mut(&mut self) -> &mut Tools {
+        &mut self.tools
+    }
+
+    /// Returns the network configuration.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Returns the CI configuration.
+    pub fn ci(&self) -> &Ci {
+        &self.ci
+    }
+
+    /// Returns the mutable CI configuration.
+    pub fn ci_mut(&mut self) -> &mut Ci {
+        &mut self.ci
+    }
+
+    /// Returns the cross-compilation configuration.
+    pub fn cross(&self) -> &Cross {
+        &self.cross
+    }
+
+    /// Returns the WebAssembly configuration.
+    pub fn wasm(&self) -> &Wasm {
+        &self.wasm
+    }
+
+    /// Returns the test configuration.
+    pub fn test(&self) -> &Test {
+        &self.test
+    }
+
+    /// Returns the benchmark configuration.
+    pub fn bench(&self) -> &Bench {
+        &self.bench
+    }
+
+    /// Returns the `no_std` compatibility configuration.
+    pub fn no_std(&self) -> &NoStd {
+        &self.no_std
+    }
+
+    /// Returns the duplicate dependency policy configuration.
+    pub fn duplicates(&self) -> &Duplicates {
+        &self.duplicates
+    }
+
+    /// Returns the Clippy configuration.
+    pub fn clippy(&self) -> &Clippy {
+        &self.clippy
+    }
+
+    /// Returns the README synchronization configuration.
+    pub fn readme(&self) -> &Readme {
+        &self.readme
+    }
+
+    /// Returns the link checking configuration.
+    pub fn links(&self) -> &Links {
+        &self.links
+    }
+
+    /// Returns the unsafe code policy configuration.
+    pub fn unsafe_code(&self) -> &UnsafeCode {
+        &self.unsafe_code
+    }
+
+    /// Returns the secret scanning configuration.
+    pub fn secrets(&self) -> &Secrets {
+        &self.secrets
+    }
+
+    /// Returns the binary size configuration.
+    pub fn bloat(&self) -> &Bloat {
+        &self.bloat
+    }
+
+    /// Returns the Git hooks configuration.
+    pub fn git_hooks(&self) -> &GitHooks {
+        &self.git_hooks
+    }
+
+    /// Returns the hook commands, keyed by the stage and command, e.g. `pre-ci`.
+    pub fn hooks(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.hooks
+    }
+
+    /// Returns the tasks, keyed by name.
+    pub fn tasks(&self) -> &BTreeMap<String, Task> {
+        &self.tasks
+    }
+
+    /// Returns the custom checks.
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
 }
 
 impl Project {
@@ -68,6 +693,7 @@ impl Project {
         Self {
             name: name_default(),
             license: license_default(),
+            msrv: None,
         }
     }
 
@@ -80,6 +706,507 @@ impl Project {
     pub fn license(&self) -> &str {
         &self.license
     }
+
+    /// Returns the minimum supported Rust version.
+    pub fn msrv(&self) -> Option<&str> {
+        self.msrv.as_deref()
+    }
+
+    /// Sets the project name.
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Sets the project License SPDX identifier.
+    pub fn set_license(&mut self, license: String) {
+        self.license = license;
+    }
+}
+
+impl Copyright {
+    /// Creates a new [`Copyright`] with default values.
+    pub fn new() -> Self {
+        Self {
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the globs of files excluded from the copyright header check.
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+}
+
+impl Clippy {
+    /// Creates a new [`Clippy`] with default values.
+    pub fn new() -> Self {
+        Self {
+            all_features: all_features_default(),
+            warn: Vec::new(),
+            allow: Vec::new(),
+            deny: Vec::new(),
+            feature_sets: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns whether to analyze with all features enabled.
+    pub fn all_features(&self) -> bool {
+        self.all_features
+    }
+
+    /// Returns the lint flags to pass to Clippy, i.e. `-W`, `-A`, and `-D` for each lint.
+    ///
+    /// Later flags take precedence, so denied lints win over allowed ones, which win over warnings.
+    pub fn lint_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for (flag, lints) in [("-W", &self.warn), ("-A", &self.allow), ("-D", &self.deny)] {
+            for lint in lints {
+                args.push(flag.to_string());
+                args.push(lint.clone());
+            }
+        }
+        args
+    }
+
+    /// Returns the named feature sets for the feature matrix mode.
+    ///
+    /// Each set lists the features to enable without the default features.
+    pub fn feature_sets(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.feature_sets
+    }
+}
+
+impl GitHooks {
+    /// Creates a new [`GitHooks`] with default values.
+    pub fn new() -> Self {
+        Self {
+            pre_commit: pre_commit_default(),
+            pre_push: pre_push_default(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the Prep commands to run before committing.
+    pub fn pre_commit(&self) -> &[String] {
+        &self.pre_commit
+    }
+
+    /// Returns the Prep commands to run before pushing.
+    pub fn pre_push(&self) -> &[String] {
+        &self.pre_push
+    }
+}
+
+impl Ci {
+    /// Creates a new [`Ci`] with default values.
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            verify_clean_tree: false,
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the custom CI pipeline steps.
+    ///
+    /// An empty list means that the default pipeline is used.
+    pub fn steps(&self) -> &[CiStep] {
+        &self.steps
+    }
+
+    /// Sets the custom CI pipeline steps.
+    pub fn set_steps(&mut self, steps: Vec<CiStep>) {
+        self.steps = steps;
+    }
+
+    /// Returns `true` if CI finishes with verifying that no step modified the working tree.
+    pub fn verify_clean_tree(&self) -> bool {
+        self.verify_clean_tree
+    }
+}
+
+impl Test {
+    /// Creates a new [`Test`] with default values.
+    pub fn new() -> Self {
+        Self {
+            partitions: None,
+            retries: None,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the default number of test partitions.
+    pub fn partitions(&self) -> Option<u32> {
+        self.partitions
+    }
+
+    /// Returns the number of times that failing tests are retried.
+    pub fn retries(&self) -> Option<u32> {
+        self.retries
+    }
+
+    /// Returns the retry counts of specific tests.
+    pub fn overrides(&self) -> &[TestOverride] {
+        &self.overrides
+    }
+
+    /// Returns `true` if any failing tests are retried.
+    pub fn has_retries(&self) -> bool {
+        self.retries.is_some() || !self.overrides.is_empty()
+    }
+}
+
+impl TestOverride {
+    /// Returns the cargo-nextest filterset of the tests.
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Returns the number of times that the matching failing tests are retried.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+}
+
+impl Bench {
+    /// Creates a new [`Bench`] with default values.
+    pub fn new() -> Self {
+        Self {
+            max_regression: max_regression_default(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the maximum allowed slowdown in percent compared to a stored baseline.
+    pub fn max_regression(&self) -> f64 {
+        self.max_regression
+    }
+}
+
+impl Cross {
+    /// Creates a new [`Cross`] with default values.
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the target triples to check.
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+}
+
+impl Wasm {
+    /// Creates a new [`Wasm`] with default values.
+    pub fn new() -> Self {
+        Self {
+            packages: Vec::new(),
+            target: wasm_target_default(),
+            bindgen: None,
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the packages which support WebAssembly, all workspace packages if empty.
+    pub fn packages(&self) -> &[String] {
+        &self.packages
+    }
+
+    /// Returns the WebAssembly target triple.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the wasm-bindgen CLI version, if the bindings should be generated.
+    pub fn bindgen(&self) -> Option<&VersionReq> {
+        self.bindgen.as_ref()
+    }
+}
+
+impl NoStd {
+    /// Creates a new [`NoStd`] with default values.
+    pub fn new() -> Self {
+        Self {
+            target: no_std_target_default(),
+            packages: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the target triple without `std`.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the packages which must build without `std`, with their features.
+    pub fn packages(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.packages
+    }
+}
+
+impl Duplicates {
+    /// Creates a new [`Duplicates`] with default values.
+    pub fn new() -> Self {
+        Self { allow: Vec::new() }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the names of the packages which are allowed to be in multiple versions.
+    pub fn allow(&self) -> &[String] {
+        &self.allow
+    }
+}
+
+impl Readme {
+    /// Creates a new [`Readme`] with default values.
+    pub fn new() -> Self {
+        Self {
+            packages: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the names of the packages whose README is generated from the crate docs.
+    pub fn packages(&self) -> &[String] {
+        &self.packages
+    }
+}
+
+impl Links {
+    /// Creates a new [`Links`] with default values.
+    pub fn new() -> Self {
+        Self {
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the regular expressions of the URLs which are not checked.
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+}
+
+impl UnsafeCode {
+    /// Creates a new [`UnsafeCode`] with default values.
+    pub fn new() -> Self {
+        Self { deny_new: false }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns `true` if workspace packages must not use more unsafe code than in the baseline.
+    pub fn deny_new(&self) -> bool {
+        self.deny_new
+    }
+}
+
+impl Secrets {
+    /// Creates a new [`Secrets`] with default values.
+    pub fn new() -> Self {
+        Self {
+            ci: false,
+            allow: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns `true` if `prep ci --extended` also scans for secrets.
+    pub fn ci(&self) -> bool {
+        self.ci
+    }
+
+    /// Returns the regular expressions of the findings which are not secrets.
+    pub fn allow(&self) -> &[String] {
+        &self.allow
+    }
+
+    /// Returns the globs of the files which are not scanned.
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+}
+
+impl Bloat {
+    /// Creates a new [`Bloat`] with default values.
+    pub fn new() -> Self {
+        Self {
+            budgets: BTreeMap::new(),
+            max_growth: None,
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the file size budgets of the release binaries in KiB, keyed by the binary name.
+    pub fn budgets(&self) -> &BTreeMap<String, u64> {
+        &self.budgets
+    }
+
+    /// Returns the maximum file size growth of a release binary over the baseline, in percent.
+    ///
+    /// `None` means that growth is not limited.
+    pub fn max_growth(&self) -> Option<f64> {
+        self.max_growth
+    }
+}
+
+impl CiStep {
+    /// Creates a new [`CiStep`] which runs the built-in step with the same `name`.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            builtin: None,
+            cargo: None,
+            command: None,
+            extended: false,
+        }
+    }
+
+    /// Returns the step name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the built-in step name.
+    pub fn builtin(&self) -> Option<&str> {
+        self.builtin.as_deref()
+    }
+
+    /// Returns the Cargo arguments.
+    pub fn cargo(&self) -> Option<&[String]> {
+        self.cargo.as_deref()
+    }
+
+    /// Returns the shell command.
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    /// Returns whether the step only runs in extended mode.
+    pub fn extended(&self) -> bool {
+        self.extended
+    }
+}
+
+impl Task {
+    /// Returns the steps to run in order.
+    pub fn steps(&self) -> &[TaskStep] {
+        &self.steps
+    }
+
+    /// Returns the additional environment variables.
+    pub fn env(&self) -> &BTreeMap<String, String> {
+        &self.env
+    }
+
+    /// Returns the working directory, relative to the project root.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+}
+
+impl TaskStep {
+    /// Returns the built-in CI step name.
+    pub fn builtin(&self) -> Option<&str> {
+        self.builtin.as_deref()
+    }
+
+    /// Returns the Cargo arguments.
+    pub fn cargo(&self) -> Option<&[String]> {
+        self.cargo.as_deref()
+    }
+
+    /// Returns the shell command.
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+}
+
+impl Check {
+    /// Returns the check name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the shell command.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the failure message.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns the globs of the files that trigger the check.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
 }
 
 impl Tools {
@@ -87,8 +1214,27 @@ impl Tools {
     pub fn new() -> Self {
         Self {
             rustup: rustup_default(),
-            rust: rust_default(),
+            rust: None,
+            rust_fallback: rust_default(),
+            rust_components: Vec::new(),
+            rust_nightly: rust_nightly_default(),
             ripgrep: ripgrep_default(),
+            taplo: taplo_default(),
+            git_cliff: git_cliff_default(),
+            cargo_vet: cargo_vet_default(),
+            cargo_sort: cargo_sort_default(),
+            cargo_nextest: cargo_nextest_default(),
+            lychee: lychee_default(),
+            cargo_geiger: cargo_geiger_default(),
+            cargo_hakari: cargo_hakari_default(),
+            cargo_bloat: cargo_bloat_default(),
+            cargo_msrv: cargo_msrv_default(),
+            cargo_deny: cargo_deny_default(),
+            prune_after: None,
+            disk_budget: None,
+            verify_hashes: verify_hashes_default(),
+            dir: None,
+            env: BTreeMap::new(),
         }
     }
 
@@ -97,15 +1243,377 @@ impl Tools {
         &self.rustup
     }
 
+    /// Sets the Rustup version.
+    pub fn set_rustup(&mut self, ver_req: VersionReq) {
+        self.rustup = ver_req;
+    }
+
     /// Returns the configured stable Rust toolchain version.
+    ///
+    /// Falls back to the `rust-toolchain.toml` version, or the Prep default.
     pub fn rust(&self) -> &VersionReq {
-        &self.rust
+        self.rust.as_ref().unwrap_or(&self.rust_fallback)
+    }
+
+    /// Returns the stable Rust toolchain version, only if it is explicitly configured.
+    pub fn rust_configured(&self) -> Option<&VersionReq> {
+        self.rust.as_ref()
+    }
+
+    /// Sets the stable Rust toolchain version used when it is not explicitly configured.
+    pub fn set_rust_fallback(&mut self, ver_req: VersionReq) {
+        self.rust_fallback = ver_req;
+    }
+
+    /// Sets the stable Rust toolchain version.
+    pub fn set_rust(&mut self, ver_req: VersionReq) {
+        self.rust = Some(ver_req);
+    }
+
+    /// Explicitly configures the stable Rust toolchain version that is currently in effect.
+    pub fn pin_rust(&mut self) {
+        self.rust = Some(self.rust().clone());
+    }
+
+    /// Returns the `required` Rust toolchain components, together with the additional ones.
+    pub fn rust_components(&self, required: &[&str]) -> Vec<String> {
+        let mut components = required.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        for component in &self.rust_components {
+            if !components.contains(component) {
+                components.push(component.clone());
+            }
+        }
+        components
+    }
+
+    /// Sets the additional Rust toolchain components.
+    pub fn set_rust_components(&mut self, components: Vec<String>) {
+        self.rust_components = components;
+    }
+
+    /// Returns the configured nightly Rust toolchain channel, e.g. `nightly-2026-01-15`.
+    pub fn rust_nightly(&self) -> &str {
+        &self.rust_nightly
     }
 
     /// Returns the configured ripgrep version.
     pub fn ripgrep(&self) -> &VersionReq {
         &self.ripgrep
     }
+
+    /// Sets the ripgrep version.
+    pub fn set_ripgrep(&mut self, ver_req: VersionReq) {
+        self.ripgrep = ver_req;
+    }
+
+    /// Returns the configured Taplo version.
+    pub fn taplo(&self) -> &VersionReq {
+        &self.taplo
+    }
+
+    /// Sets the Taplo version.
+    pub fn set_taplo(&mut self, ver_req: VersionReq) {
+        self.taplo = ver_req;
+    }
+
+    /// Returns the configured git-cliff version.
+    pub fn git_cliff(&self) -> &VersionReq {
+        &self.git_cliff
+    }
+
+    /// Sets the git-cliff version.
+    pub fn set_git_cliff(&mut self, ver_req: VersionReq) {
+        self.git_cliff = ver_req;
+    }
+
+    /// Returns the configured cargo-vet version.
+    pub fn cargo_vet(&self) -> &VersionReq {
+        &self.cargo_vet
+    }
+
+    /// Sets the cargo-vet version.
+    pub fn set_cargo_vet(&mut self, ver_req: VersionReq) {
+        self.cargo_vet = ver_req;
+    }
+
+    /// Returns the configured cargo-sort version.
+    pub fn cargo_sort(&self) -> &VersionReq {
+        &self.cargo_sort
+    }
+
+    /// Sets the cargo-sort version.
+    pub fn set_cargo_sort(&mut self, ver_req: VersionReq) {
+        self.cargo_sort = ver_req;
+    }
+
+    /// Returns the configured cargo-nextest version.
+    pub fn cargo_nextest(&self) -> &VersionReq {
+        &self.cargo_nextest
+    }
+
+    /// Sets the cargo-nextest version.
+    pub fn set_cargo_nextest(&mut self, ver_req: VersionReq) {
+        self.cargo_nextest = ver_req;
+    }
+
+    /// Returns the configured Lychee version.
+    pub fn lychee(&self) -> &VersionReq {
+        &self.lychee
+    }
+
+    /// Sets the Lychee version.
+    pub fn set_lychee(&mut self, ver_req: VersionReq) {
+        self.lychee = ver_req;
+    }
+
+    /// Returns the configured cargo-geiger version.
+    pub fn cargo_geiger(&self) -> &VersionReq {
+        &self.cargo_geiger
+    }
+
+    /// Sets the cargo-geiger version.
+    pub fn set_cargo_geiger(&mut self, ver_req: VersionReq) {
+        self.cargo_geiger = ver_req;
+    }
+
+    /// Returns the configured cargo-hakari version.
+    pub fn cargo_hakari(&self) -> &VersionReq {
+        &self.cargo_hakari
+    }
+
+    /// Sets the cargo-hakari version.
+    pub fn set_cargo_hakari(&mut self, ver_req: VersionReq) {
+        self.cargo_hakari = ver_req;
+    }
+
+    /// Returns the configured cargo-bloat version.
+    pub fn cargo_bloat(&self) -> &VersionReq {
+        &self.cargo_bloat
+    }
+
+    /// Sets the cargo-bloat version.
+    pub fn set_cargo_bloat(&mut self, ver_req: VersionReq) {
+        self.cargo_bloat = ver_req;
+    }
+
+    /// Returns the configured cargo-msrv version.
+    pub fn cargo_msrv(&self) -> &VersionReq {
+        &self.cargo_msrv
+    }
+
+    /// Sets the cargo-msrv version.
+    pub fn set_cargo_msrv(&mut self, ver_req: VersionReq) {
+        self.cargo_msrv = ver_req;
+    }
+
+    /// Returns the configured cargo-deny version.
+    pub fn cargo_deny(&self) -> &VersionReq {
+        &self.cargo_deny
+    }
+
+    /// Sets the cargo-deny version.
+    pub fn set_cargo_deny(&mut self, ver_req: VersionReq) {
+        self.cargo_deny = ver_req;
+    }
+
+    /// Returns the number of unused days after which `prep tools prune` removes tool installations.
+    ///
+    /// `None` means that automatic pruning is disabled.
+    pub fn prune_after(&self) -> Option<u32> {
+        self.prune_after
+    }
+
+    /// Returns the disk usage budget of the tools directory in MiB.
+    ///
+    /// `None` means that there is no budget.
+    pub fn disk_budget(&self) -> Option<u64> {
+        self.disk_budget
+    }
+
+    /// Returns `true` if the hashes of managed tool binaries are verified when first used.
+    pub fn verify_hashes(&self) -> bool {
+        self.verify_hashes
+    }
+
+    /// Returns the configured tools directory.
+    ///
+    /// `None` means that the OS specific data directory is used.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Returns the additional environment variables for specific tools, keyed by the tool name.
+    pub fn env(&self) -> &BTreeMap<String, BTreeMap<String, String>> {
+        &self.env
+    }
+}
+
+impl Network {
+    /// Creates a new [`Network`] with default values.
+    pub fn new() -> Self {
+        Self {
+            proxy: None,
+            mirrors: BTreeMap::new(),
+            timeout: None,
+            retries: None,
+        }
+    }
+
+    /// Returns whether all values are the defaults, which don't need to be written out.
+    fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns the HTTP(S) proxy URL.
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Returns the download timeout in seconds.
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    /// Returns the number of times to retry a failed download.
+    pub fn retries(&self) -> Option<u32> {
+        self.retries
+    }
+
+    /// Returns the `url` with its base replaced by the configured mirror, if there is one.
+    ///
+    /// When multiple mirrors match, the one with the longest base URL wins.
+    pub fn mirror_url(&self, url: &str) -> String {
+        self.mirrors
+            .iter()
+            .filter(|(base, _)| url.starts_with(base.as_str()))
+            .max_by_key(|(base, _)| base.len())
+            .map(|(base, mirror)| format!("{mirror}{}", &url[base.len()..]))
+            .unwrap_or_else(|| url.to_string())
+    }
+}
+
+/// Upgrades the config layout of `config_toml` to [`CONFIG_VERSION`], preserving comments.
+///
+/// Returns the upgraded TOML, or `None` if it's already up to date.
+/// Invalid TOML is left for [`Config::parse`] to report.
+pub fn migrate(config_toml: &str) -> Result<Option<String>> {
+    let Ok(mut doc) = config_toml.parse::<DocumentMut>() else {
+        return Ok(None);
+    };
+    // Files without a version are in the current layout, just like the default says.
+    let version = match doc.get("config-version") {
+        None => return Ok(None),
+        Some(item) => item
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v > 0)
+            .context("`config-version` must be a positive integer")?,
+    };
+    if version > CONFIG_VERSION {
+        bail!(
+            "config version {version} is newer than the supported {CONFIG_VERSION}, \
+            update Prep with `prep self update`"
+        );
+    }
+    if version == CONFIG_VERSION {
+        return Ok(None);
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(&mut doc);
+    }
+    doc.insert(
+        "config-version",
+        toml_edit::value(i64::from(CONFIG_VERSION)),
+    );
+    Ok(Some(doc.to_string()))
+}
+
+/// Merges the `overrides` into the `base` table, recursing into tables present in both.
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge(base, overrides);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Returns a readable description of the TOML parse error `e` of `config_toml`.
+fn describe_error(config_toml: &str, e: &toml::de::Error) -> anyhow::Error {
+    let re = Regex::new(r"^unknown field `([^`]*)`, expected (?:one of )?(.*)$")
+        .expect("unknown field regex was incorrect");
+    let Some(span) = e.span() else {
+        return anyhow!("{e}");
+    };
+    let Some(captures) = re.captures(e.message().trim()) else {
+        return anyhow!("{e}");
+    };
+    let key = &captures[1];
+    let expected = captures[2]
+        .split(", ")
+        .map(|k| k.trim_matches('`'))
+        .collect::<Vec<_>>();
+
+    let line_idx = config_toml[..span.start].matches('\n').count();
+    let line = config_toml.lines().nth(line_idx).unwrap_or_default();
+    let mut msg = format!(
+        "unknown key `{key}` at line {}:\n  {}",
+        line_idx + 1,
+        line.trim()
+    );
+
+    match nearest(key, &expected) {
+        Some(nearest) => msg.push_str(&format!("\ndid you mean `{nearest}`?")),
+        None => msg.push_str(&format!("\nexpected one of: {}", expected.join(", "))),
+    }
+    anyhow!(msg)
+}
+
+/// Parses a version requirement given on the command line.
+///
+/// Plain versions like `1.93` are treated as exact requirements, the same as they are written in `prep.toml`.
+pub fn parse_ver_req(s: &str) -> Result<VersionReq, semver::Error> {
+    if s.starts_with(|c: char| c.is_ascii_digit()) {
+        VersionReq::parse(&format!("={s}"))
+    } else {
+        VersionReq::parse(s)
+    }
+}
+
+/// Returns the `candidates` entry nearest to `name`, e.g. to suggest it for a typo.
+///
+/// Only candidates which are reasonably close are returned, to avoid nonsensical suggestions.
+pub fn nearest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|c| (edit_distance(name, c), *c))
+        .min()
+        .filter(|(distance, _)| *distance <= name.len().div_ceil(3).max(1))
+        .map(|(_, c)| c)
+}
+
+/// Returns the Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            cur.push(substitution.min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Returns the default config layout version, which is the current one.
+fn config_version_default() -> u32 {
+    CONFIG_VERSION
 }
 
 /// Returns the default project name.
@@ -118,6 +1626,16 @@ fn license_default() -> String {
     "Apache-2.0 OR MIT".into()
 }
 
+/// Returns the default tool binary hash verification choice.
+fn verify_hashes_default() -> bool {
+    true
+}
+
+/// Returns the default Clippy all features choice.
+fn all_features_default() -> bool {
+    true
+}
+
 /// Returns the default Rustup version.
 fn rustup_default() -> VersionReq {
     VersionReq::parse("=1").expect("default rustup version parsing failed")
@@ -128,7 +1646,97 @@ fn rust_default() -> VersionReq {
     VersionReq::parse("=1.93").expect("default rust version parsing failed")
 }
 
+/// Returns the default nightly Rust toolchain channel.
+fn rust_nightly_default() -> String {
+    "nightly".into()
+}
+
+/// Returns `true` if the nightly Rust toolchain `channel` is the default one.
+fn is_rust_nightly_default(channel: &str) -> bool {
+    channel == rust_nightly_default()
+}
+
 /// Returns the default Ripgrep version.
 fn ripgrep_default() -> VersionReq {
     VersionReq::parse("=14.1.1").expect("default ripgrep version parsing failed")
 }
+
+/// Returns the default Taplo version.
+fn taplo_default() -> VersionReq {
+    VersionReq::parse("=0.10.0").expect("default taplo version parsing failed")
+}
+
+/// Returns the default WebAssembly target triple.
+fn wasm_target_default() -> String {
+    "wasm32-unknown-unknown".into()
+}
+
+/// Returns the default maximum benchmark slowdown in percent.
+fn max_regression_default() -> f64 {
+    10.0
+}
+
+/// Returns the default `no_std` target triple.
+fn no_std_target_default() -> String {
+    "thumbv7em-none-eabihf".into()
+}
+
+/// Returns the default git-cliff version.
+fn git_cliff_default() -> VersionReq {
+    VersionReq::parse("=2.14.2").expect("default git-cliff version parsing failed")
+}
+
+/// Returns the default cargo-vet version.
+fn cargo_vet_default() -> VersionReq {
+    VersionReq::parse("=0.10.2").expect("default cargo-vet version parsing failed")
+}
+
+/// Returns the default cargo-sort version.
+fn cargo_sort_default() -> VersionReq {
+    VersionReq::parse("=2.1.4").expect("default cargo-sort version parsing failed")
+}
+
+/// Returns the default cargo-nextest version.
+fn cargo_nextest_default() -> VersionReq {
+    VersionReq::parse("=0.9.148").expect("default cargo-nextest version parsing failed")
+}
+
+/// Returns the default Lychee version.
+fn lychee_default() -> VersionReq {
+    VersionReq::parse("=0.24.2").expect("default lychee version parsing failed")
+}
+
+/// Returns the default cargo-geiger version.
+fn cargo_geiger_default() -> VersionReq {
+    VersionReq::parse("=0.13.0").expect("default cargo-geiger version parsing failed")
+}
+
+/// Returns the default cargo-hakari version.
+fn cargo_hakari_default() -> VersionReq {
+    VersionReq::parse("=0.9.39").expect("default cargo-hakari version parsing failed")
+}
+
+/// Returns the default cargo-bloat version.
+fn cargo_bloat_default() -> VersionReq {
+    VersionReq::parse("=0.12.1").expect("default cargo-bloat version parsing failed")
+}
+
+/// Returns the default cargo-msrv version.
+fn cargo_msrv_default() -> VersionReq {
+    VersionReq::parse("=0.18.4").expect("default cargo-msrv version parsing failed")
+}
+
+/// Returns the default cargo-deny version.
+fn cargo_deny_default() -> VersionReq {
+    VersionReq::parse("=0.18.4").expect("default cargo-deny version parsing failed")
+}
+
+/// Returns the default Prep commands of the Git pre-commit hook.
+fn pre_commit_default() -> Vec<String> {
+    vec!["format --check".into(), "copyright".into()]
+}
+
+/// Returns the default Prep commands of the Git pre-push hook.
+fn pre_push_default() -> Vec<String> {
+    vec!["clippy".into()]
+}
@@ -2,8 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
 use std::process::Command;
 
+use clap::ColorChoice;
+
+use crate::config::Network;
+use crate::ui;
+
 /// Set of environment variables for running a binary.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Environment {
@@ -15,6 +22,12 @@ impl Environment {
     pub fn new() -> Self {
         let mut vars = BTreeMap::new();
         vars.insert("RUSTUP_AUTO_INSTALL".into(), "0".into());
+        // Cargo doesn't know about our color choice, unless it is forwarded.
+        match ui::color::choice() {
+            ColorChoice::Always => _ = vars.insert("CARGO_TERM_COLOR".into(), "always".into()),
+            ColorChoice::Never => _ = vars.insert("CARGO_TERM_COLOR".into(), "never".into()),
+            ColorChoice::Auto => (),
+        }
         Self { vars }
     }
 
@@ -33,6 +46,54 @@ impl Environment {
         self
     }
 
+    /// Sets the proxy, timeout, and retry configuration for tools which access the network.
+    ///
+    /// These are respected by Cargo, rustup, and curl.
+    pub fn network(mut self, network: &Network) -> Self {
+        if let Some(proxy) = network.proxy() {
+            self.vars.insert("http_proxy".into(), proxy.into());
+            self.vars.insert("https_proxy".into(), proxy.into());
+        }
+        if let Some(timeout) = network.timeout() {
+            self.vars
+                .insert("CARGO_HTTP_TIMEOUT".into(), timeout.to_string());
+        }
+        if let Some(retries) = network.retries() {
+            self.vars
+                .insert("CARGO_NET_RETRY".into(), retries.to_string());
+        }
+        self
+    }
+
+    /// Sets additional custom environment variables, e.g. of a task.
+    pub fn custom(mut self, vars: &BTreeMap<String, String>) -> Self {
+        self.vars
+            .extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+
+    /// Puts the `dir` at the front of `PATH`, so that child processes find its binaries first.
+    ///
+    /// The `PATH` of our own process is used as the base, unless it has already been set.
+    pub fn prepend_path(mut self, dir: &Path) -> Self {
+        let current = match self.vars.get("PATH") {
+            Some(path) => Some(path.into()),
+            None => env::var_os("PATH"),
+        };
+        let mut dirs = current
+            .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default();
+        dirs.retain(|d| d != dir);
+        dirs.insert(0, dir.to_path_buf());
+        // Non-Unicode paths can't be represented, so they are left as they are.
+        if let Ok(path) = env::join_paths(dirs)
+            && let Ok(path) = path.into_string()
+        {
+            self.vars.insert("PATH".into(), path);
+        }
+        self
+    }
+
     /// Returns the underlying map.
     pub fn vars(&self) -> &BTreeMap<String, String> {
         &self.vars
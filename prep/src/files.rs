@@ -0,0 +1,73 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Project file discovery, following the same rules as ripgrep.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+
+/// Returns the files in `root` as paths relative to it, sorted by path.
+///
+/// Just like with ripgrep, hidden files and the files ignored by `.gitignore`, `.ignore`,
+/// `.rgignore`, or the global Git excludes are skipped, and symbolic links aren't followed.
+///
+/// The `globs` work like the `--glob` options of ripgrep. If any of them don't start with `!`,
+/// then only the matching files are included. The ones starting with `!` exclude their matches.
+/// Globs without a `/` match at any depth, and excluding a directory excludes all of its files.
+pub fn collect(root: &Path, globs: &[String]) -> Result<Vec<PathBuf>> {
+    walk(root, globs, false)
+}
+
+/// Returns the files in `root` like [`collect`], but including the hidden files, e.g. `.env`.
+///
+/// The `.git` directory is still skipped.
+pub fn collect_with_hidden(root: &Path, globs: &[String]) -> Result<Vec<PathBuf>> {
+    let mut globs = globs.to_vec();
+    globs.push("!.git/".into());
+    walk(root, &globs, true)
+}
+
+/// Returns the files in `root` matching the `globs`, including the hidden ones if `hidden`.
+fn walk(root: &Path, globs: &[String], hidden: bool) -> Result<Vec<PathBuf>> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in globs {
+        overrides
+            .add(glob)
+            .context(format!("failed to parse glob `{glob}`"))?;
+    }
+    let overrides = overrides.build().context("failed to build globs")?;
+
+    let walker = WalkBuilder::new(root)
+        .add_custom_ignore_filename(".rgignore")
+        .hidden(!hidden)
+        .overrides(overrides)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            // Files can disappear at any time, e.g. editor swap files.
+            Err(e)
+                if e.io_error()
+                    .is_some_and(|e| e.kind() == ErrorKind::NotFound) =>
+            {
+                continue;
+            }
+            Err(e) => {
+                return Err(e).context(format!("failed to read directory '{}'", root.display()));
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        files.push(path.to_path_buf());
+    }
+    Ok(files)
+}
@@ -0,0 +1,120 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! GitHub Actions integration.
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Severity of a GitHub Actions annotation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Fails the check in the GitHub UI.
+    Error,
+    /// Shown as a warning.
+    Warning,
+}
+
+/// A source location for a GitHub Actions annotation.
+pub struct Location<'a> {
+    /// The file path.
+    pub file: &'a Path,
+    /// The 1-based start line.
+    pub line: usize,
+    /// The 1-based end line.
+    pub end_line: Option<usize>,
+    /// The 1-based start column.
+    pub col: Option<usize>,
+    /// The 1-based end column.
+    pub end_col: Option<usize>,
+}
+
+/// Returns `true` if Prep is running in a GitHub Actions workflow.
+pub fn is_actions() -> bool {
+    env::var_os("GITHUB_ACTIONS").is_some_and(|v| v == "true")
+}
+
+/// Prints a workflow command that annotates the `location` with the `message`.
+///
+/// Relative file paths are resolved against `working_dir`
+/// and then made relative to the GitHub workspace, as expected by GitHub.
+///
+/// The command is printed to stderr, so that stdout remains free for machine readable output.
+/// The GitHub runner processes workflow commands from both.
+pub fn annotate(
+    level: Level,
+    working_dir: &Path,
+    location: &Location,
+    title: Option<&str>,
+    message: &str,
+) {
+    let file = working_dir.join(location.file);
+    let file = env::var_os("GITHUB_WORKSPACE")
+        .and_then(|workspace| file.strip_prefix(workspace).ok().map(Path::to_path_buf))
+        .unwrap_or(file);
+
+    let mut props = vec![
+        format!(
+            "file={}",
+            escape_property(&file.to_string_lossy().replace('\\', "/"))
+        ),
+        format!("line={}", location.line),
+    ];
+    if let Some(end_line) = location.end_line {
+        props.push(format!("endLine={end_line}"));
+    }
+    if let Some(col) = location.col {
+        props.push(format!("col={col}"));
+    }
+    if let Some(end_col) = location.end_col {
+        props.push(format!("endColumn={end_col}"));
+    }
+    if let Some(title) = title {
+        props.push(format!("title={}", escape_property(title)));
+    }
+
+    let command = match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+    };
+    eprintln!("::{command} {}::{}", props.join(","), escape_data(message));
+}
+
+/// Escapes `s` for use as workflow command data.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes `s` for use as a workflow command property value.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Appends the `markdown` to the job summary of the current GitHub Actions step.
+///
+/// Does nothing when not running in GitHub Actions.
+pub fn append_step_summary(markdown: &str) -> Result<()> {
+    let Some(path) = env::var_os("GITHUB_STEP_SUMMARY") else {
+        return Ok(());
+    };
+    let path = Path::new(&path);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!(
+            "failed to open GitHub step summary file '{}'",
+            path.display()
+        ))?;
+    writeln!(file, "{markdown}").context(format!(
+        "failed to write GitHub step summary file '{}'",
+        path.display()
+    ))?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! SHA-256 hashing with lowercase hex output.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sha2::Digest;
+
+/// Incremental SHA-256 hasher.
+pub struct Sha256(sha2::Sha256);
+
+impl Sha256 {
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        Self(sha2::Sha256::new())
+    }
+
+    /// Feeds more `data` into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Returns the final hash as a lowercase hex string.
+    pub fn finish(self) -> String {
+        format!("{:x}", self.0.finalize())
+    }
+}
+
+/// Returns the SHA-256 hash of the file at `path` as a lowercase hex string.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).context(format!("failed to open file '{}'", path.display()))?;
+    let mut hasher = sha2::Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .context(format!("failed to read file '{}'", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
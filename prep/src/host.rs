@@ -1,18 +1,232 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use directories::ProjectDirs;
+
 /// The triple that Prep was compiled for and thus is running on.
-#[expect(dead_code, reason = "for the future")]
 pub const TRIPLE: &str = env!("PREP_HOST_TRIPLE");
 
+/// Executable extensions to try on Windows when `PATHEXT` is not set.
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
 /// Returns the executable file name.
 ///
 /// Appends `.exe` on Windows, does nothing on other platforms.
 pub fn executable_name(name: &str) -> String {
-    //#[cfg(target_os = "windows")]
     if cfg!(target_os = "windows") {
         format!("{name}.exe")
     } else {
         name.to_string()
     }
 }
+
+/// Returns the path of the executable `name` as it should be spawned.
+///
+/// On Windows, the directories of `path`, or of our own `PATH` if `None`, are searched
+/// with each of the `PATHEXT` extensions, so that e.g. `.cmd` shims of npm packages are found.
+/// Spawning a bare name only finds `.exe` files there.
+///
+/// On other platforms the `name` is returned as is, because spawning it already searches `PATH`.
+pub fn resolve_executable(name: &str, path: Option<&OsStr>) -> PathBuf {
+    if !cfg!(target_os = "windows") {
+        return name.into();
+    }
+    let path = match path {
+        Some(path) => Some(path.to_os_string()),
+        None => env::var_os("PATH"),
+    };
+    path.and_then(|path| search(name, &path, &extensions()))
+        .unwrap_or_else(|| name.into())
+}
+
+/// Returns the path of the executable `name` in one of the `PATH` directories,
+/// or `None` if there is no such executable.
+///
+/// On Windows each of the `PATHEXT` extensions is tried.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    search(name, &env::var_os("PATH")?, &extensions())
+}
+
+/// Returns the first executable `name` with any of the `extensions` in the directories of `path`.
+fn search(name: &str, path: &OsStr, extensions: &[String]) -> Option<PathBuf> {
+    env::split_paths(path)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{name}{ext}")))
+        })
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Returns the extensions of executable files, in the order they should be tried.
+///
+/// On Windows these are the lowercase `PATHEXT` extensions, on other platforms just no extension.
+fn extensions() -> Vec<String> {
+    if !cfg!(target_os = "windows") {
+        return vec![String::new()];
+    }
+    let pathext = env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.into());
+    pathext
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.to_ascii_lowercase())
+        .collect()
+}
+
+/// Returns `true` if `path` is a file that can be executed.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Returns `true` if `path` is a file that can be executed.
+///
+/// The extension decides that outside of Unix, which [`find_executable`] already checked.
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Returns the shell binary and the arguments for running a command string with it.
+///
+/// This is `cmd /C` on Windows and `sh -c` on other platforms.
+pub fn shell() -> (&'static str, &'static [&'static str]) {
+    if cfg!(target_os = "windows") {
+        ("cmd", &["/C"])
+    } else {
+        ("sh", &["-c"])
+    }
+}
+
+/// Adds the `command` string as the last argument of the [`shell`] command `cmd`.
+///
+/// On Windows it's added verbatim, because `cmd` doesn't understand escaped quotes,
+/// which would break quoted paths with spaces like `"C:\Program Files\Rust\cargo.exe"`.
+pub fn shell_arg(cmd: &mut Command, command: &str) {
+    #[cfg(windows)]
+    std::os::windows::process::CommandExt::raw_arg(cmd, command);
+    #[cfg(not(windows))]
+    cmd.arg(command);
+}
+
+/// Returns the directory for the machine specific data of Prep, e.g. the managed tools.
+///
+/// On Windows this is in `%LOCALAPPDATA%`, so it's not synced between machines
+/// like the roaming `%APPDATA%`, and an overridden `LOCALAPPDATA` is respected.
+pub fn data_local_dir(project_dirs: &ProjectDirs) -> PathBuf {
+    local_app_data_dir(env::var_os("LOCALAPPDATA"), project_dirs)
+}
+
+/// Returns the data directory of Prep in the `local_app_data` directory on Windows,
+/// or the platform default if it's `None` or on other platforms.
+fn local_app_data_dir(local_app_data: Option<OsString>, project_dirs: &ProjectDirs) -> PathBuf {
+    match local_app_data {
+        // This is the same layout as `ProjectDirs` uses for the known folder.
+        Some(dir) if cfg!(target_os = "windows") && !dir.is_empty() => PathBuf::from(dir)
+            .join(project_dirs.project_path())
+            .join("data"),
+        _ => project_dirs.data_local_dir().to_path_buf(),
+    }
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use std::fs;
+    use std::process::{self, Command};
+
+    use super::*;
+
+    /// Returns a new empty directory for the test `name`, with spaces in its path.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("prep host {name} {}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn executable_name_appends_exe() {
+        assert_eq!(executable_name("cargo"), "cargo.exe");
+    }
+
+    #[test]
+    fn search_finds_cmd_shims() {
+        let dir = temp_dir("shims");
+        fs::write(dir.join("tool.cmd"), "@echo off").unwrap();
+        let extensions = [".exe".to_string(), ".cmd".to_string()];
+        assert_eq!(
+            search("tool", dir.as_os_str(), &extensions),
+            Some(dir.join("tool.cmd"))
+        );
+        assert_eq!(search("other", dir.as_os_str(), &extensions), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn search_follows_extension_order() {
+        let dir = temp_dir("order");
+        fs::write(dir.join("tool.cmd"), "@echo off").unwrap();
+        fs::write(dir.join("tool.exe"), "").unwrap();
+        let extensions = [".exe".to_string(), ".cmd".to_string()];
+        assert_eq!(
+            search("tool", dir.as_os_str(), &extensions),
+            Some(dir.join("tool.exe"))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_executable_searches_path() {
+        let dir = temp_dir("resolve");
+        fs::write(dir.join("cargo.exe"), "").unwrap();
+        let path = env::join_paths([&dir]).unwrap();
+        assert_eq!(
+            resolve_executable("cargo", Some(&path)),
+            dir.join("cargo.exe")
+        );
+        assert_eq!(
+            resolve_executable("missing", Some(&path)),
+            PathBuf::from("missing")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn shell_runs_quoted_paths_with_spaces() {
+        let dir = temp_dir("shell");
+        let script = dir.join("hello.cmd");
+        fs::write(&script, "@echo hello %1").unwrap();
+        let (shell, shell_args) = shell();
+        let mut cmd = Command::new(shell);
+        cmd.args(shell_args);
+        shell_arg(&mut cmd, &format!("\"{}\" world", script.display()));
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "hello world"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn data_local_dir_respects_local_app_data() {
+        let project_dirs = ProjectDirs::from("", "Test", "prep").unwrap();
+        assert_eq!(
+            local_app_data_dir(Some(r"D:\Local Data".into()), &project_dirs),
+            PathBuf::from(r"D:\Local Data\Test\prep\data")
+        );
+        assert_eq!(
+            local_app_data_dir(None, &project_dirs),
+            project_dirs.data_local_dir()
+        );
+    }
+}
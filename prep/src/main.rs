@@ -6,18 +6,37 @@
 mod cmd;
 mod config;
 mod environment;
+mod files;
+mod github;
+mod hash;
 mod host;
+mod metadata;
 mod session;
+mod toolchain_file;
 mod tools;
 mod toolset;
 mod ui;
 
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process;
+
 use anyhow::Result;
-use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap::{Args, ColorChoice, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::Shell;
+use semver::{Version, VersionReq};
 
 use ui::help;
 
-use crate::cmd::CargoTargets;
+use crate::cmd::clippy::FixMode;
+use crate::cmd::completions::Candidates;
+use crate::cmd::graph::GraphFormat;
+use crate::cmd::release::Level;
+use crate::cmd::sbom::SbomFormat;
+use crate::cmd::test::Partition;
+use crate::cmd::watch::WatchCommand;
+use crate::cmd::{CargoTargets, OutputFormat, Packages};
 use crate::session::Session;
 
 #[derive(Parser)]
@@ -25,28 +44,218 @@ use crate::session::Session;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    verbose: bool,
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    #[arg(long, global = true, value_enum)]
+    color: Option<ColorChoice>,
+    #[arg(short, long, global = true)]
+    yes: bool,
+    #[command(flatten)]
+    versions: ToolVersions,
+}
+
+/// Tool version requirements which override the `[tools]` configuration for a single run.
+///
+/// ripgrep is only used by `prep copyright`, so its version is given with its `--ripgrep` option.
+#[derive(Args)]
+struct ToolVersions {
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    rustup: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    rust: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    taplo: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    git_cliff: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_vet: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_sort: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_nextest: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    lychee: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_geiger: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_hakari: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_bloat: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_msrv: Option<VersionReq>,
+    #[arg(long, global = true, value_parser = config::parse_ver_req)]
+    cargo_deny: Option<VersionReq>,
+}
+
+impl ToolVersions {
+    /// Replaces the configured version requirements with the given ones.
+    fn apply(self, tools_cfg: &mut config::Tools) {
+        if let Some(ver_req) = self.rustup {
+            tools_cfg.set_rustup(ver_req);
+        }
+        if let Some(ver_req) = self.rust {
+            tools_cfg.set_rust(ver_req);
+        }
+        if let Some(ver_req) = self.taplo {
+            tools_cfg.set_taplo(ver_req);
+        }
+        if let Some(ver_req) = self.git_cliff {
+            tools_cfg.set_git_cliff(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_vet {
+            tools_cfg.set_cargo_vet(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_sort {
+            tools_cfg.set_cargo_sort(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_nextest {
+            tools_cfg.set_cargo_nextest(ver_req);
+        }
+        if let Some(ver_req) = self.lychee {
+            tools_cfg.set_lychee(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_geiger {
+            tools_cfg.set_cargo_geiger(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_hakari {
+            tools_cfg.set_cargo_hakari(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_bloat {
+            tools_cfg.set_cargo_bloat(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_msrv {
+            tools_cfg.set_cargo_msrv(ver_req);
+        }
+        if let Some(ver_req) = self.cargo_deny {
+            tools_cfg.set_cargo_deny(ver_req);
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
     #[command()]
+    Bench {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(long)]
+        smoke: bool,
+        #[arg(long)]
+        save_baseline: Option<String>,
+        #[arg(short, long)]
+        baseline: Option<String>,
+        #[command(flatten)]
+        packages: Packages,
+    },
+    #[command()]
+    Bloat {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(short, long)]
+        bin: Vec<String>,
+        #[arg(short = 'n', long, default_value_t = 20)]
+        count: usize,
+        #[arg(short, long)]
+        crates: bool,
+        #[arg(long)]
+        update_baseline: bool,
+    },
+    #[command(args_conflicts_with_subcommands = true)]
     Ci {
+        #[command(subcommand)]
+        command: Option<CiCommands>,
         #[arg(short, long)]
         extended: bool,
+        #[arg(long, value_name = "step")]
+        only: Vec<String>,
         #[arg(short, long)]
         no_fail_fast: bool,
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(short, long)]
+        junit: Option<PathBuf>,
+        #[arg(short, long)]
+        resume: bool,
+        #[arg(long, conflicts_with_all = ["output", "junit", "resume"])]
+        dry_run: bool,
+    },
+    #[command()]
+    Changelog {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(short, long)]
+        check: bool,
+    },
+    #[command()]
+    Check {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(short, long)]
+        nightly: bool,
+        #[command(flatten)]
+        packages: Packages,
+        #[arg(name = "crates", short, long, value_enum, default_value_t = CargoTargets::Main)]
+        targets: CargoTargets,
+    },
+    #[command()]
+    Clean {
+        #[arg(long)]
+        cache: bool,
+        #[arg(long)]
+        tools: bool,
+        #[arg(long)]
+        cargo: bool,
+        #[arg(short = 'n', long)]
+        dry_run: bool,
     },
     #[command(alias = "clp")]
     Clippy {
         #[arg(short, long)]
         strict: bool,
+        #[command(flatten)]
+        packages: Packages,
         #[arg(name = "crates", short, long, value_enum, default_value_t = CargoTargets::Main)]
         targets: CargoTargets,
+        #[arg(long)]
+        fix: bool,
+        #[arg(long, requires = "fix")]
+        allow_dirty: bool,
+        #[arg(short = 'm', long)]
+        features_matrix: bool,
+        #[arg(long, conflicts_with = "fix")]
+        update_baseline: bool,
+        #[arg(long)]
+        sarif: Option<PathBuf>,
+    },
+    #[command()]
+    Completions {
+        #[arg(value_enum, required_unless_present = "list")]
+        shell: Option<Shell>,
+        #[arg(long, value_enum, conflicts_with = "shell")]
+        list: Option<Candidates>,
+    },
+    #[command()]
+    Config {
+        #[command(subcommand)]
+        command: Option<ConfigCommands>,
     },
     #[command()]
     Copyright {
         #[arg(short, long)]
         strict: bool,
+        #[arg(short, long, num_args = 0..=1, value_parser = config::parse_ver_req)]
+        ripgrep: Option<Option<VersionReq>>,
+    },
+    #[command()]
+    Cross {
+        #[arg(short, long = "target")]
+        targets: Vec<String>,
     },
     #[command(alias = "fmt")]
     Format {
@@ -54,54 +263,633 @@ enum Commands {
         strict: bool,
         #[arg(short, long)]
         check: bool,
+        #[arg(short, long)]
+        toml: bool,
+        #[command(flatten)]
+        packages: Packages,
+        #[arg(long)]
+        since: Option<String>,
     },
     #[command()]
     Init {
         #[arg(short, long, default_value_t = false)]
         force: bool,
+        #[arg(short, long)]
+        github: bool,
+        #[arg(short = 't', long, conflicts_with = "github")]
+        write_toolchain_file: bool,
+        #[arg(short, long, conflicts_with_all = ["github", "write_toolchain_file"])]
+        scaffold: bool,
+    },
+    #[command()]
+    Doctor,
+    #[command()]
+    Duplicates,
+    #[command()]
+    Graph {
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+        #[arg(short, long)]
+        workspace_only: bool,
+        #[arg(short, long)]
+        dev: bool,
+        #[arg(short, long)]
+        build: bool,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    #[command()]
+    Hakari {
+        #[command(subcommand)]
+        command: Option<HakariCommands>,
+    },
+    #[command()]
+    Hooks {
+        #[command(subcommand)]
+        command: Option<HooksCommands>,
+    },
+    #[command()]
+    Licenses {
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    #[command()]
+    Links {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(long)]
+        offline: bool,
+    },
+    #[command()]
+    Logs {
+        #[command(subcommand)]
+        command: Option<LogsCommands>,
+    },
+    #[command()]
+    ManifestLint {
+        #[arg(short, long)]
+        fix: bool,
+    },
+    #[command()]
+    Msrv {
+        #[command(subcommand)]
+        command: Option<MsrvCommands>,
+    },
+    #[command()]
+    Publish {
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    #[command()]
+    Readme {
+        #[arg(short, long, conflicts_with = "fix")]
+        check: bool,
+        #[arg(short, long)]
+        fix: bool,
+    },
+    #[command()]
+    Release {
+        #[arg(value_enum)]
+        level: Option<Level>,
+        #[arg(short, long)]
+        publish: bool,
+    },
+    #[command()]
+    Run { task: Option<String> },
+    #[command()]
+    Sbom {
+        #[arg(short, long, value_enum, default_value_t = SbomFormat::CycloneDx)]
+        format: SbomFormat,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    #[command()]
+    Secrets,
+    #[command()]
+    Status,
+    #[command(name = "self")]
+    SelfCmd {
+        #[command(subcommand)]
+        command: Option<SelfCommands>,
+    },
+    #[command()]
+    Test {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(short, long)]
+        doc: bool,
+        #[arg(long)]
+        partition: Option<Partition>,
+        #[command(flatten)]
+        packages: Packages,
+    },
+    #[command()]
+    Timings {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(short, long)]
+        release: bool,
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
     },
     #[command()]
     Tools {
         #[command(subcommand)]
         command: Option<ToolsCommands>,
     },
+    #[command()]
+    UnsafeReport {
+        #[arg(short, long)]
+        strict: bool,
+        #[arg(long)]
+        update_baseline: bool,
+    },
+    #[command()]
+    Vet {
+        #[command(subcommand)]
+        command: Option<VetCommands>,
+    },
+    #[command()]
+    Wasm {
+        #[command(flatten)]
+        packages: Packages,
+    },
+    #[command()]
+    Watch {
+        #[arg(value_enum)]
+        commands: Vec<WatchCommand>,
+    },
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum CiCommands {
+    #[command()]
+    CacheKey,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    #[command()]
+    Get { key: String },
+    #[command()]
+    Set { key: String, value: String },
+    #[command()]
+    Migrate,
+}
+
+#[derive(Subcommand)]
+enum HakariCommands {
+    #[command()]
+    Generate,
+    #[command()]
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum HooksCommands {
+    #[command()]
+    Install {
+        #[arg(short, long)]
+        force: bool,
+    },
+    #[command()]
+    Uninstall,
+}
+
+#[derive(Subcommand)]
+enum LogsCommands {
+    #[command()]
+    Tail,
+}
+
+#[derive(Subcommand)]
+enum MsrvCommands {
+    #[command()]
+    Find {
+        #[arg(short, long)]
+        write: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelfCommands {
+    #[command()]
+    Update {
+        #[arg(short, long)]
+        check: bool,
+    },
 }
 
 #[derive(Subcommand)]
 enum ToolsCommands {
     #[command()]
-    List,
+    CleanTemp,
+    #[command()]
+    Du {
+        #[arg(short, long)]
+        budget: Option<u64>,
+    },
+    #[command()]
+    Export {
+        archive: PathBuf,
+        tools: Vec<String>,
+    },
+    #[command()]
+    Import { archive: PathBuf },
+    #[command()]
+    List {
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        #[arg(short, long)]
+        all_versions: bool,
+    },
+    #[command()]
+    Outdated {
+        #[arg(short, long)]
+        toml: bool,
+    },
+    #[command()]
+    Prune {
+        #[arg(short, long)]
+        days: Option<u32>,
+    },
+    #[command()]
+    Uninstall {
+        name: String,
+        version: Option<Version>,
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    #[command()]
+    Verify {
+        #[arg(short, long)]
+        repair: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VetCommands {
+    #[command(disable_help_flag = true)]
+    Certify {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
-    let ccmd = help::set(Cli::command());
+    cmd::self_update::remove_replaced();
+
+    let color = ui::color::init(ui::color::from_args(env::args().skip(1)));
+    let ccmd = help::set(Cli::command()).color(color);
     let matches = ccmd.get_matches();
     let cli = Cli::from_arg_matches(&matches).unwrap();
 
+    if cli.verbose {
+        ui::set_verbosity(ui::Verbosity::Verbose);
+    } else if cli.quiet {
+        ui::set_verbosity(ui::Verbosity::Quiet);
+    }
+    ui::prompt::set_assume_yes(cli.yes);
+
     let Some(command) = cli.command else {
         ui::print_help(ui::help::root_msg());
         return Ok(());
     };
 
-    let mut session = Session::initialize()?;
+    // Completion scripts don't depend on the project, so they can be generated anywhere.
+    if let Commands::Completions {
+        shell: Some(shell), ..
+    } = command
+    {
+        return cmd::completions::generate(shell, &mut Cli::command());
+    }
+
+    let mut session = Session::initialize(cli.profile.as_deref())?;
+    cli.versions.apply(session.config_mut().tools_mut());
+
+    let commands = Cli::command()
+        .get_subcommands()
+        .map(|c| c.get_name().to_string())
+        .collect::<Vec<_>>();
+    cmd::verify_hooks(session.config(), &commands)?;
+    let name = matches
+        .subcommand_name()
+        .expect("subcommand must be present")
+        .to_string();
+    if logged(&command)
+        && let Err(e) = ui::log::start(session.prep_dir(), env::args().skip(1).collect())
+    {
+        ui::print_warn(&format!("{:#}", e.context("failed to start logging")));
+    }
+    let result = run(&mut session, command, &name);
+    ui::log::finish(&result);
+    // External commands report their own errors, so only their exit code is passed on.
+    if let Err(e) = &result
+        && let Some(failed) = e.downcast_ref::<cmd::external::Failed>()
+    {
+        process::exit(failed.code());
+    }
+    result
+}
+
+/// Returns whether the run of the `command` is logged.
+///
+/// Read-only commands are not logged, so that the last run remains the one of interest,
+/// e.g. the completions which the shell lists on every tab press.
+/// Cleaning is not logged either, as it may remove the logs directory.
+fn logged(command: &Commands) -> bool {
+    !matches!(
+        command,
+        Commands::Clean { .. }
+            | Commands::Completions { .. }
+            | Commands::Config {
+                command: None | Some(ConfigCommands::Get { .. })
+            }
+            | Commands::Logs { .. }
+            | Commands::Status
+    )
+}
+
+/// Runs the `command` with the given `name`, along with its configured hooks.
+fn run(session: &mut Session, command: Commands, name: &str) -> Result<()> {
+    // A dry run lists the hooks instead of running them.
+    let hooks = !matches!(command, Commands::Ci { dry_run: true, .. });
+    if hooks {
+        cmd::run_hooks(session, "pre", name)?;
+    }
 
-    match command {
+    let result = match command {
+        Commands::Bench {
+            strict,
+            smoke,
+            save_baseline,
+            baseline,
+            packages,
+        } => cmd::bench::run(
+            session,
+            strict,
+            smoke,
+            save_baseline.as_deref(),
+            baseline.as_deref(),
+            &packages,
+        ),
+        Commands::Bloat {
+            strict,
+            bin,
+            count,
+            crates,
+            update_baseline,
+        } => cmd::bloat::run(session, strict, &bin, count, crates, update_baseline),
+        Commands::Ci {
+            command: Some(CiCommands::CacheKey),
+            ..
+        } => cmd::ci::cache_key(session),
+        Commands::Ci {
+            command: None,
+            extended,
+            only,
+            dry_run: true,
+            ..
+        } => cmd::ci::plan(session, extended, &only),
         Commands::Ci {
+            command: None,
             extended,
+            only,
             no_fail_fast,
-        } => cmd::ci::run(&mut session, extended, !no_fail_fast),
-        Commands::Clippy { strict, targets } => cmd::clippy::run(&mut session, strict, targets),
-        Commands::Copyright { strict } => cmd::copyright::run(&mut session, strict),
-        Commands::Format { strict, check } => cmd::format::run(&mut session, strict, check),
-        Commands::Init { force } => cmd::init::run(&session, force),
+            format,
+            output,
+            junit,
+            resume,
+            dry_run: false,
+        } => {
+            let options = cmd::ci::Options {
+                fail_fast: !no_fail_fast,
+                format,
+                output: output.as_deref(),
+                junit: junit.as_deref(),
+                resume,
+            };
+            cmd::ci::run(session, extended, &only, &options)
+        }
+        Commands::Changelog { strict, check } => cmd::changelog::run(session, strict, check),
+        Commands::Check {
+            strict,
+            nightly,
+            packages,
+            targets,
+        } => cmd::check::run(session, strict, nightly, &packages, targets),
+        Commands::Clean {
+            cache,
+            tools,
+            cargo,
+            dry_run,
+        } => {
+            let categories = cmd::clean::Categories {
+                cache,
+                tools,
+                cargo,
+            };
+            cmd::clean::run(session, categories, dry_run)
+        }
+        Commands::Clippy {
+            strict,
+            packages,
+            targets,
+            fix,
+            allow_dirty,
+            features_matrix,
+            update_baseline,
+            sarif,
+        } => {
+            let fix = match (fix, allow_dirty) {
+                (false, _) => FixMode::Off,
+                (true, false) => FixMode::AllowStaged,
+                (true, true) => FixMode::AllowDirty,
+            };
+            let options = cmd::clippy::Options {
+                fix,
+                features_matrix,
+                update_baseline,
+                sarif: sarif.as_deref(),
+            };
+            cmd::clippy::run(session, strict, &packages, targets, &options)
+        }
+        Commands::Completions { list, .. } => {
+            let list = list.expect("either shell or list must be present");
+            cmd::completions::list(session, list)
+        }
+        Commands::Copyright { strict, ripgrep } => {
+            // A specific ripgrep version can only be provided by the managed ripgrep.
+            let locked = match ripgrep.clone().flatten() {
+                Some(ver_req) => {
+                    session.config_mut().tools_mut().set_ripgrep(ver_req);
+                    true
+                }
+                None => strict,
+            };
+            cmd::copyright::run(session, locked, ripgrep.is_some())
+        }
+        Commands::Cross { targets } => cmd::cross::run(session, &targets),
+        Commands::Format {
+            strict,
+            check,
+            toml,
+            packages,
+            since,
+        } => cmd::format::run(session, strict, check, toml, &packages, since.as_deref()),
+        Commands::Init {
+            force,
+            github,
+            write_toolchain_file,
+            scaffold,
+        } => cmd::init::run(session, force, github, write_toolchain_file, scaffold),
+        Commands::Doctor => cmd::doctor::run(session),
+        Commands::Duplicates => cmd::duplicates::run(session),
+        Commands::Graph {
+            format,
+            workspace_only,
+            dev,
+            build,
+            output,
+        } => {
+            let edges = cmd::graph::Edges {
+                workspace_only,
+                dev,
+                build,
+            };
+            cmd::graph::run(session, format, edges, output.as_deref())
+        }
+        Commands::Config { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::config_msg());
+                return Ok(());
+            };
+            match command {
+                ConfigCommands::Get { key } => cmd::config::get(session, &key),
+                ConfigCommands::Set { key, value } => cmd::config::set(session, &key, &value),
+                ConfigCommands::Migrate => cmd::config::migrate(session),
+            }
+        }
+        Commands::Hakari { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::hakari_msg());
+                return Ok(());
+            };
+            match command {
+                HakariCommands::Generate => cmd::hakari::generate(session),
+                HakariCommands::Verify => cmd::hakari::verify(session),
+            }
+        }
+        Commands::Hooks { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::hooks_msg());
+                return Ok(());
+            };
+            match command {
+                HooksCommands::Install { force } => cmd::hooks::install(session, force),
+                HooksCommands::Uninstall => cmd::hooks::uninstall(session),
+            }
+        }
+        Commands::Licenses { format } => cmd::licenses::run(session, format),
+        Commands::Links { strict, offline } => cmd::links::run(session, strict, offline),
+        Commands::Logs { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::logs_msg());
+                return Ok(());
+            };
+            match command {
+                LogsCommands::Tail => cmd::logs::tail(session),
+            }
+        }
+        Commands::ManifestLint { fix } => cmd::manifest_lint::run(session, fix),
+        Commands::Msrv { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::msrv_msg());
+                return Ok(());
+            };
+            match command {
+                MsrvCommands::Find { write } => cmd::msrv::find(session, write),
+            }
+        }
+        Commands::Publish { dry_run } => cmd::publish::run(session, dry_run),
+        // Verifying is the default, so --check only makes it explicit.
+        Commands::Readme { check: _, fix } => cmd::readme::run(session, fix),
+        Commands::Release { level, publish } => cmd::release::run(session, level, publish),
+        Commands::Run { task } => cmd::run::run(session, task.as_deref()),
+        Commands::Sbom { format, output } => cmd::sbom::run(session, format, output.as_deref()),
+        Commands::Secrets => cmd::secrets::run(session),
+        Commands::Status => cmd::status::run(session),
+        Commands::Test {
+            strict,
+            doc,
+            partition,
+            packages,
+        } => cmd::test::run(session, strict, doc, partition, &packages),
+        Commands::Timings {
+            strict,
+            release,
+            count,
+        } => cmd::timings::run(session, strict, release, count),
+        Commands::SelfCmd { command } => {
+            let Some(command) = command else {
+                ui::print_help(ui::help::self_msg());
+                return Ok(());
+            };
+            match command {
+                SelfCommands::Update { check } => cmd::self_update::run(session, check),
+            }
+        }
         Commands::Tools { command } => {
             let Some(command) = command else {
                 ui::print_help(ui::help::tools_msg());
                 return Ok(());
             };
             match command {
-                ToolsCommands::List => cmd::tools::list::run(&mut session),
+                ToolsCommands::CleanTemp => cmd::tools::clean_temp::run(session),
+                ToolsCommands::Du { budget } => cmd::tools::du::run(session, budget),
+                ToolsCommands::Export { archive, tools } => {
+                    cmd::tools::export::run(session, &archive, &tools)
+                }
+                ToolsCommands::Import { archive } => cmd::tools::import::run(session, &archive),
+                ToolsCommands::List {
+                    format,
+                    all_versions,
+                } => cmd::tools::list::run(session, format, all_versions),
+                ToolsCommands::Outdated { toml } => cmd::tools::outdated::run(session, toml),
+                ToolsCommands::Prune { days } => cmd::tools::prune::run(session, days),
+                ToolsCommands::Uninstall {
+                    name,
+                    version,
+                    dry_run,
+                } => cmd::tools::uninstall::run(session, &name, version.as_ref(), dry_run),
+                ToolsCommands::Verify { repair } => cmd::tools::verify::run(session, repair),
             }
         }
+        Commands::UnsafeReport {
+            strict,
+            update_baseline,
+        } => cmd::unsafe_report::run(session, strict, update_baseline),
+        Commands::Vet { command } => match command {
+            None => cmd::vet::run(session),
+            Some(VetCommands::Certify { args }) => cmd::vet::certify(session, &args),
+        },
+        Commands::Wasm { packages } => cmd::wasm::run(session, &packages),
+        Commands::Watch { commands } => cmd::watch::run(session, &commands),
+        Commands::External(args) => {
+            let command = Cli::command();
+            let builtins = command
+                .get_subcommands()
+                .map(|c| c.get_name())
+                .collect::<Vec<_>>();
+            cmd::external::run(session, &args, &builtins)
+        }
+    };
+    result?;
+
+    if hooks {
+        cmd::run_hooks(session, "post", name)?;
     }
+    Ok(())
 }
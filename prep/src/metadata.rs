@@ -0,0 +1,69 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Cargo metadata of the project workspace.
+
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use cargo_metadata::{Metadata, MetadataCommand, Package};
+
+use crate::session::Session;
+
+/// Returns the Cargo metadata of the workspace, without the dependencies.
+pub fn fetch(session: &mut Session) -> Result<Metadata> {
+    cmd(session)
+        .exec()
+        .context("failed to fetch Cargo metadata")
+}
+
+/// Returns the Cargo metadata of the workspace with the full dependency graph of `Cargo.lock`.
+pub fn fetch_graph(session: &mut Session) -> Result<Metadata> {
+    graph_cmd(session)
+        .exec()
+        .context("failed to fetch Cargo metadata")
+}
+
+/// Returns the command for the Cargo metadata of the workspace, without the dependencies.
+pub fn cmd(session: &mut Session) -> MetadataCommand {
+    let mut metadata_cmd = base_cmd(session);
+    metadata_cmd.no_deps();
+    metadata_cmd
+}
+
+/// Returns the command for the Cargo metadata of the workspace with the full dependency graph.
+///
+/// The graph is that of `Cargo.lock`, which must be up to date.
+pub fn graph_cmd(session: &mut Session) -> MetadataCommand {
+    let mut metadata_cmd = base_cmd(session);
+    metadata_cmd.other_options(vec!["--locked".into()]);
+    metadata_cmd
+}
+
+/// Returns the workspace packages with their directories, deepest directories first.
+///
+/// So the first package whose directory contains a file is the one that the file belongs to,
+/// even with nested packages.
+pub fn package_dirs(metadata: &Metadata) -> Vec<(&Package, PathBuf)> {
+    let mut packages = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter_map(|p| {
+            let dir = p.manifest_path.parent()?.as_std_path().to_path_buf();
+            Some((p, dir))
+        })
+        .collect::<Vec<_>>();
+    packages.sort_by_key(|(_, dir)| Reverse(dir.components().count()));
+    packages
+}
+
+/// Returns the Cargo metadata command that runs in the project root with the toolset environment.
+fn base_cmd(session: &mut Session) -> MetadataCommand {
+    let mut metadata_cmd = MetadataCommand::new();
+    metadata_cmd.current_dir(session.root_dir());
+    for (k, v) in session.toolset().environment().vars() {
+        metadata_cmd.env(k, v);
+    }
+    metadata_cmd
+}
@@ -10,11 +10,33 @@ use anyhow::{Context, Result, bail};
 use cargo_metadata::MetadataCommand;
 use directories::ProjectDirs;
 
-use crate::config::Config;
+use crate::config::{self, Config};
 use crate::environment::Environment;
+use crate::toolchain_file::{self, ToolchainFile};
 use crate::tools::Tool;
 use crate::tools::cargo::{Cargo, CargoDeps};
+use crate::tools::cargo_binstall::CargoBinstall;
+use crate::tools::cargo_bloat::CargoBloat;
+use crate::tools::cargo_deny::CargoDeny;
+use crate::tools::cargo_geiger::CargoGeiger;
+use crate::tools::cargo_hakari::CargoHakari;
+use crate::tools::cargo_msrv::CargoMsrv;
+use crate::tools::cargo_nextest::CargoNextest;
+use crate::tools::cargo_sort::CargoSort;
+use crate::tools::cargo_vet::CargoVet;
+use crate::tools::clippy::Clippy;
+use crate::tools::curl::Curl;
+use crate::tools::git::Git;
+use crate::tools::git_cliff::GitCliff;
+use crate::tools::lychee::Lychee;
+use crate::tools::ripgrep::Ripgrep;
+use crate::tools::rustfmt::Rustfmt;
+use crate::tools::rustup::Rustup;
+use crate::tools::taplo::Taplo;
+use crate::tools::tar::Tar;
+use crate::tools::wasm_bindgen::WasmBindgen;
 use crate::toolset::Toolset;
+use crate::{host, ui};
 
 const ORG_TLD: &str = "ee";
 const ORG_NAME: &str = "Nevermore";
@@ -22,11 +44,36 @@ const APP_NAME: &str = "Prep";
 
 const PREP_DIR: &str = ".prep";
 const CONFIG_FILE: &str = "prep.toml";
+const USER_CONFIG_FILE: &str = "config.toml";
+
+/// How the project root directory was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootDiscovery {
+    /// The closest directory with a Prep config.
+    PrepConfig,
+    /// The root of the Cargo workspace.
+    CargoWorkspace,
+    /// The root of the Git repository, when there is no Cargo workspace.
+    GitRepository,
+}
+
+impl RootDiscovery {
+    /// Returns a description of the method, e.g. for logs.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::PrepConfig => "Prep config",
+            Self::CargoWorkspace => "Cargo workspace",
+            Self::GitRepository => "Git repository",
+        }
+    }
+}
 
 /// Information about the current runtime session.
 pub struct Session {
     /// The project root directory.
     root_dir: PathBuf,
+    /// How the project root directory was found.
+    root_discovery: RootDiscovery,
     /// The project's prep directory.
     prep_dir: PathBuf,
     /// The project's prep config path.
@@ -43,8 +90,9 @@ pub struct Session {
 impl Session {
     /// Initializes and returns a fresh [`Session`].
     ///
-    /// This function will also Load the configuration file.
-    pub fn initialize() -> Result<Session> {
+    /// This function will also Load the configuration file,
+    /// applying the configuration `profile` with the given name.
+    pub fn initialize(profile: Option<&str>) -> Result<Session> {
         // Initialize the default environment variables.
         let environment = Environment::new();
 
@@ -56,43 +104,127 @@ impl Session {
         let root_dir =
             find_root_dir(&current_dir).context("failed to look for Prep config file")?;
 
-        // Fall back to the Cargo workspace root
-        let root_dir = match root_dir {
-            Some(root_dir) => root_dir,
+        // Fall back to the Cargo workspace root, and then to the Git repository root
+        let (root_dir, root_discovery) = match root_dir {
+            Some(root_dir) => (root_dir, RootDiscovery::PrepConfig),
             None => {
                 let mut metadata_cmd = MetadataCommand::new();
                 for (k, v) in environment.vars() {
                     metadata_cmd.env(k, v);
                 }
-                let metadata = metadata_cmd
-                    .exec()
-                    .context("failed to fetch Cargo metadata")?;
-                let workspace_dir = metadata.workspace_root.into_std_path_buf();
-                workspace_dir
-                    .canonicalize()
-                    .context("failed to canonicalize Cargo workspace dir")?
+                match metadata_cmd.exec() {
+                    Ok(metadata) => {
+                        let workspace_dir = metadata.workspace_root.into_std_path_buf();
+                        let workspace_dir = workspace_dir
+                            .canonicalize()
+                            .context("failed to canonicalize Cargo workspace dir")?;
+                        (workspace_dir, RootDiscovery::CargoWorkspace)
+                    }
+                    // A broken workspace must be reported, not worked around.
+                    Err(e) if has_cargo_manifest(&current_dir) => {
+                        return Err(e).context("failed to fetch Cargo metadata");
+                    }
+                    Err(e) => match find_git_root(&current_dir) {
+                        Some(git_dir) => (git_dir, RootDiscovery::GitRepository),
+                        None => {
+                            return Err(e).context(
+                                "failed to find the project root, \
+                                there is no Prep config, Cargo workspace, or Git repository",
+                            );
+                        }
+                    },
+                }
             }
         };
+        ui::print_debug(&format!(
+            "Using project root '{}' found by {}",
+            root_dir.display(),
+            root_discovery.description()
+        ));
 
         let prep_dir = root_dir.join(PREP_DIR);
         let config_path = prep_dir.join(CONFIG_FILE);
 
-        // Attempt to load the config
-        let config = if config_path.exists() {
-            Self::load_config(&config_path)?
-        } else {
-            Config::new()
-        };
-
         let project_dirs = ProjectDirs::from(ORG_TLD, ORG_NAME, APP_NAME)
             .context("failed to get OS specific directories")?;
-        let tools_dir = project_dirs.data_local_dir().to_path_buf();
+        let user_config_path = project_dirs.config_dir().join(USER_CONFIG_FILE);
+
+        // Attempt to load the config, with the project config taking precedence over the user's
+        let mut config = Self::load_config(&[&user_config_path, &config_path], profile)?;
 
-        let mut toolset = Toolset::new(tools_dir, root_dir.clone(), environment)
-            .context("failed to initialize toolset")?;
+        // Fall back to the toolchain file for the Rust version and components.
+        if let Some(toolchain_file) = ToolchainFile::load(&root_dir)? {
+            let tools_cfg = config.tools_mut();
+            if let Some(ver_req) = toolchain_file.ver_req() {
+                match tools_cfg.rust_configured() {
+                    Some(configured) if *configured != ver_req => {
+                        ui::print_warn(&format!(
+                            "Rust version {configured} in '{}' differs from {ver_req} in '{}'",
+                            config_path.display(),
+                            root_dir.join(toolchain_file::FILE_NAME).display()
+                        ));
+                    }
+                    _ => tools_cfg.set_rust_fallback(ver_req),
+                }
+            }
+            tools_cfg.set_rust_components(toolchain_file.components().to_vec());
+        }
+
+        let tools_dir = match config.tools().dir() {
+            Some(dir) => dir.to_path_buf(),
+            None => host::data_local_dir(&project_dirs),
+        };
+
+        // Catch typos in the tool names, as their environment variables would be silently ignored.
+        let tool_names = [
+            Cargo::NAME,
+            CargoBinstall::NAME,
+            CargoGeiger::NAME,
+            CargoHakari::NAME,
+            CargoBloat::NAME,
+            CargoDeny::NAME,
+            CargoMsrv::NAME,
+            CargoNextest::NAME,
+            CargoSort::NAME,
+            CargoVet::NAME,
+            Clippy::NAME,
+            Curl::NAME,
+            Git::NAME,
+            GitCliff::NAME,
+            Lychee::NAME,
+            Ripgrep::NAME,
+            Rustfmt::NAME,
+            Rustup::NAME,
+            Taplo::NAME,
+            Tar::NAME,
+            WasmBindgen::NAME,
+        ];
+        for name in config.tools().env().keys() {
+            if !tool_names.contains(&name.as_str()) {
+                bail!(
+                    "unknown tool `{name}` in tools.env, expected one of: {}",
+                    tool_names.join(", ")
+                );
+            }
+        }
+
+        let environment = environment.network(config.network());
+        let network = config.network().clone();
+        let tool_env = config.tools().env().clone();
+        let verify_hashes = config.tools().verify_hashes();
+        let toolset = Toolset::new(
+            tools_dir,
+            root_dir.clone(),
+            environment,
+            network,
+            tool_env,
+            verify_hashes,
+        )
+        .context("failed to initialize toolset")?;
 
         let session = Session {
             root_dir,
+            root_discovery,
             prep_dir,
             config_path,
             project_dirs,
@@ -108,6 +240,11 @@ impl Session {
         &self.root_dir
     }
 
+    /// Returns how the project root directory was found.
+    pub fn root_discovery(&self) -> RootDiscovery {
+        self.root_discovery
+    }
+
     /// Returns the project's prep directory.
     pub fn prep_dir(&self) -> &Path {
         &self.prep_dir
@@ -118,6 +255,11 @@ impl Session {
         &self.config_path
     }
 
+    /// Returns the user's prep config path.
+    pub fn user_config_path(&self) -> PathBuf {
+        self.project_dirs.config_dir().join(USER_CONFIG_FILE)
+    }
+
     /// Returns the OS-specific directories.
     pub fn project_dirs(&self) -> &ProjectDirs {
         &self.project_dirs
@@ -128,6 +270,11 @@ impl Session {
         &self.config
     }
 
+    /// Returns the project's mutable prep config.
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.config
+    }
+
     /// Returns this session's toolset.
     pub fn toolset(&mut self) -> &mut Toolset {
         &mut self.toolset
@@ -149,22 +296,58 @@ impl Session {
         Ok(())
     }
 
-    /// Loads the configuration from file.
-    pub fn load_config(config_path: &Path) -> Result<Config> {
-        let config_toml = fs::read(config_path).context(format!(
-            "failed to read config file '{}'",
-            config_path.display()
-        ))?;
-        let config: Config =
-            toml::from_slice(&config_toml).context("failed to parse config TOML")?;
+    /// Loads the configuration from the files that exist out of `config_paths`.
+    ///
+    /// Later files take precedence over earlier ones, and the `profile` over all of them.
+    pub fn load_config(config_paths: &[&Path], profile: Option<&str>) -> Result<Config> {
+        let mut files = Vec::new();
+        for config_path in config_paths.iter().filter(|p| p.exists()) {
+            let config_toml = fs::read_to_string(config_path).context(format!(
+                "failed to read config file '{}'",
+                config_path.display()
+            ))?;
+            let config_toml = match config::migrate(&config_toml).context(format!(
+                "failed to migrate config file '{}'",
+                config_path.display()
+            ))? {
+                Some(migrated) => {
+                    ui::print_warn(&format!(
+                        "config file '{}' uses an outdated layout, \
+                        run `prep config migrate` to upgrade it",
+                        config_path.display()
+                    ));
+                    migrated
+                }
+                None => config_toml,
+            };
+            files.push((*config_path, config_toml));
+        }
+        if files.is_empty() && profile.is_none() {
+            return Ok(Config::new());
+        }
+        let files = files
+            .iter()
+            .map(|(path, config_toml)| (*path, config_toml.as_str()))
+            .collect::<Vec<_>>();
+        Config::parse_layers(&files, profile)
+    }
+
+    /// Loads only the project's own config file, along with the toolchain file fallback.
+    ///
+    /// Unlike [`Session::config`], it has nothing from the user config, profile, or command line.
+    pub fn project_config(&self) -> Result<Config> {
+        let mut config = Self::load_config(&[&self.config_path], None)?;
+        let toolchain_file = ToolchainFile::load(&self.root_dir)?;
+        if let Some(ver_req) = toolchain_file.and_then(|file| file.ver_req()) {
+            config.tools_mut().set_rust_fallback(ver_req);
+        }
         Ok(config)
     }
 
-    /// Saves the configuration to file.
-    pub fn save_config(&self) -> Result<()> {
+    /// Saves `config` to the project config file.
+    pub fn save_config(&self, config: &Config) -> Result<()> {
         self.ensure_prep_dir()?;
-        let config_toml =
-            toml::to_string(&self.config).context("failed to generate config TOML")?;
+        let config_toml = toml::to_string(config).context("failed to generate config TOML")?;
         fs::write(&self.config_path, &config_toml).context(format!(
             "failed to write config file '{}'",
             self.config_path.display()
@@ -184,3 +367,17 @@ fn find_root_dir(dir: &Path) -> Result<Option<PathBuf>> {
     }
     Ok(None)
 }
+
+/// Returns `true` if `dir` or any of its parents contains a Cargo manifest.
+fn has_cargo_manifest(dir: &Path) -> bool {
+    dir.ancestors().any(|dir| dir.join("Cargo.toml").is_file())
+}
+
+/// Returns the root directory of the Git repository containing `dir`, if any.
+///
+/// The `.git` entry can also be a file, e.g. in worktrees and submodules.
+fn find_git_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
@@ -0,0 +1,102 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rustup `rust-toolchain.toml` integration.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+/// The toolchain file name, which rustup looks for in the project root.
+pub const FILE_NAME: &str = "rust-toolchain.toml";
+
+/// Rustup toolchain file.
+#[derive(Serialize, Deserialize)]
+pub struct ToolchainFile {
+    /// Toolchain section.
+    toolchain: Toolchain,
+}
+
+/// Toolchain section of the toolchain file.
+#[derive(Serialize, Deserialize)]
+struct Toolchain {
+    /// Toolchain channel, e.g. `1.93`, `stable`, or `nightly-2026-01-15`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    channel: Option<String>,
+    /// Additional components to install.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    components: Vec<String>,
+    /// Rustup profile to install with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+}
+
+impl ToolchainFile {
+    /// Creates a new toolchain file for the Rust `ver_req` with the given `components`.
+    ///
+    /// The `ver_req` must be a simple `=MAJOR.MINOR` version requirement.
+    pub fn new(ver_req: &VersionReq, components: Vec<String>) -> Self {
+        let channel = ver_req.to_string().trim_start_matches('=').to_string();
+        Self {
+            toolchain: Toolchain {
+                channel: Some(channel),
+                components,
+                profile: Some("minimal".into()),
+            },
+        }
+    }
+
+    /// Loads the toolchain file from the `root_dir`, if it exists.
+    pub fn load(root_dir: &Path) -> Result<Option<Self>> {
+        let path = root_dir.join(FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file_toml = fs::read(&path).context(format!(
+            "failed to read toolchain file '{}'",
+            path.display()
+        ))?;
+        let file = toml::from_slice(&file_toml).context(format!(
+            "failed to parse toolchain file '{}'",
+            path.display()
+        ))?;
+        Ok(Some(file))
+    }
+
+    /// Saves the toolchain file to the `root_dir`.
+    pub fn save(&self, root_dir: &Path) -> Result<()> {
+        let path = root_dir.join(FILE_NAME);
+        let file_toml = toml::to_string(self).context("failed to generate toolchain file TOML")?;
+        fs::write(&path, file_toml).context(format!(
+            "failed to write toolchain file '{}'",
+            path.display()
+        ))?;
+        Ok(())
+    }
+
+    /// Returns the Rust version requirement of the channel.
+    ///
+    /// Only numeric `MAJOR.MINOR` and `MAJOR.MINOR.PATCH` channels have one.
+    /// The patch version is dropped, because the toolchain is locked by `=MAJOR.MINOR`.
+    pub fn ver_req(&self) -> Option<VersionReq> {
+        let channel = self.toolchain.channel.as_deref()?;
+        let mut parts = channel.split('.');
+        let major = parts.next()?.parse::<u64>().ok()?;
+        let minor = parts.next()?.parse::<u64>().ok()?;
+        if parts
+            .next()
+            .is_some_and(|patch| patch.parse::<u64>().is_err())
+        {
+            return None;
+        }
+        VersionReq::parse(&format!("={major}.{minor}")).ok()
+    }
+
+    /// Returns the components listed in the toolchain file.
+    pub fn components(&self) -> &[String] {
+        &self.toolchain.components
+    }
+}
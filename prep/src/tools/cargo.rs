@@ -1,13 +1,32 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
 use anyhow::{Context, Result, bail, ensure};
+use regex::Regex;
 use semver::{Op, Version, VersionReq};
+use serde::Deserialize;
 
+use crate::tools::cargo_binstall::CargoBinstall;
+use crate::tools::curl::{self, Curl};
 use crate::tools::rustup::Rustup;
 use crate::tools::{BinCtx, Tool};
-use crate::toolset::Toolset;
-use crate::ui;
+use crate::toolset::{InstallStrategy, Toolset};
+use crate::{host, ui};
+
+/// The crates.io sparse index.
+const INDEX_URL: &str = "https://index.crates.io";
+
+/// A single version entry in the crates.io sparse index.
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: Version,
+    #[serde(default)]
+    yanked: bool,
+}
 
 /// Cargo from the Rust toolchain.
 pub struct Cargo;
@@ -19,6 +38,8 @@ pub struct CargoDeps {
     rustup_ver_req: Option<VersionReq>,
     /// Rust toolchain components.
     components: Vec<String>,
+    /// Rust toolchain channel, e.g. `nightly-2026-01-15`, which replaces version requirements.
+    channel: Option<String>,
 }
 
 impl CargoDeps {
@@ -29,6 +50,25 @@ impl CargoDeps {
         Self {
             rustup_ver_req: rustup_ver_req.into(),
             components,
+            channel: None,
+        }
+    }
+
+    /// Creates new Cargo dependency requirements for a specific toolchain `channel`.
+    ///
+    /// The `channel` is either `stable`, `beta`, or `nightly`, optionally followed by a date,
+    /// e.g. `nightly-2026-01-15`. Cargo with a channel must be requested without a version.
+    ///
+    /// `None` means that the default Rustup version will be used.
+    pub fn channel(
+        rustup_ver_req: impl Into<Option<VersionReq>>,
+        channel: String,
+        components: Vec<String>,
+    ) -> Self {
+        Self {
+            rustup_ver_req: rustup_ver_req.into(),
+            components,
+            channel: Some(channel),
         }
     }
 }
@@ -40,11 +80,36 @@ impl Tool for Cargo {
     const BIN: &str = "cargo";
     const MANAGED: bool = false;
 
+    fn default_binctx(toolset: &mut Toolset, deps: &Self::Deps) -> Result<BinCtx> {
+        let Some(channel) = &deps.channel else {
+            return Ok(toolset.binctx(Self::BIN.into()));
+        };
+        ensure!(
+            valid_channel(channel),
+            "Only `stable`, `beta`, or `nightly` Rust toolchain channels, \
+            optionally followed by a `-YYYY-MM-DD` date, are supported, got: {channel}"
+        );
+
+        // An already installed undated channel is used as is, without updating it.
+        install_toolchain(toolset, deps, channel)?;
+        let binctx = binctx(toolset, channel.clone());
+        if toolset.version::<Self>(&binctx)?.is_none() {
+            bail!("Rust toolchain '{channel}' was just installed but now was no longer found");
+        }
+
+        Ok(binctx)
+    }
+
     fn set_up(
         toolset: &mut Toolset,
         deps: &Self::Deps,
         ver_req: &VersionReq,
     ) -> Result<(BinCtx, Version)> {
+        if let Some(channel) = &deps.channel {
+            bail!(
+                "Rust toolchain channel '{channel}' can't be combined with a version requirement, got: {ver_req}"
+            );
+        }
         if ver_req.comparators.len() != 1 {
             bail!(
                 "Only simple `=MAJOR.MINOR` version requirements are supported for the Rust toolchain, got: {}",
@@ -64,55 +129,427 @@ impl Tool for Cargo {
         }
         let toolchain_name = format!("{}.{}", ver_req_comp.major, ver_req_comp.minor.unwrap());
 
-        // TODO: Maybe worth checking if it's already installed first? A non-network command probably then.
-        // TODO: Perhaps worth doing component delta checks and using the component commands to manage them.
-        //       Because then there wouldn't be a toolchain 1.92.0 -> 1.92.1 update just because of component.
+        install_toolchain(toolset, deps, &toolchain_name)?;
+        let binctx = binctx(toolset, toolchain_name);
 
-        // Set up the toolchain
-        let rustup = toolset.get::<Rustup>(&(), deps.rustup_ver_req.as_ref())?;
-        let mut cmd = rustup.cmd();
+        // Verify that it actually works.
+        let Some(version) = toolset
+            .verify::<Self>(&binctx, ver_req)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            bail!(
+                "'{}' was just installed but now was no longer found",
+                binctx.path().display()
+            );
+        };
+
+        Ok((binctx, version))
+    }
+}
+
+/// Returns `true` if `channel` is `stable`, `beta`, or `nightly`, optionally with a date.
+fn valid_channel(channel: &str) -> bool {
+    let re = Regex::new(r"^(stable|beta|nightly)(-\d{4}-\d{2}-\d{2})?$")
+        .expect("Toolchain channel regex was incorrect");
+    re.is_match(channel)
+}
+
+/// Installs the Rust toolchain `toolchain_name` with the components of `deps`.
+///
+/// If the toolchain is already installed, then only the missing components are added.
+/// That way an installed toolchain doesn't get updated, e.g. from 1.92.0 to 1.92.1,
+/// just because a component was missing.
+fn install_toolchain(toolset: &mut Toolset, deps: &CargoDeps, toolchain_name: &str) -> Result<()> {
+    let rustup = toolset.get::<Rustup>(&(), deps.rustup_ver_req.as_ref())?;
+
+    let mut cmd = rustup.cmd();
+    if let Some(installed) = installed_components(&rustup, toolchain_name)? {
+        let missing = deps
+            .components
+            .iter()
+            .filter(|c| !installed.contains(c))
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        cmd.arg("component")
+            .arg("add")
+            .args(["--toolchain", toolchain_name])
+            .args(missing);
+    } else {
         cmd.arg("toolchain")
             .arg("install")
-            .arg(&toolchain_name)
+            .arg(toolchain_name)
             .arg("--no-self-update")
             .args(["--profile", "minimal"]);
 
         if !deps.components.is_empty() {
             cmd.args(["--component", &deps.components.join(",")]);
         }
+    }
 
-        ui::print_cmd(&cmd);
+    ui::print_cmd(&cmd);
 
-        let status = cmd
-            .status()
-            .context(format!("failed to run {}", Rustup::NAME))?;
-        ensure!(status.success(), "{} failed: {status}", Rustup::NAME);
-
-        // We need to configure the toolchain version via an environment variable.
-        // This is because we want to run the correct rustfmt version when invoking `cargo fmt`.
-        // Directly running the correct cargo executable is not enough
-        // as it will still choose the default version of rustfmt.
-        // Unlike things like cargo-clippy or cargo-nextest, `cargo fmt` is not
-        // just a simple wrapper over rustfmt so we can't easily run it directly.
-        let environment = toolset.environment().clone().rust(Some(toolchain_name));
-        // Given that we use the environment for version control, we'll just use the binary name.
-        let binctx = BinCtx::new(
-            Self::BIN.into(),
-            toolset.working_dir().to_path_buf(),
-            environment,
-        );
+    let phase = format!("installing Rust {toolchain_name}");
+    let status = ui::progress::status(&mut cmd, &phase)
+        .context(format!("failed to run {}", Rustup::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", Rustup::NAME);
 
-        // Verify that it actually works.
-        let Some(version) = toolset
-            .verify::<Self>(&binctx, ver_req)
-            .context(format!("failed to verify {}", Self::NAME))?
-        else {
+    Ok(())
+}
+
+/// Returns the names of the components installed for the Rust toolchain `toolchain_name`.
+///
+/// Returns `None` if the toolchain is not installed.
+fn installed_components(rustup: &BinCtx, toolchain_name: &str) -> Result<Option<Vec<String>>> {
+    let mut cmd = rustup.cmd();
+    cmd.arg("component")
+        .arg("list")
+        .arg("--installed")
+        .args(["--toolchain", toolchain_name]);
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd
+        .output()
+        .context(format!("failed to run {}", Rustup::NAME))?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        if error.contains("is not installed") {
+            return Ok(None);
+        }
+        bail!("{} failed: {}\n{error}", Rustup::NAME, output.status);
+    }
+    let list = String::from_utf8(output.stdout)
+        .context(format!("{} output not valid UTF-8", Rustup::NAME))?;
+
+    // The components are listed with the host target suffix, e.g. `clippy-x86_64-pc-windows-msvc`.
+    let suffix = format!("-{}", host::TRIPLE);
+    let components = list
+        .lines()
+        .map(|line| line.trim())
+        .map(|line| line.strip_suffix(&suffix).unwrap_or(line).to_string())
+        .collect();
+    Ok(Some(components))
+}
+
+/// Returns the Cargo binary context for the Rust toolchain `toolchain_name`.
+fn binctx(toolset: &Toolset, toolchain_name: String) -> BinCtx {
+    // We need to configure the toolchain version via an environment variable.
+    // This is because we want to run the correct rustfmt version when invoking `cargo fmt`.
+    // Directly running the correct cargo executable is not enough
+    // as it will still choose the default version of rustfmt.
+    // Unlike things like cargo-clippy or cargo-nextest, `cargo fmt` is not
+    // just a simple wrapper over rustfmt so we can't easily run it directly.
+    let environment = toolset.environment().clone().rust(Some(toolchain_name));
+    // Given that we use the environment for version control, we'll just use the binary name.
+    BinCtx::new(
+        Cargo::BIN.into(),
+        toolset.working_dir().to_path_buf(),
+        environment,
+    )
+}
+
+/// Returns the environment variables which select the Rust toolchain of the `cargo` binary context.
+///
+/// Cargo subcommands which are run directly, e.g. `cargo-nextest`, need these
+/// to invoke the same Cargo when they in turn run Cargo.
+pub fn toolchain_vars(cargo: &BinCtx) -> BTreeMap<String, String> {
+    cargo
+        .environment()
+        .vars()
+        .iter()
+        .filter(|(k, _)| *k == "RUSTUP_TOOLCHAIN")
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Installs the tool `T` from source with `cargo install`.
+///
+/// The newest version satisfying `ver_req` is installed, see [`resolve_version`].
+///
+/// Returns the specific version and the binary context.
+pub fn install<T: Tool>(
+    toolset: &mut Toolset,
+    cargo_deps: &CargoDeps,
+    cargo_ver_req: Option<&VersionReq>,
+    ver_req: &VersionReq,
+) -> Result<(BinCtx, Version)> {
+    let version = resolve_version::<T>(toolset, ver_req)?;
+    toolset.clean_interrupted_install::<T>(&version)?;
+
+    // Prepare the install directory
+    let install_dir = toolset.install_dir(T::NAME, &version);
+    if install_dir.exists() {
+        if !empty_dir(&install_dir)? {
             bail!(
-                "'{}' was just installed but now was no longer found",
-                binctx.path().display()
+                "{} install directory '{}' unexpectedly already exists \
+                and is not an empty directory, aborting for safety.",
+                T::NAME,
+                install_dir.display()
             );
-        };
+        }
+    } else {
+        fs::create_dir_all(&install_dir).context(format!(
+            "failed to create install directory '{}'",
+            install_dir.display()
+        ))?;
+    }
 
-        Ok((binctx, version))
+    let temp_install_dir = toolset.temp_install_dir(T::NAME);
+    if temp_install_dir.exists() && !empty_dir(&temp_install_dir)? {
+        bail!(
+            "Temporary {} install directory '{}' unexpectedly already exists \
+            and is not an empty directory, aborting for safety.",
+            T::NAME,
+            temp_install_dir.display()
+        );
+    }
+    let bin_src_dir = temp_install_dir.join("bin");
+
+    // Prefer a prebuilt binary via cargo-binstall, as compiling from source takes minutes.
+    let strategy = if binstall::<T>(toolset, &version, &bin_src_dir)? {
+        InstallStrategy::Binstall
+    } else {
+        // Install it with Cargo
+        let cargo = toolset.get::<Cargo>(cargo_deps, cargo_ver_req)?;
+
+        let mut cmd = cargo.cmd();
+        cmd.arg("install")
+            .arg(T::PACKAGE)
+            .arg("--locked")
+            .args(["--version", &version.to_string()])
+            .arg("--root")
+            .arg(temp_install_dir.as_os_str());
+
+        ui::print_cmd(&cmd);
+
+        let phase = format!("installing {} {version}", T::NAME);
+        let status =
+            ui::progress::status(&mut cmd, &phase).context("failed to run cargo install")?;
+        ensure!(status.success(), "cargo install failed: {status}");
+        InstallStrategy::Source
+    };
+
+    // Copy the binary to the install directory
+    let manifest_a = temp_install_dir.join(".crates.toml");
+    let manifest_b = temp_install_dir.join(".crates2.json");
+    let bin_name = host::executable_name(T::BIN);
+    let bin_src = bin_src_dir.join(&bin_name);
+    let bin_dst = install_dir.join(&bin_name);
+
+    if !bin_src.exists() {
+        bail!(
+            "{} binary at '{}' unexpectedly not found, aborting.",
+            T::NAME,
+            bin_src.display()
+        );
+    }
+    if bin_dst.exists() {
+        bail!(
+            "{} binary at '{}' unexpectedly already exists, aborting.",
+            T::NAME,
+            bin_dst.display()
+        );
+    }
+    fs::copy(&bin_src, &bin_dst).context(format!(
+        "failed to copy {} binary from '{}' to '{}'",
+        T::NAME,
+        bin_src.display(),
+        bin_dst.display()
+    ))?;
+
+    // Safely clean up the temporary directory
+    fs::remove_file(&bin_src).context(format!(
+        "failed to remove {} binary at '{}'",
+        T::NAME,
+        bin_src.display()
+    ))?;
+    fs::remove_dir(&bin_src_dir).context(format!(
+        "failed to remove temporary directory '{}'",
+        bin_src_dir.display()
+    ))?;
+    // cargo-binstall doesn't track its installations, so only Cargo leaves manifests behind.
+    for manifest in [&manifest_a, &manifest_b] {
+        if manifest.exists() {
+            fs::remove_file(manifest).context(format!(
+                "failed to remove temporary manifest file at '{}'",
+                manifest.display()
+            ))?;
+        }
+    }
+    fs::remove_dir(&temp_install_dir).context(format!(
+        "failed to remove temporary directory '{}'",
+        temp_install_dir.display()
+    ))?;
+
+    // Verify that the installed version is correct
+    let binctx = toolset.binctx(bin_dst);
+
+    let Some(version) = toolset
+        .verify::<T>(&binctx, ver_req)
+        .context(format!("failed to verify {}", T::NAME))?
+    else {
+        bail!(
+            "'{}' was just installed but now was no longer found",
+            binctx.path().display()
+        );
+    };
+    toolset.set_install_strategy(T::NAME, strategy);
+
+    Ok((binctx, version))
+}
+
+/// Installs a prebuilt binary of `version` of the tool `T` into `bin_dir` with cargo-binstall.
+///
+/// Returns `false` if cargo-binstall isn't available or failed to find a prebuilt binary,
+/// in which case the tool needs to be built from source instead.
+fn binstall<T: Tool>(toolset: &mut Toolset, version: &Version, bin_dir: &Path) -> Result<bool> {
+    let binstall = toolset.get::<CargoBinstall>(&(), None)?;
+    if toolset.version::<CargoBinstall>(&binstall)?.is_none() {
+        ui::print_debug(&format!(
+            "{} not found, building {} from source",
+            CargoBinstall::NAME,
+            T::NAME
+        ));
+        return Ok(false);
+    }
+
+    let mut cmd = binstall.cmd();
+    cmd.arg(format!("{}@{version}", T::PACKAGE))
+        .args(["--no-confirm", "--no-track", "--locked"])
+        // Only prebuilt binaries, as building from source is done by Prep itself.
+        .args(["--disable-strategies", "compile"])
+        .arg("--install-path")
+        .arg(bin_dir.as_os_str());
+
+    ui::print_cmd(&cmd);
+
+    let phase = format!("downloading {} {version}", T::NAME);
+    let status = ui::progress::status(&mut cmd, &phase).context("failed to run cargo-binstall")?;
+    if status.success() && bin_dir.join(host::executable_name(T::BIN)).exists() {
+        return Ok(true);
+    }
+
+    ui::print_warn(&format!(
+        "{} failed to install a prebuilt {}: {status}\nFalling back to building it from source.",
+        CargoBinstall::NAME,
+        T::NAME
+    ));
+    // Leave the temporary directory as Cargo expects it.
+    for dir in [Some(bin_dir), bin_dir.parent()].into_iter().flatten() {
+        if dir.exists() && empty_dir(dir)? {
+            fs::remove_dir(dir).context(format!(
+                "failed to remove temporary directory '{}'",
+                dir.display()
+            ))?;
+        }
+    }
+    Ok(false)
+}
+
+/// Returns the exact version specified by a simple `=MAJOR.MINOR.PATCH` version requirement.
+pub fn exact_version<T: Tool>(ver_req: &VersionReq) -> Result<Version> {
+    if ver_req.comparators.len() != 1 {
+        bail!(
+            "Only simple `=MAJOR.MINOR.PATCH` version requirements \
+            are supported for {}, got: {}",
+            T::NAME,
+            ver_req
+        );
+    }
+    let ver_req_comp = ver_req.comparators.first().unwrap();
+    if ver_req_comp.op != Op::Exact
+        || ver_req_comp.minor.is_none()
+        || ver_req_comp.patch.is_none()
+        || !ver_req_comp.pre.is_empty()
+    {
+        bail!(
+            "Only simple `=MAJOR.MINOR.PATCH` version requirements \
+            are supported for {}, got: {}",
+            T::NAME,
+            ver_req_comp
+        );
+    }
+    Ok(Version::new(
+        ver_req_comp.major,
+        ver_req_comp.minor.unwrap(),
+        ver_req_comp.patch.unwrap(),
+    ))
+}
+
+/// Returns the specific version of the tool `T` to install for `ver_req`.
+///
+/// Simple `=MAJOR.MINOR.PATCH` version requirements are used as is.
+/// Other requirements, e.g. `^14` or `>=14, <16`, are resolved to the newest matching version
+/// published on crates.io, which requires network access.
+pub fn resolve_version<T: Tool>(toolset: &mut Toolset, ver_req: &VersionReq) -> Result<Version> {
+    if let Ok(version) = exact_version::<T>(ver_req) {
+        return Ok(version);
+    }
+    let version = published_versions(toolset, T::PACKAGE)?
+        .into_iter()
+        .filter(|version| ver_req.matches(version))
+        .max()
+        .context(format!(
+            "no published {} version on crates.io satisfies {ver_req}",
+            T::NAME
+        ))?;
+    ui::print_debug(&format!(
+        "Resolved {} {ver_req} to {version} via crates.io",
+        T::NAME
+    ));
+    Ok(version)
+}
+
+/// Returns all the non-yanked versions of `package` published on crates.io.
+pub fn published_versions(toolset: &mut Toolset, package: &str) -> Result<Vec<Version>> {
+    let curl = toolset.get::<Curl>(&(), None)?;
+
+    let url = format!("{INDEX_URL}/{}", index_path(package));
+    let mut cmd = curl::download_cmd(&curl, toolset.network(), &url);
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd.output().context("failed to run curl")?;
+    ensure!(
+        output.status.success(),
+        "failed to fetch {package} from the crates.io index: {}",
+        output.status
+    );
+    let index = String::from_utf8(output.stdout).context("crates.io index not valid UTF-8")?;
+
+    let mut versions = Vec::new();
+    for line in index.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: IndexEntry = serde_json::from_str(line).context(format!(
+            "failed to parse crates.io index entry of {package}"
+        ))?;
+        if !entry.yanked {
+            versions.push(entry.vers);
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Returns the path of `package` in the crates.io sparse index.
+fn index_path(package: &str) -> String {
+    let package = package.to_lowercase();
+    match package.len() {
+        1 => format!("1/{package}"),
+        2 => format!("2/{package}"),
+        3 => format!("3/{}/{package}", &package[..1]),
+        _ => format!("{}/{}/{package}", &package[..2], &package[2..4]),
+    }
+}
+
+/// Returns `true` if `path` is a directory and is empty.
+pub fn empty_dir(path: &Path) -> Result<bool> {
+    if !path.is_dir() {
+        return Ok(false);
     }
+    let read_dir = path
+        .read_dir()
+        .context(format!("failed to read directory '{}'", path.display()))?;
+    Ok(read_dir.count() == 0)
 }
@@ -0,0 +1,50 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
+
+use crate::tools::{BinCtx, Tool};
+use crate::toolset::Toolset;
+use crate::ui;
+
+/// cargo-binstall for installing prebuilt binaries of Cargo packages.
+///
+/// It is optional, when it's not available the managed tools are built from source instead.
+pub struct CargoBinstall;
+
+impl Tool for CargoBinstall {
+    type Deps = ();
+
+    const NAME: &str = "cargo-binstall";
+    const BIN: &str = "cargo-binstall";
+    const MANAGED: bool = false;
+    // Only the version is reported, without the binary name in front of it.
+    const VERSION_REGEX: &str = r"^(?:\S+\s+)?(\d+\.\d+\.\d+[^\s]*)";
+
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        // Check if the default cargo-binstall installation already meets the requirement.
+        let binctx = Self::default_binctx(toolset, deps)?;
+
+        let Some(version) = toolset
+            .verify::<Self>(&binctx, ver_req)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            ui::print_err(
+                "\
+				This Prep command requires cargo-binstall.\n\
+				\n\
+				There is no automatic setup implemented for it, sorry.\n\
+				Please go to https://github.com/cargo-bins/cargo-binstall and install it manually.\n\
+				",
+            );
+            bail!("{} not found", Self::NAME);
+        };
+
+        Ok((binctx, version))
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anyhow::Result;
+use semver::{Version, VersionReq};
+
+use crate::tools::cargo::{self, CargoDeps};
+use crate::tools::{BinCtx, Tool};
+use crate::toolset::Toolset;
+
+/// cargo-hakari for managing workspace-hack crates.
+pub struct CargoHakari;
+
+/// cargo-hakari dependencies.
+#[derive(Default)]
+pub struct CargoHakariDeps {
+    /// Cargo dependencies.
+    cargo_deps: CargoDeps,
+    /// Cargo version requirement.
+    cargo_ver_req: Option<VersionReq>,
+}
+
+impl CargoHakariDeps {
+    /// Creates new cargo-hakari dependency requirements.
+    ///
+    /// `None` means that the default version will be used.
+    pub fn new(cargo_deps: CargoDeps, cargo_ver_req: impl Into<Option<VersionReq>>) -> Self {
+        Self {
+            cargo_deps,
+            cargo_ver_req: cargo_ver_req.into(),
+        }
+    }
+}
+
+impl Tool for CargoHakari {
+    type Deps = CargoHakariDeps;
+
+    const NAME: &str = "cargo-hakari";
+    const BIN: &str = "cargo-hakari";
+    const MANAGED: bool = true;
+
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        cargo::install::<Self>(
+            toolset,
+            &deps.cargo_deps,
+            deps.cargo_ver_req.as_ref(),
+            ver_req,
+        )
+    }
+}
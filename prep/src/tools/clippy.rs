@@ -50,19 +50,7 @@ impl Tool for Clippy {
         deps: &Self::Deps,
         ver_req: &VersionReq,
     ) -> Result<(BinCtx, Version)> {
-        // Directly calling set_up will bypass the toolset cache
-        // and allows us to set up the potentially missing components.
-        let cargo_ver_req = deps.cargo_ver_req.as_ref().unwrap_or_else(|| {
-            panic!(
-                "{} set up requires a specific {} version dependency",
-                Self::NAME,
-                Cargo::NAME
-            )
-        });
-        let (cargo, _) = Cargo::set_up(toolset, &deps.cargo_deps, cargo_ver_req)
-            .context(format!("failed to set up {}", Cargo::NAME))?;
-
-        let binctx = cargo.args(vec!["clippy".into()]);
+        let binctx = set_up_cargo(toolset, deps)?;
 
         // Verify that it actually works.
         let Some(version) = toolset
@@ -77,4 +65,40 @@ impl Tool for Clippy {
 
         Ok((binctx, version))
     }
+
+    fn set_up_any(toolset: &mut Toolset, deps: &Self::Deps) -> Result<(BinCtx, Version)> {
+        let binctx = set_up_cargo(toolset, deps)?;
+
+        // Verify that it actually works.
+        let Some(version) = toolset
+            .version::<Self>(&binctx)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            bail!(
+                "'{}' was just installed but now was no longer found",
+                binctx.path().display()
+            );
+        };
+
+        Ok((binctx, version))
+    }
+}
+
+/// Sets up Cargo with the components required by Clippy.
+///
+/// Returns the binary context that runs `cargo clippy`.
+fn set_up_cargo(toolset: &mut Toolset, deps: &ClippyDeps) -> Result<BinCtx> {
+    // Directly calling set_up will bypass the toolset cache
+    // and allows us to set up the potentially missing components.
+    let cargo_ver_req = deps.cargo_ver_req.as_ref().unwrap_or_else(|| {
+        panic!(
+            "{} set up requires a specific {} version dependency",
+            Clippy::NAME,
+            Cargo::NAME
+        )
+    });
+    let (cargo, _) = Cargo::set_up(toolset, &deps.cargo_deps, cargo_ver_req)
+        .context(format!("failed to set up {}", Cargo::NAME))?;
+
+    Ok(cargo.args(vec!["clippy".into()]))
 }
@@ -0,0 +1,68 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
+
+use crate::config::Network;
+use crate::tools::{BinCtx, Tool};
+use crate::toolset::Toolset;
+use crate::ui;
+
+/// Curl for downloading files.
+pub struct Curl;
+
+impl Tool for Curl {
+    type Deps = ();
+
+    const NAME: &str = "curl";
+    const BIN: &str = "curl";
+    const MANAGED: bool = false;
+
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        // Check if the default curl installation already meets the requirement.
+        let binctx = Self::default_binctx(toolset, deps)?;
+
+        let Some(version) = toolset
+            .verify::<Self>(&binctx, ver_req)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            ui::print_err(
+                "\
+				This Prep command requires curl.\n\
+				\n\
+				There is no automatic setup implemented for it, sorry.\n\
+				Please go to https://curl.se/ and install it manually.\n\
+				",
+            );
+            bail!("{} not found", Self::NAME);
+        };
+
+        Ok((binctx, version))
+    }
+}
+
+/// Returns a curl [`Command`] for downloading `url` that respects the `network` configuration.
+///
+/// The downloaded data is written to stdout unless an `--output` argument is added.
+pub fn download_cmd(curl: &BinCtx, network: &Network, url: &str) -> Command {
+    let mut cmd = curl.cmd();
+    cmd.args(["--fail", "--location", "--silent", "--show-error"]);
+    if let Some(proxy) = network.proxy() {
+        cmd.args(["--proxy", proxy]);
+    }
+    if let Some(timeout) = network.timeout() {
+        cmd.args(["--max-time", &timeout.to_string()]);
+    }
+    if let Some(retries) = network.retries() {
+        cmd.args(["--retry", &retries.to_string()]);
+    }
+    cmd.arg(network.mirror_url(url));
+    cmd
+}
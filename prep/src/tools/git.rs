@@ -0,0 +1,122 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::{env, fs};
+
+use anyhow::{Context, Result, bail, ensure};
+use semver::{Version, VersionReq};
+
+use crate::tools::{BinCtx, Tool};
+use crate::toolset::Toolset;
+use crate::ui;
+
+/// Git version control.
+pub struct Git;
+
+impl Tool for Git {
+    type Deps = ();
+
+    const NAME: &str = "git";
+    const BIN: &str = "git";
+    const MANAGED: bool = false;
+    // Windows builds have a suffix like `2.43.0.windows.1` which is not a valid semver version.
+    const VERSION_REGEX: &str = r"^git version (\d+\.\d+\.\d+)";
+
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        // Check if the default Git installation already meets the requirement.
+        let binctx = Self::default_binctx(toolset, deps)?;
+
+        let Some(version) = toolset
+            .verify::<Self>(&binctx, ver_req)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            ui::print_err(
+                "\
+				This Prep command requires Git.\n\
+				\n\
+				There is no automatic setup implemented for it, sorry.\n\
+				Please go to https://git-scm.com/ and install it manually.\n\
+				",
+            );
+            bail!("{} not found", Self::NAME);
+        };
+
+        Ok((binctx, version))
+    }
+}
+
+/// Returns the Git tree hash of the working tree, including uncommitted and untracked files.
+///
+/// The hash is computed with a temporary copy of the index, so the real index is left untouched.
+/// Files matching the `exclude` pathspecs are left out.
+pub fn tree_hash(git: &BinCtx, exclude: &[&Path]) -> Result<String> {
+    let index = output(git, &["rev-parse", "--git-path", "index"])?;
+    let index = git.working_dir().join(index.trim());
+    let temp_index = env::temp_dir().join(format!("prep-index-{}", process::id()));
+    if index.exists() {
+        fs::copy(&index, &temp_index).context(format!(
+            "failed to copy Git index '{}' to '{}'",
+            index.display(),
+            temp_index.display()
+        ))?;
+    }
+
+    let result = write_tree(git, &temp_index, exclude);
+    // Clean up the temporary index, even if writing the tree failed.
+    if temp_index.exists() {
+        fs::remove_file(&temp_index).context(format!(
+            "failed to remove temporary Git index '{}'",
+            temp_index.display()
+        ))?;
+    }
+    result
+}
+
+/// Returns the Git hooks directory, respecting the `core.hooksPath` setting.
+pub fn hooks_dir(git: &BinCtx) -> Result<PathBuf> {
+    let dir = output(git, &["rev-parse", "--git-path", "hooks"])?;
+    Ok(git.working_dir().join(dir.trim()))
+}
+
+/// Adds all the files to the `index` and writes it as a tree, returning the tree hash.
+fn write_tree(git: &BinCtx, index: &Path, exclude: &[&Path]) -> Result<String> {
+    let mut cmd = git.cmd();
+    cmd.env("GIT_INDEX_FILE", index)
+        .args(["add", "--all", "--", "."]);
+    for path in exclude {
+        cmd.arg(format!(":(exclude){}", path.display()));
+    }
+    run(cmd)?;
+
+    let mut cmd = git.cmd();
+    cmd.env("GIT_INDEX_FILE", index).arg("write-tree");
+    let tree = run(cmd)?;
+    Ok(tree.trim().to_string())
+}
+
+/// Runs Git with the given `args` and returns its stdout.
+pub fn output(git: &BinCtx, args: &[&str]) -> Result<String> {
+    let mut cmd = git.cmd();
+    cmd.args(args);
+    run(cmd)
+}
+
+/// Runs the Git `cmd` and returns its stdout.
+fn run(mut cmd: Command) -> Result<String> {
+    ui::print_cmd(&cmd);
+
+    let output = cmd.output().context("failed to run git")?;
+    ensure!(
+        output.status.success(),
+        "git failed: {}\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).context("git output not valid UTF-8")
+}
@@ -2,39 +2,68 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 pub mod cargo;
+pub mod cargo_binstall;
+pub mod cargo_bloat;
+pub mod cargo_deny;
+pub mod cargo_geiger;
+pub mod cargo_hakari;
+pub mod cargo_msrv;
+pub mod cargo_nextest;
+pub mod cargo_sort;
+pub mod cargo_vet;
 pub mod clippy;
+pub mod curl;
+pub mod git;
+pub mod git_cliff;
+pub mod lychee;
+pub mod prep;
 pub mod ripgrep;
 pub mod rustfmt;
 pub mod rustup;
+pub mod taplo;
+pub mod tar;
+pub mod wasm_bindgen;
 
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::{fmt, fs};
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, bail, ensure};
 use regex::Regex;
 use semver::{Version, VersionReq};
 
 use crate::environment::Environment;
-use crate::toolset::Toolset;
-use crate::ui;
+use crate::hash;
+use crate::tools::cargo::empty_dir;
+use crate::tools::curl::Curl;
+use crate::tools::tar::Tar;
+use crate::toolset::{InstallStrategy, Toolset};
+use crate::{host, ui};
 
 /// Generic Prep tool code.
 pub trait Tool: Sized + 'static {
     /// The type describing the dependencies of this tool.
     type Deps: Default;
 
-    /// The name of the tool, used for both display and to identify the cargo package.
+    /// The name of the tool, used for display and in the toolset manifest.
     const NAME: &str;
+    /// The Cargo package name of the tool.
+    const PACKAGE: &str = Self::NAME;
     /// The binary executable name.
     const BIN: &str;
     /// Whether the tool installation is managed by toolset.
     const MANAGED: bool;
+    /// The regex for extracting the version from the first line of `--version` output.
+    const VERSION_REGEX: &str = r"^\S+\s+(\d+\.\d+\.\d+[^\s]*)";
 
     /// Returns the default binary context for this tool
     #[expect(unused_variables, reason = "default impl doesn't use deps")]
     fn default_binctx(toolset: &mut Toolset, deps: &Self::Deps) -> Result<BinCtx> {
-        Ok(toolset.binctx(Self::BIN.into()))
+        let path = toolset.environment().vars().get("PATH").map(OsStr::new);
+        Ok(toolset.binctx(host::resolve_executable(Self::BIN, path)))
     }
 
     /// Sets up a version of the tool that meets the given `ver_req`.
@@ -50,6 +79,22 @@ pub trait Tool: Sized + 'static {
         ver_req: &VersionReq,
     ) -> Result<(BinCtx, Version)>;
 
+    /// Sets up the tool in whichever version its dependencies provide.
+    ///
+    /// This is how [`ToolVersionReq::Any`] is satisfied when the tool is missing,
+    /// which is only supported by tools that are coupled to another tool, e.g. the Rust toolchain.
+    ///
+    /// Returns the specific version and the binary context.
+    ///
+    /// [`ToolVersionReq::Any`]: crate::toolset::ToolVersionReq::Any
+    #[expect(unused_variables, reason = "default impl doesn't use deps")]
+    fn set_up_any(toolset: &mut Toolset, deps: &Self::Deps) -> Result<(BinCtx, Version)> {
+        bail!(
+            "{} can only be set up for a specific version requirement",
+            Self::NAME
+        )
+    }
+
     /// Returns the [`Version`] of the given binary context.
     ///
     /// Returns `None` if the given binary context's path doesn't exist.
@@ -92,8 +137,7 @@ pub trait Tool: Sized + 'static {
             .next()
             .context(format!("'{}' output was empty", binctx.path().display()))?;
 
-        let re = Regex::new(r"^\S+\s+(\d+\.\d+\.\d+[^\s]*)")
-            .expect("Version extraction regex was incorrect");
+        let re = Regex::new(Self::VERSION_REGEX).expect("Version extraction regex was incorrect");
         let version = re
             .captures(version)
             .and_then(|c| c.get(1).map(|m| m.as_str()))
@@ -131,6 +175,12 @@ impl BinCtx {
         }
     }
 
+    /// Returns the binary context with the additional environment variables `vars`.
+    pub fn env(mut self, vars: &BTreeMap<String, String>) -> Self {
+        self.environment = self.environment.custom(vars);
+        self
+    }
+
     /// Returns the binary context with the given base arguments.
     pub fn args(mut self, args: Vec<String>) -> Self {
         self.args = args;
@@ -150,4 +200,241 @@ impl BinCtx {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Returns the working directory.
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// Returns the environment variables.
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+}
+
+/// A downloaded archive whose SHA-256 hash doesn't match its published checksum.
+///
+/// It may be a sign of tampering, so it must not be worked around with another install method.
+#[derive(Debug)]
+pub struct ChecksumMismatch {
+    tool: &'static str,
+    url: String,
+    actual: String,
+    expected: String,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} archive from '{}' has SHA-256 hash {} but expected {}, aborting.",
+            self.tool, self.url, self.actual, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// Installs the tool `T` by downloading a prebuilt archive from `url`.
+///
+/// The `archive_bin` is the path of the binary inside the archive,
+/// which will be the only file extracted from it.
+///
+/// If `checksum_url` is provided, the archive's SHA-256 hash is verified against it before extraction.
+/// The checksum file is expected to be in the `sha256sum` output format.
+///
+/// Returns the specific version and the binary context.
+pub fn install_prebuilt<T: Tool>(
+    toolset: &mut Toolset,
+    ver_req: &VersionReq,
+    version: &Version,
+    url: &str,
+    checksum_url: Option<&str>,
+    archive_bin: &Path,
+) -> Result<(BinCtx, Version)> {
+    toolset.clean_interrupted_install::<T>(version)?;
+
+    // Prepare the install directory
+    let install_dir = toolset.install_dir(T::NAME, version);
+    if install_dir.exists() {
+        if !empty_dir(&install_dir)? {
+            bail!(
+                "{} install directory '{}' unexpectedly already exists \
+                and is not an empty directory, aborting for safety.",
+                T::NAME,
+                install_dir.display()
+            );
+        }
+    } else {
+        fs::create_dir_all(&install_dir).context(format!(
+            "failed to create install directory '{}'",
+            install_dir.display()
+        ))?;
+    }
+
+    let temp_install_dir = toolset.temp_install_dir(T::NAME);
+    if temp_install_dir.exists() {
+        if !empty_dir(&temp_install_dir)? {
+            bail!(
+                "Temporary {} install directory '{}' unexpectedly already exists \
+                and is not an empty directory, aborting for safety.",
+                T::NAME,
+                temp_install_dir.display()
+            );
+        }
+    } else {
+        fs::create_dir_all(&temp_install_dir).context(format!(
+            "failed to create temporary directory '{}'",
+            temp_install_dir.display()
+        ))?;
+    }
+
+    let archive_name = url.rsplit('/').next().unwrap_or(url);
+    let archive = temp_install_dir.join(archive_name);
+    let bin_dst = install_dir.join(host::executable_name(T::BIN));
+
+    let result = download_bin::<T>(toolset, url, checksum_url, &archive, archive_bin, &bin_dst);
+    // Safely clean up the temporary directory, even if the download failed.
+    let cleanup = remove_download(&temp_install_dir, &archive, archive_bin);
+    result?;
+    cleanup?;
+
+    // Verify that the installed version is correct
+    let binctx = toolset.binctx(bin_dst);
+
+    let Some(version) = toolset
+        .verify::<T>(&binctx, ver_req)
+        .context(format!("failed to verify {}", T::NAME))?
+    else {
+        bail!(
+            "'{}' was just installed but now was no longer found",
+            binctx.path().display()
+        );
+    };
+    toolset.set_install_strategy(T::NAME, InstallStrategy::Prebuilt);
+
+    Ok((binctx, version))
+}
+
+/// Downloads the archive from `url` to `archive` and copies its `archive_bin` to `bin_dst`.
+fn download_bin<T: Tool>(
+    toolset: &mut Toolset,
+    url: &str,
+    checksum_url: Option<&str>,
+    archive: &Path,
+    archive_bin: &Path,
+    bin_dst: &Path,
+) -> Result<()> {
+    let archive_dir = archive.parent().expect("archive path must have a parent");
+
+    // Download the archive
+    let curl = toolset.get::<Curl>(&(), None)?;
+    let mut cmd = curl::download_cmd(&curl, toolset.network(), url);
+    cmd.arg("--output").arg(archive.as_os_str());
+
+    ui::print_cmd(&cmd);
+
+    let phase = format!("downloading {}", T::NAME);
+    let status = ui::progress::status(&mut cmd, &phase).context("failed to run curl")?;
+    ensure!(status.success(), "curl failed: {status}");
+
+    // Verify the archive checksum
+    if let Some(checksum_url) = checksum_url {
+        let mut cmd = curl::download_cmd(&curl, toolset.network(), checksum_url);
+
+        ui::print_cmd(&cmd);
+
+        let output = cmd.output().context("failed to run curl")?;
+        ensure!(output.status.success(), "curl failed: {}", output.status);
+
+        let checksum = String::from_utf8(output.stdout).context("checksum file not valid UTF-8")?;
+        let expected = checksum
+            .split_whitespace()
+            .next()
+            .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .context(format!(
+                "checksum file at '{checksum_url}' didn't contain a SHA-256 hash"
+            ))?
+            .to_ascii_lowercase();
+        let actual = hash::sha256_file(archive)?;
+        if actual != expected {
+            return Err(ChecksumMismatch {
+                tool: T::NAME,
+                url: url.to_string(),
+                actual,
+                expected,
+            }
+            .into());
+        }
+    }
+
+    // Extract only the binary
+    let tar = toolset.get::<Tar>(&(), None)?;
+    let mut cmd = tar.cmd();
+    cmd.arg("-xf")
+        .arg(archive.as_os_str())
+        .arg("-C")
+        .arg(archive_dir.as_os_str())
+        .arg(archive_bin.to_string_lossy().replace('\\', "/"));
+
+    ui::print_cmd(&cmd);
+
+    let status = cmd.status().context("failed to run tar")?;
+    ensure!(status.success(), "tar failed: {status}");
+
+    // Copy the binary to the install directory
+    let bin_src = archive_dir.join(archive_bin);
+    if !bin_src.exists() {
+        bail!(
+            "{} binary at '{}' unexpectedly not found, aborting.",
+            T::NAME,
+            bin_src.display()
+        );
+    }
+    if bin_dst.exists() {
+        bail!(
+            "{} binary at '{}' unexpectedly already exists, aborting.",
+            T::NAME,
+            bin_dst.display()
+        );
+    }
+    fs::copy(&bin_src, bin_dst).context(format!(
+        "failed to copy {} binary from '{}' to '{}'",
+        T::NAME,
+        bin_src.display(),
+        bin_dst.display()
+    ))?;
+
+    Ok(())
+}
+
+/// Removes the downloaded `archive` and the extracted `archive_bin` from `temp_dir`,
+/// and then removes `temp_dir` itself.
+///
+/// Only the expected files are removed, so this errors if `temp_dir` contains anything else.
+fn remove_download(temp_dir: &Path, archive: &Path, archive_bin: &Path) -> Result<()> {
+    let bin = temp_dir.join(archive_bin);
+    for file in [bin.as_path(), archive] {
+        if file.exists() {
+            fs::remove_file(file)
+                .context(format!("failed to remove file at '{}'", file.display()))?;
+        }
+    }
+    for dir in archive_bin.ancestors().skip(1) {
+        if dir.as_os_str().is_empty() {
+            break;
+        }
+        let dir = temp_dir.join(dir);
+        if dir.exists() {
+            fs::remove_dir(&dir).context(format!(
+                "failed to remove temporary directory '{}'",
+                dir.display()
+            ))?;
+        }
+    }
+    fs::remove_dir(temp_dir).context(format!(
+        "failed to remove temporary directory '{}'",
+        temp_dir.display()
+    ))?;
+    Ok(())
 }
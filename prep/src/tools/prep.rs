@@ -0,0 +1,83 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
+
+use crate::host;
+use crate::tools::{self, BinCtx, Tool, cargo};
+use crate::toolset::Toolset;
+
+/// Prep itself, for updating to a newer release.
+pub struct Prep;
+
+impl Tool for Prep {
+    type Deps = ();
+
+    const NAME: &str = "prep";
+    const BIN: &str = "prep";
+    const MANAGED: bool = false;
+
+    #[expect(unused_variables, reason = "Prep has no dependencies")]
+    fn default_binctx(toolset: &mut Toolset, deps: &Self::Deps) -> Result<BinCtx> {
+        let path = env::current_exe().context("failed to get the current executable path")?;
+        Ok(toolset.binctx(path))
+    }
+
+    #[expect(unused_variables, reason = "Prep has no dependencies")]
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        let version = cargo::exact_version::<Self>(ver_req)?;
+
+        let Some(triple) = prebuilt_triple(host::TRIPLE) else {
+            bail!(
+                "there is no prebuilt {} binary for {}, install it with `cargo install {}` instead",
+                Self::NAME,
+                host::TRIPLE,
+                Self::PACKAGE
+            );
+        };
+        let ext = if triple.contains("windows") {
+            "zip"
+        } else {
+            "tar.gz"
+        };
+        let archive_dir = format!("prep-{version}-{triple}");
+        let url = format!(
+            "https://github.com/Nevermore/prep/releases/download/v{version}/{archive_dir}.{ext}"
+        );
+        let archive_bin = PathBuf::from(archive_dir).join(host::executable_name(Self::BIN));
+        let checksum_url = format!("{url}.sha256");
+        tools::install_prebuilt::<Self>(
+            toolset,
+            ver_req,
+            &version,
+            &url,
+            Some(&checksum_url),
+            &archive_bin,
+        )
+    }
+}
+
+/// Returns the triple of the official prebuilt Prep binary that runs on the `host` triple.
+///
+/// Returns `None` if there is no such prebuilt binary.
+fn prebuilt_triple(host: &str) -> Option<&'static str> {
+    let triple = match host {
+        // The Linux binaries are statically linked with musl and thus also run on glibc.
+        "x86_64-unknown-linux-gnu" | "x86_64-unknown-linux-musl" => "x86_64-unknown-linux-musl",
+        "aarch64-unknown-linux-gnu" | "aarch64-unknown-linux-musl" => "aarch64-unknown-linux-musl",
+        "x86_64-apple-darwin" => "x86_64-apple-darwin",
+        "aarch64-apple-darwin" => "aarch64-apple-darwin",
+        "x86_64-pc-windows-msvc" => "x86_64-pc-windows-msvc",
+        "aarch64-pc-windows-msvc" => "aarch64-pc-windows-msvc",
+        _ => return None,
+    };
+    Some(triple)
+}
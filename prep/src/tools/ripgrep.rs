@@ -1,14 +1,13 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
 
-use anyhow::{Context, Result, bail, ensure};
-use semver::{Op, Version, VersionReq};
+use anyhow::Result;
+use semver::{Version, VersionReq};
 
-use crate::tools::cargo::{Cargo, CargoDeps};
-use crate::tools::{BinCtx, Tool};
+use crate::tools::cargo::{self, CargoDeps};
+use crate::tools::{self, BinCtx, ChecksumMismatch, Tool};
 use crate::toolset::Toolset;
 use crate::{host, ui};
 
@@ -48,151 +47,64 @@ impl Tool for Ripgrep {
         deps: &Self::Deps,
         ver_req: &VersionReq,
     ) -> Result<(BinCtx, Version)> {
-        if ver_req.comparators.len() != 1 {
-            bail!(
-                "Only simple `=MAJOR.MINOR.PATCH` version requirements \
-                are supported for ripgrep, got: {}",
-                ver_req
+        let version = cargo::resolve_version::<Self>(toolset, ver_req)?;
+
+        // Prefer the official prebuilt binary as compiling from source takes minutes.
+        if let Some(triple) = prebuilt_triple(host::TRIPLE) {
+            let ext = if triple.contains("windows") {
+                "zip"
+            } else {
+                "tar.gz"
+            };
+            let archive_dir = format!("ripgrep-{version}-{triple}");
+            let url = format!(
+                "https://github.com/BurntSushi/ripgrep/releases/download/{version}/{archive_dir}.{ext}"
             );
-        }
-        let ver_req_comp = ver_req.comparators.first().unwrap();
-        if ver_req_comp.op != Op::Exact
-            || ver_req_comp.minor.is_none()
-            || ver_req_comp.patch.is_none()
-            || !ver_req_comp.pre.is_empty()
-        {
-            bail!(
-                "Only simple `=MAJOR.MINOR.PATCH` version requirements \
-                are supported for ripgrep, got: {}",
-                ver_req_comp
-            );
-        }
-        let version = Version::new(
-            ver_req_comp.major,
-            ver_req_comp.minor.unwrap(),
-            ver_req_comp.patch.unwrap(),
-        );
-
-        // Prepare the install directory
-        let install_dir = toolset.install_dir(Self::NAME, &version);
-        if install_dir.exists() {
-            if !empty_dir(&install_dir)? {
-                bail!(
-                    "{} install directory '{}' unexpectedly already exists \
-                    and is not an empty directory, aborting for safety.",
-                    Self::NAME,
-                    install_dir.display()
-                );
+            let archive_bin = PathBuf::from(archive_dir).join(host::executable_name(Self::BIN));
+            let checksum_url = format!("{url}.sha256");
+            match tools::install_prebuilt::<Self>(
+                toolset,
+                ver_req,
+                &version,
+                &url,
+                Some(&checksum_url),
+                &archive_bin,
+            ) {
+                Ok(result) => return Ok(result),
+                // A source build would hide a possibly tampered download.
+                Err(e) if e.is::<ChecksumMismatch>() => return Err(e),
+                Err(e) => ui::print_warn(&format!(
+                    "failed to install prebuilt {}: {e:#}\nFalling back to building it from source.",
+                    Self::NAME
+                )),
             }
-        } else {
-            fs::create_dir_all(&install_dir).context(format!(
-                "failed to create install directory '{}'",
-                install_dir.display()
-            ))?;
-        }
-
-        // Install it with Cargo
-        let cargo = toolset.get::<Cargo>(&deps.cargo_deps, deps.cargo_ver_req.as_ref())?;
-
-        let temp_install_dir = toolset.temp_install_dir(Self::NAME);
-        if temp_install_dir.exists() && !empty_dir(&temp_install_dir)? {
-            bail!(
-                "Temporary {} install directory '{}' unexpectedly already exists \
-                and is not an empty directory, aborting for safety.",
-                Self::NAME,
-                temp_install_dir.display()
-            );
-        }
-
-        let mut cmd = cargo.cmd();
-        cmd.arg("install")
-            .arg(Self::NAME)
-            .arg("--locked")
-            .args(["--version", &version.to_string()])
-            .arg("--root")
-            .arg(temp_install_dir.as_os_str());
-
-        ui::print_cmd(&cmd);
-
-        let status = cmd.status().context("failed to run cargo install")?;
-        ensure!(status.success(), "cargo install failed: {status}");
-
-        // Copy the binary to the install directory
-        let manifest_a = temp_install_dir.join(".crates.toml");
-        let manifest_b = temp_install_dir.join(".crates2.json");
-        let bin_name = host::executable_name(Self::BIN);
-        let bin_src_dir = temp_install_dir.join("bin");
-        let bin_src = bin_src_dir.join(&bin_name);
-        let bin_dst = install_dir.join(&bin_name);
-
-        if !bin_src.exists() {
-            bail!(
-                "{} binary at '{}' unexpectedly not found, aborting.",
-                Self::NAME,
-                bin_src.display()
-            );
-        }
-        if bin_dst.exists() {
-            bail!(
-                "{} binary at '{}' unexpectedly already exists, aborting.",
-                Self::NAME,
-                bin_dst.display()
-            );
         }
-        fs::copy(&bin_src, &bin_dst).context(format!(
-            "failed to copy {} binary from '{}' to '{}'",
-            Self::NAME,
-            bin_src.display(),
-            bin_dst.display()
-        ))?;
 
-        // Safely clean up the temporary directory
-        fs::remove_file(&bin_src).context(format!(
-            "failed to remove {} binary at '{}'",
-            Self::NAME,
-            bin_src.display()
-        ))?;
-        fs::remove_dir(&bin_src_dir).context(format!(
-            "failed to remove temporary directory '{}'",
-            bin_src_dir.display()
-        ))?;
-        fs::remove_file(&manifest_a).context(format!(
-            "failed to remove temporary manifest file at '{}'",
-            manifest_a.display()
-        ))?;
-        fs::remove_file(&manifest_b).context(format!(
-            "failed to remove temporary manifest file at '{}'",
-            manifest_b.display()
-        ))?;
-        fs::remove_dir(&temp_install_dir).context(format!(
-            "failed to remove temporary directory '{}'",
-            temp_install_dir.display()
-        ))?;
-
-        // Verify that the installed version is correct
-        let binctx = toolset.binctx(bin_dst);
-
-        let Some(version) = toolset
-            .verify::<Self>(&binctx, ver_req)
-            .context(format!("failed to verify {}", Self::NAME))?
-        else {
-            bail!(
-                "'{}' was just installed but now was no longer found",
-                binctx.path().display()
-            );
-        };
-
-        Ok((binctx, version))
+        cargo::install::<Self>(
+            toolset,
+            &deps.cargo_deps,
+            deps.cargo_ver_req.as_ref(),
+            ver_req,
+        )
     }
 }
 
-/// Returns `true` if `path` is a directory and is empty.
-fn empty_dir(path: &Path) -> Result<bool> {
-    if !path.is_dir() {
-        return Ok(false);
-    }
-    let read_dir = path
-        .read_dir()
-        .context(format!("failed to read directory '{}'", path.display()))?;
-    Ok(read_dir.count() == 0)
+/// Returns the triple of the official prebuilt ripgrep binary that runs on the `host` triple.
+///
+/// Returns `None` if there is no such prebuilt binary.
+fn prebuilt_triple(host: &str) -> Option<&'static str> {
+    let triple = match host {
+        // The Linux x86-64 binary is statically linked with musl and thus also runs on glibc.
+        "x86_64-unknown-linux-gnu" | "x86_64-unknown-linux-musl" => "x86_64-unknown-linux-musl",
+        "aarch64-unknown-linux-gnu" => "aarch64-unknown-linux-gnu",
+        "armv7-unknown-linux-gnueabihf" => "armv7-unknown-linux-gnueabihf",
+        "i686-unknown-linux-gnu" => "i686-unknown-linux-gnu",
+        "x86_64-apple-darwin" => "x86_64-apple-darwin",
+        "aarch64-apple-darwin" => "aarch64-apple-darwin",
+        "x86_64-pc-windows-msvc" => "x86_64-pc-windows-msvc",
+        "x86_64-pc-windows-gnu" => "x86_64-pc-windows-gnu",
+        "i686-pc-windows-msvc" => "i686-pc-windows-msvc",
+        _ => return None,
+    };
+    Some(triple)
 }
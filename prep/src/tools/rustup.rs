@@ -1,7 +1,7 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, bail, ensure};
 use semver::{Version, VersionReq};
 
 use crate::tools::{BinCtx, Tool};
@@ -47,3 +47,72 @@ impl Tool for Rustup {
         Ok((binctx, version))
     }
 }
+
+/// Adds the compilation `targets` to the Rust toolchain used by `cargo`, if they are missing.
+pub fn add_targets(
+    toolset: &mut Toolset,
+    ver_req: Option<&VersionReq>,
+    cargo: &BinCtx,
+    targets: &[String],
+) -> Result<()> {
+    let rustup = toolset.get::<Rustup>(&(), ver_req)?;
+    // Without an explicit toolchain the one active in the working directory is used.
+    let toolchain = cargo.environment().vars().get("RUSTUP_TOOLCHAIN");
+    let toolchain_args = toolchain
+        .map(|t| vec!["--toolchain", t.as_str()])
+        .unwrap_or_default();
+
+    let mut cmd = rustup.cmd();
+    cmd.arg("target")
+        .arg("list")
+        .arg("--installed")
+        .args(&toolchain_args);
+
+    ui::print_cmd(&cmd);
+
+    let output = cmd
+        .output()
+        .context(format!("failed to run {}", Rustup::NAME))?;
+    ensure!(
+        output.status.success(),
+        "{} failed: {}\n{}",
+        Rustup::NAME,
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let installed = String::from_utf8(output.stdout)
+        .context(format!("{} output not valid UTF-8", Rustup::NAME))?;
+    let installed = installed
+        .lines()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>();
+    let missing = targets
+        .iter()
+        .filter(|t| !installed.contains(&t.as_str()))
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut cmd = rustup.cmd();
+    cmd.arg("target")
+        .arg("add")
+        .args(&toolchain_args)
+        .args(&missing);
+
+    ui::print_cmd(&cmd);
+
+    let phase = format!(
+        "adding Rust targets {}",
+        missing
+            .iter()
+            .map(|t| t.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let status = ui::progress::status(&mut cmd, &phase)
+        .context(format!("failed to run {}", Rustup::NAME))?;
+    ensure!(status.success(), "{} failed: {status}", Rustup::NAME);
+
+    Ok(())
+}
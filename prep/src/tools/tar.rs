@@ -0,0 +1,48 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
+
+use crate::tools::{BinCtx, Tool};
+use crate::toolset::Toolset;
+use crate::ui;
+
+/// Tar for extracting archives.
+pub struct Tar;
+
+impl Tool for Tar {
+    type Deps = ();
+
+    const NAME: &str = "tar";
+    const BIN: &str = "tar";
+    const MANAGED: bool = false;
+    // GNU tar reports only `MAJOR.MINOR`, so versions can only be extracted from bsdtar.
+    const VERSION_REGEX: &str = r"^bsdtar (\d+\.\d+\.\d+)";
+
+    fn set_up(
+        toolset: &mut Toolset,
+        deps: &Self::Deps,
+        ver_req: &VersionReq,
+    ) -> Result<(BinCtx, Version)> {
+        // Check if the default tar installation already meets the requirement.
+        let binctx = Self::default_binctx(toolset, deps)?;
+
+        let Some(version) = toolset
+            .verify::<Self>(&binctx, ver_req)
+            .context(format!("failed to verify {}", Self::NAME))?
+        else {
+            ui::print_err(
+                "\
+				This Prep command requires tar.\n\
+				\n\
+				There is no automatic setup implemented for it, sorry.\n\
+				Please install it manually with your system package manager.\n\
+				",
+            );
+            bail!("{} not found", Self::NAME);
+        };
+
+        Ok((binctx, version))
+    }
+}
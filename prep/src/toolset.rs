@@ -2,19 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use std::collections::{BTreeMap, HashMap};
-use std::fs;
+use std::env;
+use std::fs::{self, File, TryLockError};
 use std::path::{Path, PathBuf};
 
+use anstream::eprintln;
 use anyhow::{Context, Result, bail};
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
-use time::{Date, UtcDateTime};
+use time::{Date, Duration, UtcDateTime};
 
+use crate::config::Network;
 use crate::environment::Environment;
+use crate::tools::cargo::empty_dir;
 use crate::tools::{BinCtx, Tool};
 use crate::ui;
+use crate::ui::style::HEADER;
+use crate::{hash, host};
 
 const MANIFEST_NAME: &str = "tools.toml";
+const LOCK_NAME: &str = "tools.lock";
 
 /// Collection of tools.
 pub struct Toolset {
@@ -25,12 +32,22 @@ pub struct Toolset {
     manifest: Manifest,
 
     environment: Environment,
+    network: Network,
+    /// Additional environment variables for specific tools, keyed by the tool name.
+    tool_env: BTreeMap<String, BTreeMap<String, String>>,
+    /// Whether to re-hash the binaries from the manifest when they are first used in this process.
+    verify_hashes: bool,
 
     /// Binary context mapping to its info.
     ///
     /// All the entries in this map have been verified to exist and be the specified version.
     /// With that verification having happened during the lifetime of this specific process.
     bins: HashMap<BinCtx, BinInfo>,
+
+    /// How the tools set up during this process were installed, keyed by the tool name.
+    ///
+    /// The entries are taken when the installations are recorded in the manifest.
+    strategies: HashMap<String, InstallStrategy>,
 }
 
 struct BinInfo {
@@ -38,9 +55,75 @@ struct BinInfo {
     version: Version,
 }
 
+/// How a tool installation was produced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallStrategy {
+    /// Downloaded from the tool's official prebuilt release archives.
+    Prebuilt,
+    /// Downloaded by cargo-binstall.
+    Binstall,
+    /// Built from source with `cargo install`.
+    Source,
+}
+
+/// Status of a tool installation in the manifest.
+pub enum InstallStatus {
+    /// The installation exists and matches the manifest.
+    Valid,
+    /// The binary no longer exists.
+    Missing,
+    /// The binary reports a different version.
+    WrongVersion(Version),
+    /// The binary doesn't match the SHA-256 hash recorded at installation.
+    HashMismatch(String),
+}
+
+/// Version requirement for getting a tool from the [`Toolset`].
+#[derive(Clone, Copy, Debug)]
+pub enum ToolVersionReq<'a> {
+    /// Whichever version the tool's dependencies provide, e.g. the one shipped with the Rust toolchain.
+    ///
+    /// Unlike [`Default`], the tool is set up if it is missing.
+    ///
+    /// [`Default`]: Self::Default
+    Any,
+    /// The default version, without a version check.
+    Default,
+    /// Exactly the given version, including any pre-release suffix like `-stable`.
+    Exact(&'a Version),
+    /// Any version that satisfies the requirement.
+    Req(&'a VersionReq),
+}
+
+impl<'a> From<&'a VersionReq> for ToolVersionReq<'a> {
+    fn from(ver_req: &'a VersionReq) -> Self {
+        Self::Req(ver_req)
+    }
+}
+
+impl<'a> From<Option<&'a VersionReq>> for ToolVersionReq<'a> {
+    fn from(ver_req: Option<&'a VersionReq>) -> Self {
+        ver_req.map_or(Self::Default, Self::Req)
+    }
+}
+
+impl<'a> From<&'a Version> for ToolVersionReq<'a> {
+    fn from(version: &'a Version) -> Self {
+        Self::Exact(version)
+    }
+}
+
 impl Toolset {
     /// Creates a new toolset.
-    pub fn new(tools_dir: PathBuf, working_dir: PathBuf, environment: Environment) -> Result<Self> {
+    pub fn new(
+        tools_dir: PathBuf,
+        working_dir: PathBuf,
+        environment: Environment,
+        network: Network,
+        tool_env: BTreeMap<String, BTreeMap<String, String>>,
+        verify_hashes: bool,
+    ) -> Result<Self> {
         let manifest_path = tools_dir.join(MANIFEST_NAME);
 
         // Attempt to load the manifest
@@ -56,22 +139,52 @@ impl Toolset {
             manifest_path,
             manifest,
             environment,
+            network,
+            tool_env,
+            verify_hashes,
             bins: HashMap::new(),
+            strategies: HashMap::new(),
         };
 
         Ok(this)
     }
 
+    /// Returns the directory where the managed tools are installed.
+    pub fn tools_dir(&self) -> &Path {
+        &self.tools_dir
+    }
+
     /// Returns a reference to the default environment.
     pub fn environment(&self) -> &Environment {
         &self.environment
     }
 
+    /// Sets the default environment.
+    ///
+    /// The binaries used so far are forgotten, as they are bound to the previous environment.
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+        self.bins.clear();
+    }
+
+    /// Returns the network configuration.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
     /// Returns the default working directory.
     pub fn working_dir(&self) -> &Path {
         &self.working_dir
     }
 
+    /// Sets the default working directory.
+    ///
+    /// The binaries used so far are forgotten, as they are bound to the previous directory.
+    pub fn set_working_dir(&mut self, working_dir: PathBuf) {
+        self.working_dir = working_dir;
+        self.bins.clear();
+    }
+
     /// Returns a new [`BinCtx`] with default working dir and environment variables.
     pub fn binctx(&self, path: PathBuf) -> BinCtx {
         BinCtx::new(path, self.working_dir.clone(), self.environment.clone())
@@ -80,16 +193,57 @@ impl Toolset {
     /// Get a specific tool that meets the given version requirement
     /// and uses the specified dependencies.
     ///
-    /// `None` as the version requirement means that the default version will be used.
+    /// `None` as the version requirement means [`ToolVersionReq::Default`].
+    ///
+    /// The environment variables configured for the tool are added to its binary context.
     pub fn get<'a, T: Tool>(
         &mut self,
         deps: &T::Deps,
-        ver_req: impl Into<Option<&'a VersionReq>>,
+        ver_req: impl Into<ToolVersionReq<'a>>,
     ) -> Result<BinCtx> {
-        // Check if we can just return the default version,
-        // i.e. just the binary name with no detailed path.
-        let Some(ver_req) = ver_req.into() else {
-            return T::default_binctx(self, deps);
+        let mut binctx = self.resolve::<T>(deps, ver_req.into())?;
+
+        // Managed tools are put on the PATH, so that the tools spawned by other tools find them.
+        if binctx.path().starts_with(&self.tools_dir)
+            && let Some(dir) = binctx.path().parent()
+            && !self
+                .environment
+                .vars()
+                .get("PATH")
+                .is_some_and(|path| env::split_paths(path).any(|d| d == dir))
+        {
+            ui::print_debug(&format!("Prepending '{}' to PATH", dir.display()));
+            self.environment = self.environment.clone().prepend_path(dir);
+        }
+        // The binary may have been resolved before the PATH was last extended.
+        if let Some(path) = self.environment.vars().get("PATH") {
+            binctx = binctx.env(&BTreeMap::from([("PATH".into(), path.clone())]));
+        }
+        if let Some(vars) = self.tool_env.get(T::NAME) {
+            binctx = binctx.env(vars);
+        }
+        Ok(binctx)
+    }
+
+    /// Resolves the tool that meets the given version requirement, setting it up if needed.
+    fn resolve<T: Tool>(&mut self, deps: &T::Deps, ver_req: ToolVersionReq) -> Result<BinCtx> {
+        let exact_ver_req;
+        let ver_req = match ver_req {
+            // Check if we can just return the default version,
+            // i.e. just the binary name with no detailed path.
+            ToolVersionReq::Default => {
+                ui::print_debug(&format!(
+                    "Using the default {} without a version check",
+                    T::NAME
+                ));
+                return T::default_binctx(self, deps);
+            }
+            ToolVersionReq::Any => return self.resolve_any::<T>(deps),
+            ToolVersionReq::Exact(version) => {
+                exact_ver_req = exact(version)?;
+                &exact_ver_req
+            }
+            ToolVersionReq::Req(ver_req) => ver_req,
         };
 
         // A specific version requirement was provided.
@@ -101,6 +255,11 @@ impl Toolset {
             .find(|(_, info)| info.name == T::NAME && ver_req.matches(&info.version))
             .map(|(binctx, _)| binctx)
         {
+            ui::print_debug(&format!(
+                "Using {} at '{}' already verified to satisfy {ver_req}",
+                T::NAME,
+                binctx.path().display()
+            ));
             return Ok(binctx.clone());
         }
 
@@ -119,16 +278,35 @@ impl Toolset {
             };
             let binctx = self.binctx(path);
             // Verify that it still exists and is the correct version.
-            let exact_ver_req = VersionReq::parse(&format!("={}", version)).context(format!(
-                "failed to convert version '{}' to exact version requirement",
-                version
-            ))?;
+            let exact_ver_req = exact(&version)?;
             if self.verify::<T>(&binctx, &exact_ver_req)?.is_some() {
-                // Check whether the last use date in the manifest needs updating.
-                if self.manifest.mark_used(T::NAME, &version, today) {
-                    self.save_manifest()
-                        .context("failed to save tool manifest")?;
+                // Reject the binary if it has been modified since it was installed.
+                // Installations recorded by older Prep versions get their hash recorded now.
+                if self.verify_hashes {
+                    let actual = hash::sha256_file(binctx.path())?;
+                    match self.manifest.sha256(T::NAME, &version).map(String::from) {
+                        Some(expected) if actual != expected => bail!(
+                            "{} {} binary at '{}' has SHA-256 hash {actual} \
+                            but {expected} was recorded at installation, \
+                            it may have been tampered with.",
+                            T::NAME,
+                            version,
+                            binctx.path().display()
+                        ),
+                        Some(_) => (),
+                        None => {
+                            self.update_manifest(|manifest| {
+                                manifest.set_sha256(T::NAME, &version, actual)
+                            })?;
+                        }
+                    }
                 }
+                ui::print_debug(&format!(
+                    "Using {} {version} from the tool manifest to satisfy {ver_req}",
+                    T::NAME
+                ));
+                // Check whether the last use date in the manifest needs updating.
+                self.update_manifest(|manifest| manifest.mark_used(T::NAME, &version, today))?;
                 return Ok(binctx);
             } else {
                 // It no longer exists, remove the manifest entry.
@@ -138,10 +316,7 @@ impl Toolset {
                     version,
                     binctx.path().display()
                 ));
-                if self.manifest.remove(T::NAME, &version) {
-                    self.save_manifest()
-                        .context("failed to save tool manifest")?;
-                }
+                self.update_manifest(|manifest| manifest.remove(T::NAME, &version))?;
             }
         }
 
@@ -153,10 +328,18 @@ impl Toolset {
             .context(format!("failed to get the default {} version", T::NAME))?
             && ver_req.matches(&version)
         {
+            ui::print_debug(&format!(
+                "Using the default {} {version} to satisfy {ver_req}",
+                T::NAME
+            ));
             return Ok(binctx);
         }
 
         // No satisfactory available anywhere, need to set it up.
+        ui::print_debug(&format!(
+            "Setting up {} as no installation satisfies {ver_req}",
+            T::NAME
+        ));
         let (binctx, version) = T::set_up(self, deps, ver_req)?;
 
         // Update the manifest if this tool is supposed to be managed by the toolset.
@@ -166,21 +349,51 @@ impl Toolset {
                 .path()
                 .strip_prefix(&self.tools_dir)
                 .unwrap_or(binctx.path());
+            let sha256 = hash::sha256_file(binctx.path())?;
+            let strategy = self.strategies.remove(T::NAME);
 
-            self.manifest.set(
-                T::NAME.to_string(),
-                version.clone(),
-                save_path.to_path_buf(),
-                today,
-            );
-            self.save_manifest()
-                .context("failed to save tool manifest")?;
+            self.update_manifest(|manifest| {
+                manifest.set(
+                    T::NAME.to_string(),
+                    version.clone(),
+                    save_path.to_path_buf(),
+                    sha256,
+                    strategy,
+                    today,
+                );
+                true
+            })?;
         }
 
         // Return the result
         Ok(binctx)
     }
 
+    /// Resolves whichever version of the tool its dependencies provide, setting it up if needed.
+    ///
+    /// The tool's version is fully determined by its dependencies,
+    /// so binaries verified earlier for other dependencies can't be reused.
+    fn resolve_any<T: Tool>(&mut self, deps: &T::Deps) -> Result<BinCtx> {
+        let binctx = T::default_binctx(self, deps)?;
+        if let Some(version) = self
+            .version::<T>(&binctx)
+            .context(format!("failed to get the default {} version", T::NAME))?
+        {
+            ui::print_debug(&format!(
+                "Using the {} {version} provided by its dependencies",
+                T::NAME
+            ));
+            return Ok(binctx);
+        }
+
+        ui::print_debug(&format!(
+            "Setting up {} as its dependencies don't provide it",
+            T::NAME
+        ));
+        let (binctx, _) = T::set_up_any(self, deps)?;
+        Ok(binctx)
+    }
+
     /// Verifies that the given `path` is a binary for the given `ver_req` of the tool.
     ///
     /// Returns the specific `Version` of the tool, or `None` if the path doesn't exist.
@@ -234,6 +447,169 @@ impl Toolset {
         Ok(Some(version))
     }
 
+    /// Returns the tool manifest.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Returns the names and versions of all the tool installations in the manifest.
+    pub fn installations(&self) -> Vec<(String, Version)> {
+        self.manifest.entries()
+    }
+
+    /// Checks whether the specified manifest installation of `T` still exists,
+    /// reports the correct version, and matches its recorded hash.
+    pub fn check<T: Tool>(&mut self, version: &Version) -> Result<InstallStatus> {
+        let Some(path) = self.installed_path(T::NAME, version) else {
+            bail!("{} {version} is not in the tool manifest", T::NAME);
+        };
+        let binctx = self.binctx(path);
+        let Some(actual) = self.version::<T>(&binctx)? else {
+            return Ok(InstallStatus::Missing);
+        };
+        if actual != *version {
+            return Ok(InstallStatus::WrongVersion(actual));
+        }
+        if let Some(expected) = self.manifest.sha256(T::NAME, version) {
+            let actual = hash::sha256_file(binctx.path())?;
+            if actual != expected {
+                return Ok(InstallStatus::HashMismatch(actual));
+            }
+        }
+        Ok(InstallStatus::Valid)
+    }
+
+    /// Returns all the installed versions of the specified tool according to the manifest.
+    pub fn installed_versions(&self, name: &str) -> Vec<Version> {
+        self.manifest.versions(name)
+    }
+
+    /// Returns the installation path of the specified tool version according to the manifest.
+    pub fn installed_path(&self, name: &str, version: &Version) -> Option<PathBuf> {
+        let path = self.manifest.path(name, version)?;
+        // Relative paths are relative to the tools directory.
+        if path.is_relative() {
+            Some(self.tools_dir.join(path))
+        } else {
+            Some(path.to_path_buf())
+        }
+    }
+
+    /// Returns the size in bytes of the specified tool version on disk.
+    ///
+    /// This is the size of the whole install directory, if the binary is inside it.
+    pub fn installed_size(&self, name: &str, version: &Version) -> Result<u64> {
+        let Some(path) = self.installed_path(name, version) else {
+            bail!("{name} {version} is not in the tool manifest");
+        };
+        let install_dir = self.install_dir(name, version);
+        if path.parent() == Some(install_dir.as_path()) {
+            dir_size(&install_dir)
+        } else {
+            let metadata =
+                fs::metadata(&path).context(format!("failed to read '{}'", path.display()))?;
+            Ok(metadata.len())
+        }
+    }
+
+    /// Returns the name, version directory name, and size in bytes
+    /// of every tool version directory in the tools directory.
+    ///
+    /// Temporary install directories are skipped, as they only exist during an installation.
+    pub fn disk_usage(&self) -> Result<Vec<(String, String, u64)>> {
+        let mut usage = Vec::new();
+        if !self.tools_dir.is_dir() {
+            return Ok(usage);
+        }
+        let read_dir = |dir: &Path| -> Result<Vec<(String, PathBuf)>> {
+            let mut dirs = Vec::new();
+            let entries = dir
+                .read_dir()
+                .context(format!("failed to read directory '{}'", dir.display()))?;
+            for entry in entries {
+                let entry =
+                    entry.context(format!("failed to read directory '{}'", dir.display()))?;
+                if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    dirs.push((name, entry.path()));
+                }
+            }
+            Ok(dirs)
+        };
+        for (name, tool_dir) in read_dir(&self.tools_dir)? {
+            if name.starts_with("temp-") {
+                continue;
+            }
+            for (version, version_dir) in read_dir(&tool_dir)? {
+                usage.push((name.clone(), version, dir_size(&version_dir)?));
+            }
+        }
+        usage.sort();
+        Ok(usage)
+    }
+
+    /// Returns the names and versions of the tool installations
+    /// which haven't been used in the last `max_age` days.
+    pub fn stale(&self, max_age: u32) -> Vec<(String, Version)> {
+        let cutoff = UtcDateTime::now().date() - Duration::days(max_age.into());
+        self.manifest.unused_since(cutoff)
+    }
+
+    /// Removes the tool installations which haven't been used in the last `max_age` days.
+    ///
+    /// Returns the names and versions of the removed tools.
+    pub fn prune(&mut self, max_age: u32) -> Result<Vec<(String, Version)>> {
+        let stale = self.stale(max_age);
+        for (name, version) in &stale {
+            self.uninstall(name, version)?;
+        }
+        Ok(stale)
+    }
+
+    /// Removes the specified tool installation from disk and from the manifest.
+    ///
+    /// Only the binary and its install directory inside the tools directory are deleted.
+    /// Installations outside of the tools directory are only removed from the manifest.
+    pub fn uninstall(&mut self, name: &str, version: &Version) -> Result<()> {
+        let Some(path) = self.installed_path(name, version) else {
+            bail!("{name} {version} is not in the tool manifest");
+        };
+
+        let install_dir = self.install_dir(name, version);
+        if path.parent() == Some(install_dir.as_path()) {
+            if path.exists() {
+                fs::remove_file(&path).context(format!(
+                    "failed to remove {name} binary at '{}'",
+                    path.display()
+                ))?;
+            }
+            if install_dir.exists() {
+                fs::remove_dir(&install_dir).context(format!(
+                    "failed to remove {name} install directory '{}'",
+                    install_dir.display()
+                ))?;
+            }
+            // Also remove the tool directory if no other versions remain.
+            if let Some(tool_dir) = install_dir.parent()
+                && empty_dir(tool_dir)?
+            {
+                fs::remove_dir(tool_dir).context(format!(
+                    "failed to remove {name} directory '{}'",
+                    tool_dir.display()
+                ))?;
+            }
+        }
+
+        self.bins.retain(|binctx, _| binctx.path() != path);
+        self.update_manifest(|manifest| manifest.remove(name, version))?;
+        Ok(())
+    }
+
+    /// Sets the `strategy` that just installed the tool `name`, to be recorded in the manifest.
+    pub fn set_install_strategy(&mut self, name: &str, strategy: InstallStrategy) {
+        self.strategies.insert(name.into(), strategy);
+    }
+
     /// Returns the directory where the tool binary should be installed.
     pub fn install_dir(&self, name: &str, version: &Version) -> PathBuf {
         self.tools_dir.join(name).join(version.to_string())
@@ -249,6 +625,43 @@ impl Toolset {
         self.tools_dir.join(format!("temp-{name}"))
     }
 
+    /// Removes the leftovers of an interrupted installation of `version` of the tool `T`,
+    /// so that the installation can be retried.
+    ///
+    /// Both the temporary install directory and the install directory of `version` are cleaned,
+    /// the latter only if `version` isn't in the manifest.
+    pub fn clean_interrupted_install<T: Tool>(&self, version: &Version) -> Result<()> {
+        let mut removed = self.clean_temp_install_dir::<T>()?;
+        let install_dir = self.install_dir(T::NAME, version);
+        if self.manifest.path(T::NAME, version).is_none() && install_dir.exists() {
+            removed.extend(remove_leftovers::<T>(&install_dir)?);
+        }
+        if !removed.is_empty() {
+            ui::print_warn(&format!(
+                "removed {} leftover files of an interrupted {} installation, retrying",
+                removed.len(),
+                T::NAME
+            ));
+        }
+        Ok(())
+    }
+
+    /// Removes the temporary install directory of the tool `T` along with any leftover files in it.
+    ///
+    /// Returns the paths of the removed files.
+    pub fn clean_temp_install_dir<T: Tool>(&self) -> Result<Vec<PathBuf>> {
+        let temp_install_dir = self.temp_install_dir(T::NAME);
+        if !temp_install_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let removed = remove_leftovers::<T>(&temp_install_dir)?;
+        fs::remove_dir(&temp_install_dir).context(format!(
+            "failed to remove temporary directory '{}'",
+            temp_install_dir.display()
+        ))?;
+        Ok(removed)
+    }
+
     /// Ensures that the tools directory exists.
     pub fn ensure_tools_dir(&self) -> Result<()> {
         if !self.tools_dir.exists() {
@@ -265,6 +678,83 @@ impl Toolset {
         Ok(())
     }
 
+    /// Imports the installations of the `imported` manifest from `dir`,
+    /// which is laid out like the tools directory and on the same file system.
+    ///
+    /// The binaries are verified against their recorded hashes and moved into the tools directory.
+    /// Installations which are already in the manifest are skipped.
+    ///
+    /// Returns the names and versions of the imported installations.
+    pub fn import(&mut self, dir: &Path, imported: &Manifest) -> Result<Vec<(String, Version)>> {
+        let today = UtcDateTime::now().date();
+        let mut installed = Vec::new();
+        for (name, version) in imported.entries() {
+            let installation = &imported.tools[&name][&version];
+            // Only accept the layout of the tools directory, so nothing ends up outside of it.
+            let install_dir = Path::new(&name).join(version.to_string());
+            let valid_name = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name || installation.path.parent() != Some(install_dir.as_path()) {
+                bail!(
+                    "{name} {version} binary path '{}' is not in its install directory",
+                    installation.path.display()
+                );
+            }
+            if self.manifest.path(&name, &version).is_some() {
+                ui::print_debug(&format!(
+                    "Skipping {name} {version} as it is already installed"
+                ));
+                continue;
+            }
+
+            let bin = dir.join(&installation.path);
+            let Some(expected) = &installation.sha256 else {
+                bail!("{name} {version} has no recorded SHA-256 hash");
+            };
+            let actual = hash::sha256_file(&bin)?;
+            if actual != *expected {
+                bail!(
+                    "{name} {version} binary at '{}' has SHA-256 hash {actual} \
+                    but {expected} was recorded, the archive may be corrupted.",
+                    bin.display()
+                );
+            }
+
+            let dst = self.install_dir(&name, &version);
+            if dst.exists() {
+                bail!(
+                    "{name} install directory '{}' unexpectedly already exists, aborting.",
+                    dst.display()
+                );
+            }
+            let tool_dir = self.tools_dir.join(&name);
+            fs::create_dir_all(&tool_dir).context(format!(
+                "failed to create {name} directory '{}'",
+                tool_dir.display()
+            ))?;
+            fs::rename(dir.join(&install_dir), &dst).context(format!(
+                "failed to move {name} {version} to '{}'",
+                dst.display()
+            ))?;
+
+            let installation = Installation {
+                used: today,
+                ..installation.clone()
+            };
+            self.update_manifest(|manifest| {
+                manifest
+                    .tools
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(version.clone(), installation);
+                true
+            })?;
+            installed.push((name, version));
+        }
+        Ok(installed)
+    }
+
     /// Loads the tool manifest from file.
     pub fn load_manifest(path: &Path) -> Result<Manifest> {
         let manifest_toml = fs::read(path).context(format!(
@@ -276,15 +766,71 @@ impl Toolset {
         Ok(manifest)
     }
 
-    /// Saves the tool manifest to file.
-    // TODO: Should check before writing whether it has been modified since we loaded it,
-    //       or even use locking. Otherwise parallel Prep usage is broken.
-    pub fn save_manifest(&self) -> Result<()> {
+    /// Applies the `update` to the tool manifest and saves it to file if it returns `true`.
+    ///
+    /// The manifest file is locked for the duration of the update and reloaded before applying it,
+    /// so that changes made by other Prep processes in the meantime aren't lost.
+    ///
+    /// Returns `true` if the manifest was changed.
+    fn update_manifest(&mut self, update: impl FnOnce(&mut Manifest) -> bool) -> Result<bool> {
+        let _lock = self.lock_manifest()?;
+        if self.manifest_path.exists() {
+            self.manifest = Self::load_manifest(&self.manifest_path)?;
+        }
+        if !update(&mut self.manifest) {
+            return Ok(false);
+        }
+        self.save_manifest()
+            .context("failed to save tool manifest")?;
+        Ok(true)
+    }
+
+    /// Acquires an exclusive lock for modifying the tool manifest, blocking until it is available.
+    ///
+    /// The lock is released when the returned file is dropped.
+    fn lock_manifest(&self) -> Result<File> {
         self.ensure_tools_dir()?;
+        let lock_path = self.tools_dir.join(LOCK_NAME);
+        let lock = File::create(&lock_path).context(format!(
+            "failed to open tool manifest lock file '{}'",
+            lock_path.display()
+        ))?;
+        match lock.try_lock() {
+            Ok(()) => (),
+            Err(TryLockError::WouldBlock) => {
+                let h = HEADER;
+                eprintln!("    {h}Blocking{h:#} waiting for file lock on tool manifest");
+                lock.lock().context(format!(
+                    "failed to lock tool manifest lock file '{}'",
+                    lock_path.display()
+                ))?;
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).context(format!(
+                    "failed to lock tool manifest lock file '{}'",
+                    lock_path.display()
+                ));
+            }
+        }
+        Ok(lock)
+    }
+
+    /// Saves the tool manifest to file.
+    ///
+    /// The file is replaced atomically, so concurrent readers never see a partially written file.
+    /// Callers must hold the manifest lock, see [`lock_manifest`].
+    ///
+    /// [`lock_manifest`]: Self::lock_manifest
+    fn save_manifest(&self) -> Result<()> {
         let manifest_toml =
             toml::to_string(&self.manifest).context("failed to generate tool manifest TOML")?;
-        fs::write(&self.manifest_path, &manifest_toml).context(format!(
+        let temp_path = self.manifest_path.with_extension("toml.tmp");
+        fs::write(&temp_path, &manifest_toml).context(format!(
             "failed to write tool manifest file '{}'",
+            temp_path.display()
+        ))?;
+        fs::rename(&temp_path, &self.manifest_path).context(format!(
+            "failed to replace tool manifest file '{}'",
             self.manifest_path.display()
         ))?;
         Ok(())
@@ -299,10 +845,41 @@ pub struct Manifest {
 }
 
 /// Information about a tool installation.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Installation {
     path: PathBuf,
     used: Date,
+    /// Date of the installation, unknown for installations recorded by older Prep versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    installed: Option<Date>,
+    /// SHA-256 hash of the binary, taken at installation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    /// How the tool was installed, unknown for installations recorded by older Prep versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strategy: Option<InstallStrategy>,
+}
+
+impl Installation {
+    /// Returns the binary path, which is relative to the tools directory unless absolute.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the date when the installation was last used.
+    pub fn used(&self) -> Date {
+        self.used
+    }
+
+    /// Returns the date when the tool was installed, if known.
+    pub fn installed(&self) -> Option<Date> {
+        self.installed
+    }
+
+    /// Returns how the tool was installed, if known.
+    pub fn strategy(&self) -> Option<InstallStrategy> {
+        self.strategy
+    }
 }
 
 impl Manifest {
@@ -330,24 +907,121 @@ impl Manifest {
         None
     }
 
-    /// Sets the given tool's `version` to `path`.
-    pub fn set(&mut self, name: String, version: Version, path: PathBuf, today: Date) {
+    /// Returns the recorded SHA-256 hash of the specified tool version's binary.
+    pub fn sha256(&self, name: &str, version: &Version) -> Option<&str> {
+        self.tools.get(name)?.get(version)?.sha256.as_deref()
+    }
+
+    /// Returns all the installations of the specified tool.
+    pub fn tool(&self, name: &str) -> Option<&BTreeMap<Version, Installation>> {
+        self.tools.get(name)
+    }
+
+    /// Returns all the versions of the specified tool.
+    pub fn versions(&self, name: &str) -> Vec<Version> {
+        self.tools
+            .get(name)
+            .map(|tool| tool.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the installation path of the specified tool version.
+    pub fn path(&self, name: &str, version: &Version) -> Option<&Path> {
+        Some(&self.tools.get(name)?.get(version)?.path)
+    }
+
+    /// Returns the names and versions of all tool installations.
+    pub fn entries(&self) -> Vec<(String, Version)> {
+        let mut entries = self
+            .tools
+            .iter()
+            .flat_map(|(name, tool)| tool.keys().map(|version| (name.clone(), version.clone())))
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    /// Returns a manifest with only the specified `entries`.
+    pub fn subset(&self, entries: &[(String, Version)]) -> Manifest {
+        let mut subset = Manifest::new();
+        for (name, version) in entries {
+            if let Some(installation) = self.tools.get(name).and_then(|tool| tool.get(version)) {
+                subset
+                    .tools
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(version.clone(), installation.clone());
+            }
+        }
+        subset
+    }
+
+    /// Returns the names and versions of all tool installations last used before `date`.
+    pub fn unused_since(&self, date: Date) -> Vec<(String, Version)> {
+        let mut unused = self
+            .tools
+            .iter()
+            .flat_map(|(name, tool)| {
+                tool.iter()
+                    .filter(|(_, installation)| installation.used < date)
+                    .map(|(version, _)| (name.clone(), version.clone()))
+            })
+            .collect::<Vec<_>>();
+        unused.sort();
+        unused
+    }
+
+    /// Sets the given tool's `version` to `path` with the binary's `sha256` hash
+    /// and the `strategy` that installed it.
+    pub fn set(
+        &mut self,
+        name: String,
+        version: Version,
+        path: PathBuf,
+        sha256: String,
+        strategy: Option<InstallStrategy>,
+        today: Date,
+    ) {
         let tool = self.tools.entry(name).or_default();
         // Remove any other versions that still think this path serves them.
         tool.retain(|_, i| i.path != path);
         // Add the new correct entry.
-        tool.insert(version, Installation { path, used: today });
+        let installation = Installation {
+            path,
+            used: today,
+            installed: Some(today),
+            sha256: Some(sha256),
+            strategy,
+        };
+        tool.insert(version, installation);
     }
 
     /// Removes the given tool's `version` from the manifest.
     ///
     /// Returns `true` if anything was changed.
     pub fn remove(&mut self, name: &str, version: &Version) -> bool {
-        if let Some(tool) = self.tools.get_mut(name) {
-            tool.remove(version).is_some()
-        } else {
-            false
+        let Some(tool) = self.tools.get_mut(name) else {
+            return false;
+        };
+        let removed = tool.remove(version).is_some();
+        if tool.is_empty() {
+            self.tools.remove(name);
         }
+        removed
+    }
+
+    /// Sets the binary's `sha256` hash of the specified tool version, if it has none yet.
+    ///
+    /// Returns `true` if anything was changed.
+    pub fn set_sha256(&mut self, name: &str, version: &Version, sha256: String) -> bool {
+        if let Some(tool) = self.tools.get_mut(name)
+            && let Some(installation) = tool.get_mut(version)
+            && installation.sha256.is_none()
+        {
+            installation.sha256 = Some(sha256);
+            return true;
+        }
+        false
     }
 
     /// Sets the last used date of the specified tool version.
@@ -364,3 +1038,89 @@ impl Manifest {
         false
     }
 }
+
+/// Removes the files that an installation of the tool `T` leaves behind in `dir`,
+/// along with the directories containing them, but not `dir` itself.
+///
+/// Those are the `cargo install` metadata files, downloaded archives, and the tool's binary.
+/// Errors without removing anything if `dir` contains any other files.
+///
+/// Returns the paths of the removed files.
+fn remove_leftovers<T: Tool>(dir: &Path) -> Result<Vec<PathBuf>> {
+    /// Collects the leftover files and directories in `dir`, with the directories last.
+    fn inventory(
+        dir: &Path,
+        bin: &str,
+        files: &mut Vec<PathBuf>,
+        dirs: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let read_dir = dir
+            .read_dir()
+            .context(format!("failed to read directory '{}'", dir.display()))?;
+        for entry in read_dir {
+            let entry = entry.context(format!("failed to read directory '{}'", dir.display()))?;
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                inventory(&path, bin, files, dirs)?;
+                dirs.push(path);
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let known = name == ".crates.toml"
+                || name == ".crates2.json"
+                || name == bin
+                || [".tar.gz", ".tgz", ".tar.xz", ".zip"]
+                    .iter()
+                    .any(|ext| name.ends_with(ext));
+            if !known {
+                bail!(
+                    "unexpected file '{}' found, aborting for safety. \
+                    Remove it manually if it is no longer needed.",
+                    path.display()
+                );
+            }
+            files.push(path);
+        }
+        Ok(())
+    }
+
+    let bin = host::executable_name(T::BIN);
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    inventory(dir, &bin, &mut files, &mut dirs)?;
+
+    for file in &files {
+        fs::remove_file(file).context(format!("failed to remove file at '{}'", file.display()))?;
+    }
+    for dir in &dirs {
+        fs::remove_dir(dir).context(format!("failed to remove directory '{}'", dir.display()))?;
+    }
+    Ok(files)
+}
+
+/// Returns the version requirement which only `version` satisfies.
+fn exact(version: &Version) -> Result<VersionReq> {
+    VersionReq::parse(&format!("={version}")).context(format!(
+        "failed to convert version '{version}' to exact version requirement"
+    ))
+}
+
+/// Returns the total size in bytes of all the files in `dir`, recursively.
+fn dir_size(dir: &Path) -> Result<u64> {
+    let read_dir = dir
+        .read_dir()
+        .context(format!("failed to read directory '{}'", dir.display()))?;
+    let mut size = 0;
+    for entry in read_dir {
+        let entry = entry.context(format!("failed to read directory '{}'", dir.display()))?;
+        let metadata = entry
+            .metadata()
+            .context(format!("failed to read '{}'", entry.path().display()))?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
@@ -0,0 +1,120 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Capturing the output of child processes.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use anstream::eprint;
+
+use crate::ui::{self, log};
+
+/// The output captured so far, or `None` when not capturing.
+static CAPTURED: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// Starts capturing the output of commands run via [`status`].
+pub fn start() {
+    *CAPTURED.lock().unwrap() = Some(Vec::new());
+}
+
+/// Stops capturing and returns the output captured since [`start`].
+pub fn finish() -> String {
+    let captured = CAPTURED.lock().unwrap().take().unwrap_or_default();
+    String::from_utf8_lossy(&captured).into_owned()
+}
+
+/// Runs the command to completion just like [`Command::status`].
+///
+/// When capturing, the command's stdout and stderr are also recorded.
+/// Both are still forwarded to our stderr, so that stdout remains free for machine readable output.
+///
+/// The exit status and duration are recorded in the log.
+pub fn status(cmd: &mut Command) -> io::Result<ExitStatus> {
+    let start = Instant::now();
+    let status = if CAPTURED.lock().unwrap().is_none() {
+        cmd.status()?
+    } else {
+        status_tee(cmd)?
+    };
+    log::exit(ui::cmd_line(cmd), start.elapsed(), status.to_string());
+    Ok(status)
+}
+
+/// Runs the command to completion, copying its stdout and stderr to our stderr and the capture.
+fn status_tee(cmd: &mut Command) -> io::Result<ExitStatus> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take().expect("child stdout was not piped");
+    let stderr = child.stderr.take().expect("child stderr was not piped");
+
+    let stdout_thread = thread::spawn(move || tee(stdout));
+    let stderr_result = tee(stderr);
+    let stdout_result = stdout_thread
+        .join()
+        .expect("stdout capture thread panicked");
+    let status = child.wait()?;
+    stdout_result?;
+    stderr_result?;
+
+    Ok(status)
+}
+
+/// Runs the command to completion, passing each line of its stdout to `f`.
+///
+/// The stdout lines are not printed, `f` is expected to do that via [`eprint`] if desired.
+/// The command's stderr is handled just like with [`status`].
+pub fn status_lines(cmd: &mut Command, mut f: impl FnMut(&str)) -> io::Result<ExitStatus> {
+    let start = Instant::now();
+    let capturing = CAPTURED.lock().unwrap().is_some();
+    cmd.stdout(Stdio::piped());
+    if capturing {
+        cmd.stderr(Stdio::piped());
+    }
+    let mut child = cmd.spawn()?;
+    let stdout = child.stdout.take().expect("child stdout was not piped");
+
+    let stderr_thread = child
+        .stderr
+        .take()
+        .map(|stderr| thread::spawn(move || tee(stderr)));
+    let stdout_result = BufReader::new(stdout)
+        .lines()
+        .try_for_each(|line| line.map(|line| f(&line)));
+    let stderr_result = stderr_thread
+        .map(|t| t.join().expect("stderr capture thread panicked"))
+        .unwrap_or(Ok(()));
+    let status = child.wait()?;
+    stdout_result?;
+    stderr_result?;
+
+    log::exit(ui::cmd_line(cmd), start.elapsed(), status.to_string());
+    Ok(status)
+}
+
+/// Prints `text` to stderr, also recording it when capturing.
+pub fn eprint(text: &str) {
+    eprint!("{text}");
+    if let Some(captured) = CAPTURED.lock().unwrap().as_mut() {
+        captured.extend_from_slice(text.as_bytes());
+    }
+}
+
+/// Copies everything from `reader` to our stderr and to the capture buffer.
+fn tee(mut reader: impl Read) -> io::Result<()> {
+    let mut buf = [0; 8192];
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(len) => len,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        io::stderr().write_all(&buf[..len])?;
+        if let Some(captured) = CAPTURED.lock().unwrap().as_mut() {
+            captured.extend_from_slice(&buf[..len]);
+        }
+    }
+}
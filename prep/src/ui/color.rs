@@ -0,0 +1,59 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Color support detection.
+//!
+//! All our output goes through [`anstream`], which strips the ANSI codes based on the choice here.
+
+use std::env;
+
+use clap::{ColorChoice, ValueEnum};
+
+/// Returns the value of the `--color` argument in `args`, if it is present and valid.
+///
+/// This is needed before the arguments are parsed, so that clap's own output respects it too.
+pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<ColorChoice> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let value = match arg.strip_prefix("--color") {
+            Some("") => args.next(),
+            Some(value) => value.strip_prefix('=').map(Into::into),
+            None => continue,
+        };
+        return value.and_then(|v| ColorChoice::from_str(&v, true).ok());
+    }
+    None
+}
+
+/// Resolves the `choice` and sets it for all output for the rest of the process.
+///
+/// An explicit choice wins, otherwise `CLICOLOR_FORCE` forces colors and `NO_COLOR` disables them.
+/// With neither, colors are used when the output is a terminal.
+pub fn init(choice: Option<ColorChoice>) -> ColorChoice {
+    let is_set = |key| env::var_os(key).is_some_and(|v| !v.is_empty() && v != "0");
+    let choice = match choice {
+        Some(choice) => choice,
+        None if is_set("CLICOLOR_FORCE") => ColorChoice::Always,
+        None if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => ColorChoice::Never,
+        None => ColorChoice::Auto,
+    };
+    let global = match choice {
+        ColorChoice::Auto => anstream::ColorChoice::Auto,
+        ColorChoice::Always => anstream::ColorChoice::Always,
+        ColorChoice::Never => anstream::ColorChoice::Never,
+    };
+    global.write_global();
+    choice
+}
+
+/// Returns the color choice set by [`init`].
+pub fn choice() -> ColorChoice {
+    match anstream::ColorChoice::global() {
+        anstream::ColorChoice::Always | anstream::ColorChoice::AlwaysAnsi => ColorChoice::Always,
+        anstream::ColorChoice::Never => ColorChoice::Never,
+        anstream::ColorChoice::Auto => ColorChoice::Auto,
+    }
+}
@@ -5,31 +5,254 @@ use clap::Command;
 use clap::builder::StyledStr;
 
 use crate::ui::style::{HEADER, LITERAL, PLACEHOLDER};
+use crate::ui::term;
+
+/// The ANSI escape sequence which resets all styles.
+const RESET: &str = "\x1b[0m";
+
+/// Narrowest room for the description, below which wrapped lines continue at their indentation.
+const MIN_WRAP_WIDTH: usize = 20;
 
 /// Sets our custom help messages.
 pub fn set(cmd: Command) -> Command {
-    let cmd = cmd.override_help(root_msg());
+    let cmd = cmd.override_help(fit(root_msg()));
 
     cmd.mut_subcommands(|scmd| {
         let name = scmd.get_name();
-        if name == "ci" {
-            scmd.override_help(ci_msg())
+        if name == "bench" {
+            scmd.override_help(fit(bench_msg()))
+        } else if name == "bloat" {
+            scmd.override_help(fit(bloat_msg()))
+        } else if name == "changelog" {
+            scmd.override_help(fit(changelog_msg()))
+        } else if name == "check" {
+            scmd.override_help(fit(check_msg()))
+        } else if name == "ci" {
+            scmd.override_help(fit(ci_msg()))
+        } else if name == "clean" {
+            scmd.override_help(fit(clean_msg()))
         } else if name == "clippy" {
-            scmd.override_help(clippy_msg())
+            scmd.override_help(fit(clippy_msg()))
+        } else if name == "completions" {
+            scmd.override_help(fit(completions_msg()))
+        } else if name == "config" {
+            scmd.override_help(fit(config_msg()))
         } else if name == "copyright" {
-            scmd.override_help(copyright_msg())
+            scmd.override_help(fit(copyright_msg()))
+        } else if name == "cross" {
+            scmd.override_help(fit(cross_msg()))
+        } else if name == "doctor" {
+            scmd.override_help(fit(doctor_msg()))
+        } else if name == "duplicates" {
+            scmd.override_help(fit(duplicates_msg()))
         } else if name == "format" {
-            scmd.override_help(format_msg())
+            scmd.override_help(fit(format_msg()))
+        } else if name == "graph" {
+            scmd.override_help(fit(graph_msg()))
+        } else if name == "hakari" {
+            scmd.override_help(fit(hakari_msg()))
+        } else if name == "hooks" {
+            scmd.override_help(fit(hooks_msg()))
         } else if name == "init" {
-            scmd.override_help(init_msg())
+            scmd.override_help(fit(init_msg()))
+        } else if name == "licenses" {
+            scmd.override_help(fit(licenses_msg()))
+        } else if name == "links" {
+            scmd.override_help(fit(links_msg()))
+        } else if name == "logs" {
+            scmd.override_help(fit(logs_msg()))
+        } else if name == "manifest-lint" {
+            scmd.override_help(fit(manifest_lint_msg()))
+        } else if name == "msrv" {
+            scmd.override_help(fit(msrv_msg()))
+        } else if name == "publish" {
+            scmd.override_help(fit(publish_msg()))
+        } else if name == "readme" {
+            scmd.override_help(fit(readme_msg()))
+        } else if name == "release" {
+            scmd.override_help(fit(release_msg()))
+        } else if name == "run" {
+            scmd.override_help(fit(run_msg()))
+        } else if name == "sbom" {
+            scmd.override_help(fit(sbom_msg()))
+        } else if name == "secrets" {
+            scmd.override_help(fit(secrets_msg()))
+        } else if name == "self" {
+            scmd.override_help(fit(self_msg()))
+        } else if name == "status" {
+            scmd.override_help(fit(status_msg()))
+        } else if name == "test" {
+            scmd.override_help(fit(test_msg()))
+        } else if name == "timings" {
+            scmd.override_help(fit(timings_msg()))
         } else if name == "tools" {
-            scmd.override_help(tools_msg())
+            scmd.override_help(fit(tools_msg()))
+        } else if name == "unsafe-report" {
+            scmd.override_help(fit(unsafe_report_msg()))
+        } else if name == "vet" {
+            scmd.override_help(fit(vet_msg()))
+        } else if name == "wasm" {
+            scmd.override_help(fit(wasm_msg()))
+        } else if name == "watch" {
+            scmd.override_help(fit(watch_msg()))
         } else {
             panic!("Sub-command '{name}' help message is not implemented");
         }
     })
 }
 
+/// Wraps the lines of the `help` message which don't fit the terminal.
+///
+/// Wrapped lines continue at the column where the line's description starts,
+/// so the hand-aligned option and command columns stay aligned.
+/// Output which doesn't go to a terminal is left as is.
+pub fn fit(help: StyledStr) -> StyledStr {
+    let Some(width) = term::width() else {
+        return help;
+    };
+    let text = help.ansi().to_string();
+    let lines = text
+        .split('\n')
+        .map(|line| wrap_line(line, width))
+        .collect::<Vec<_>>();
+    StyledStr::from(lines.join("\n"))
+}
+
+/// A part of a styled line.
+enum Token<'a> {
+    /// An ANSI escape sequence, which takes no space.
+    Escape(&'a str),
+    /// A visible character.
+    Char(char),
+}
+
+/// Wraps the styled `line` to `width` columns, continuing the wrapped lines at its description.
+fn wrap_line(line: &str, width: usize) -> String {
+    let tokens = tokenize(line);
+    let chars = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Char(c) => Some(*c),
+            Token::Escape(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if chars.len() <= width {
+        return line.into();
+    }
+
+    // When the line starts with a padded styled column, e.g. the option names,
+    // the description starts right after it. Other lines continue at their own indentation.
+    let indent = chars.iter().take_while(|c| **c == ' ').count();
+    let mut hang = indent;
+    let mut col = 0;
+    let mut styled = false;
+    for token in &tokens {
+        match token {
+            Token::Escape(RESET) if styled => {
+                if chars.get(col).is_some_and(|c| *c != ' ') {
+                    hang = col;
+                }
+                break;
+            }
+            Token::Escape(_) if col <= indent => styled = true,
+            Token::Escape(_) => (),
+            Token::Char(_) if !styled && col >= indent => break,
+            Token::Char(_) => col += 1,
+        }
+    }
+    if width < hang + MIN_WRAP_WIDTH {
+        hang = indent;
+        if width < hang + MIN_WRAP_WIDTH {
+            return line.into();
+        }
+    }
+
+    let mut out = String::new();
+    // The style which is active at the current position, so it can be continued after a break.
+    let mut active = None;
+
+    let mut tokens = tokens.into_iter().peekable();
+    let mut col = 0;
+    while col < hang {
+        match tokens.next() {
+            Some(Token::Escape(e)) => {
+                track(e, &mut active);
+                out.push_str(e);
+            }
+            Some(Token::Char(c)) => {
+                out.push(c);
+                col += 1;
+            }
+            None => break,
+        }
+    }
+
+    let mut words = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            Token::Char(' ') => words.push(Vec::new()),
+            token => words.last_mut().expect("words is never empty").push(token),
+        }
+    }
+    for (i, word) in words.into_iter().enumerate() {
+        let len = word.iter().filter(|t| matches!(t, Token::Char(_))).count();
+        if i > 0 {
+            if col + 1 + len > width && col > hang {
+                if active.is_some() {
+                    out.push_str(RESET);
+                }
+                out.push('\n');
+                out.push_str(&" ".repeat(hang));
+                if let Some(active) = &active {
+                    out.push_str(active);
+                }
+                col = hang;
+            } else {
+                out.push(' ');
+                col += 1;
+            }
+        }
+        for token in word {
+            match token {
+                Token::Escape(e) => {
+                    track(e, &mut active);
+                    out.push_str(e);
+                }
+                Token::Char(c) => out.push(c),
+            }
+        }
+        col += len;
+    }
+    out
+}
+
+/// Updates the `active` style with the `escape` sequence.
+fn track(escape: &str, active: &mut Option<String>) {
+    if escape == RESET {
+        *active = None;
+    } else {
+        active.get_or_insert_default().push_str(escape);
+    }
+}
+
+/// Splits the styled `line` into ANSI escape sequences and visible characters.
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b'
+            && let Some(end) = rest.find('m')
+        {
+            tokens.push(Token::Escape(&rest[..=end]));
+            rest = &rest[end + 1..];
+        } else {
+            tokens.push(Token::Char(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    tokens
+}
+
 /// Returns the main help message.
 pub fn root_msg() -> StyledStr {
     let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
@@ -40,18 +263,167 @@ Prepare Rust projects for greatness.
 {h}Usage:{h:#} {l}prep{l:#} {p}[command] [options]{p:#}
 
 {h}Commands:{h:#}
+  {l}     bench           {l:#}Run benchmarks with Cargo.
+  {l}     bloat           {l:#}Report binary sizes with cargo-bloat.
+  {l}     changelog       {l:#}Generate the changelog with git-cliff.
+  {l}     check           {l:#}Check with Cargo.
   {l}     ci              {l:#}Verify for CI.
+  {l}     clean           {l:#}Remove caches, stale tools, and build artifacts.
   {l}clp  clippy          {l:#}Analyze with Clippy.
+  {l}     completions     {l:#}Generate shell completions.
+  {l}     config          {l:#}Manage Prep configuration.
   {l}     copyright       {l:#}Verify copyright headers.
+  {l}     cross           {l:#}Check cross-compilation targets.
+  {l}     doctor          {l:#}Diagnose environment problems.
+  {l}     duplicates      {l:#}Verify there are no duplicate dependencies.
   {l}fmt  format          {l:#}Format with rustfmt.
+  {l}     graph           {l:#}Export the dependency graph.
+  {l}     hakari          {l:#}Manage the workspace-hack crate.
+  {l}     hooks           {l:#}Manage Git hooks.
   {l}     init            {l:#}Initialize Prep configuration.
+  {l}     licenses        {l:#}Report and verify dependency licenses.
+  {l}     links           {l:#}Verify links with Lychee.
+  {l}     logs            {l:#}Inspect the logs of previous runs.
+  {l}     manifest-lint   {l:#}Verify that manifests are sorted.
+  {l}     msrv            {l:#}Find the minimum supported Rust version.
+  {l}     publish         {l:#}Verify release readiness.
+  {l}     readme          {l:#}Verify READMEs match the crate docs.
+  {l}     release         {l:#}Bump versions, tag, and publish.
+  {l}     run             {l:#}Run a task configured in {l}prep.toml{l:#}.
+  {l}     sbom            {l:#}Generate a software bill of materials.
+  {l}     secrets         {l:#}Verify no credentials are committed.
+  {l}     self            {l:#}Manage the Prep installation.
+  {l}     status          {l:#}Show an overview of the project.
+  {l}     test            {l:#}Test with Cargo.
+  {l}     timings         {l:#}Profile build times with Cargo.
+  {l}     unsafe-report   {l:#}Report unsafe code with cargo-geiger.
+  {l}     vet             {l:#}Verify dependency audits with cargo-vet.
+  {l}     wasm            {l:#}Build for WebAssembly.
+  {l}     watch           {l:#}Re-run checks on file changes.
   {l}     help            {l:#}Print help for the provided command.
 
+Any other {p}command{p:#} runs the {l}prep-<command>{l:#} executable found on {l}PATH{l:#}, with the project
+root, config path, and tool paths in {l}PREP_*{l:#} environment variables.
+
 {h}Options:{h:#}
+  {l}     --profile <name>{l:#}
+  ···                     ·····Use the named profile from {l}prep.toml{l:#}, e.g. {l}[profile.ci]{l:#}.
+  {l}-v   --verbose       {l:#}Also print debugging details, e.g. how tools are resolved.
+  {l}-q   --quiet         {l:#}Don't print the commands being run.
+  {l}-y   --yes           {l:#}Confirm all prompts without asking, and use the defaults for the rest.
+  {l}     --<tool> <ver>  {l:#}Override a tool version from {l}prep.toml{l:#} for this run, e.g. {l}--rust 1.90{l:#}.
+  ···                     ·····Possible tools: {p}rustup{p:#}, {p}rust{p:#}, {p}taplo{p:#}, {p}git-cliff{p:#}, {p}cargo-vet{p:#},
+  ···                     ·····{p}cargo-sort{p:#}, {p}cargo-nextest{p:#}, {p}lychee{p:#}, {p}cargo-geiger{p:#},
+  ···                     ·····{p}cargo-hakari{p:#}, {p}cargo-bloat{p:#}, {p}cargo-msrv{p:#}, {p}cargo-deny{p:#}.
+  {l}     --color <val>   {l:#}Whether to use colors. Possible values:
+  ···                     ·····{p}auto{p:#}   -> Only in terminals, respecting {l}NO_COLOR{l:#}. (default)
+  ···                     ·····{p}always{p:#} -> Always, also enabled by {l}CLICOLOR_FORCE{l:#}.
+  ···                     ·····{p}never{p:#}  -> Never.
   {l}-h   --help          {l:#}Print help for the provided command.
   {l}-V   --version       {l:#}Print version information.
 "
-    );
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `bench` help message.
+fn bench_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Run the benchmarks of the Rust workspace with Cargo.
+
+{h}Usage:{h:#} {l}prep bench{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain version.
+  {l}     --smoke         {l:#}Only build the benchmarks and run each of them once.
+  {l}     --save-baseline <name>{l:#}
+  ···                     ·····Store the Criterion results as the named baseline in {l}.prep/bench/{l:#}.
+  {l}-b   --baseline <name>{l:#}
+  ···                     ·····Compare the Criterion results with the named stored baseline.
+  ···                     ·····Fails on slowdowns above {l}max_regression{l:#} in {l}[bench]{l:#} of {l}prep.toml{l:#}.
+  {l}-p   --package <pkg> {l:#}Only target the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Exclude the specified package. Can be repeated.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `bloat` help message.
+fn bloat_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Report what takes the most space in the release binaries of the workspace with cargo-bloat.
+This fails when a binary exceeds its budget in KiB from {l}budgets{l:#} in {l}[bloat]{l:#} of {l}prep.toml{l:#},
+or grows over the baseline by more than {l}max_growth{l:#} percent.
+
+{h}Usage:{h:#} {l}prep bloat{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain and cargo-bloat versions.
+  {l}-b   --bin <name>    {l:#}Only analyze the named binary. Can be used multiple times.
+  {l}-n   --count <num>   {l:#}Number of the largest items to list. (default: 20)
+  {l}-c   --crates        {l:#}List the largest crates instead of functions.
+  {l}     --update-baseline{l:#}
+  ···                     ·····Record the current binary sizes as the accepted baseline.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `changelog` help message.
+fn changelog_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Generate {l}CHANGELOG.md{l:#} from the conventional commits of the Git history with git-cliff.
+The commits since the last tag are listed as unreleased, configure it with {l}cliff.toml{l:#}.
+
+{h}Usage:{h:#} {l}prep changelog{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use the locked git-cliff version.
+  {l}-c   --check         {l:#}Verify that the changelog is already up to date.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `check` help message.
+fn check_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Check the Rust workspace for errors with Cargo.
+
+{h}Usage:{h:#} {l}prep check{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain version and treat warnings as errors.
+  {l}-n   --nightly       {l:#}Use the nightly Rust toolchain, {l}tools.rust-nightly{l:#} in {l}prep.toml{l:#}.
+  {l}-p   --package <pkg> {l:#}Only target the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Exclude the specified package. Can be repeated.
+  {l}-c   --crates <val>  {l:#}Target specified crates. Possible values:
+  ···                     ·····{p}main{p:#} -> Binaries and the main library. (default)
+  ···                     ·····{p}aux{p:#}  -> Examples, tests, and benches.
+  ···                     ·····{p}all{p:#}  -> All of the above.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
 
     StyledStr::from(help)
 }
@@ -62,13 +434,56 @@ fn ci_msg() -> StyledStr {
     let help = format!(
         "\
 Verify the Rust workspace for CI.
+With {l}verify_clean_tree = true{l:#} in {l}[ci]{l:#} of {l}prep.toml{l:#}, it fails when the steps modify any files.
+Each {l}[[check]]{l:#} in {l}prep.toml{l:#} with a {l}name{l:#} and a shell {l}command{l:#} is a custom step,
+which runs after the default steps or wherever a {l}[[ci.step]]{l:#} names it.
+With {l}files{l:#} globs it's skipped when no files match, and a {l}message{l:#} explains its failure.
+
+{h}Usage:{h:#} {l}prep ci{l:#} {p}[command] [options]{p:#}
 
-{h}Usage:{h:#} {l}prep ci{l:#} {p}[options]{p:#}
+{h}Commands:{h:#}
+  {l}     cache-key       {l:#}Print a cache key for CI caches of the tools and the Cargo build.
+  ···                     ·····It changes with the tools config, {l}rust-toolchain.toml{l:#},
+  ···                     ·····{l}Cargo.lock{l:#}, and the host.
+  {l}     help            {l:#}Print help for the provided command.
 
 {h}Options:{h:#}
   {l}-e   --extended      {l:#}Run the extended verification suite.
   ···                     ·····Good idea for actual CI, rarely useful for local prep.
+  {l}     --only <step>   {l:#}Only run the named step, e.g. {p}clippy-main{p:#}. Can be repeated.
   {l}-n   --no-fail-fast  {l:#}Keep going when encountering an error.
+  {l}-f   --format <val>  {l:#}Output format. Possible values:
+  ···                     ·····{p}text{p:#} -> Human readable output. (default)
+  ···                     ·····{p}json{p:#} -> Also print a JSON run report to stdout.
+  {l}-o   --output <path> {l:#}Write the JSON run report to a file instead of stdout.
+  {l}-j   --junit <path>  {l:#}Write a JUnit XML report to a file.
+  {l}-r   --resume        {l:#}Skip the steps that already passed for the current Git tree.
+  {l}     --dry-run       {l:#}Only print the steps, the tool versions, and the commands
+  ···                     ·····that would run, without running anything.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `clean` help message.
+fn clean_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Remove Prep caches, stale tool installations, and Cargo build artifacts.
+Without any category options the caches and stale tool installations are removed.
+
+{h}Usage:{h:#} {l}prep clean{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}     --cache         {l:#}Remove the CI state and the logs in {l}.prep{l:#}.
+  {l}     --tools         {l:#}Remove tool installations unused for 90 days,
+  ···                     ·····or the {l}prune_after{l:#} days in {l}[tools]{l:#} of {l}prep.toml{l:#}.
+  {l}     --cargo         {l:#}Remove the Cargo build artifacts with {l}cargo clean{l:#}.
+  {l}-n   --dry-run       {l:#}Only list what would be removed.
   {l}-h   --help          {l:#}Print this help message.
 "
     )
@@ -89,10 +504,47 @@ Analyze the Rust workspace with Clippy.
 
 {h}Options:{h:#}
   {l}-s   --strict        {l:#}Use locked Rust toolchain version and treat warnings as errors.
+  {l}-p   --package <pkg> {l:#}Only target the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Exclude the specified package. Can be repeated.
   {l}-c   --crates <val>  {l:#}Target specified crates. Possible values:
   ···                     ·····{p}main{p:#} -> Binaries and the main library. (default)
   ···                     ·····{p}aux{p:#}  -> Examples, tests, and benches.
   ···                     ·····{p}all{p:#}  -> All of the above.
+  {l}-m   --features-matrix{l:#}
+  ···                     ·····Analyze separately with no default, default, and all features,
+  ···                     ·····and with each feature set configured in {l}prep.toml{l:#}.
+  {l}     --fix           {l:#}Automatically apply the suggested fixes.
+  {l}     --allow-dirty   {l:#}Allow fixing with uncommitted changes that are not staged.
+  {l}     --update-baseline{l:#}
+  ···                     ·····Record the current warnings in {l}.prep/clippy-baseline.json{l:#}.
+  ···                     ·····In strict mode only warnings missing from the baseline fail.
+  {l}     --sarif <path>  {l:#}Also write the diagnostics to a SARIF report, e.g. for code scanning.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `completions` help message.
+fn completions_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Print the completion script for the shell to stdout.
+Task and tool names are also completed in bash and fish.
+
+{h}Usage:{h:#} {l}prep completions{l:#} {p}<shell>{p:#}
+
+{h}Shells:{h:#}
+  {l}     bash            {l:#}Load with {l}source <(prep completions bash){l:#}
+  {l}     elvish          {l:#}Load with {l}eval (prep completions elvish | slurp){l:#}
+  {l}     fish            {l:#}Load with {l}prep completions fish | source{l:#}
+  {l}     powershell      {l:#}Load with {l}prep completions powershell | Out-String | Invoke-Expression{l:#}
+  {l}     zsh             {l:#}Save as {l}_prep{l:#} in a directory of {l}$fpath{l:#}
+
+{h}Options:{h:#}
   {l}-h   --help          {l:#}Print this help message.
 "
     )
@@ -107,11 +559,75 @@ fn copyright_msg() -> StyledStr {
     let help = format!(
         "\
 Verify that all Rust source files have the correct copyright header.
+Also verify that a license file exists for every license of the project,
+e.g. {l}LICENSE-APACHE{l:#} and {l}LICENSE-MIT{l:#}, and that all published packages declare the same license.
 
 {h}Usage:{h:#} {l}prep copyright{l:#}
 
 {h}Options:{h:#}
-  {l}-s   --strict        {l:#}Use locked ripgrep version.
+  {l}-r   --ripgrep [ver] {l:#}Use ripgrep instead of the built-in scanner.
+  ···                     ·····With a version, e.g. {l}--ripgrep 14.0.3{l:#}, use that locked version.
+  {l}-s   --strict        {l:#}Use ripgrep with the locked version.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `cross` help message.
+fn cross_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Check the Rust workspace for each cross-compilation target with Cargo and Clippy.
+The targets are configured in {l}[cross]{l:#} of {l}prep.toml{l:#} and added with Rustup if missing.
+
+{h}Usage:{h:#} {l}prep cross{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-t   --target <triple>{l:#}
+  ···                     ·····Check the specified target instead. Can be repeated.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `doctor` help message.
+fn doctor_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Diagnose the environment for problems that would make Prep commands fail.
+Checks rustup, the default Rust toolchain, the tools directory, Git,
+toolchain overriding environment variables, and access to crates.io and GitHub.
+
+{h}Usage:{h:#} {l}prep doctor{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `duplicates` help message.
+fn duplicates_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Report the dependencies which are in the dependency graph in multiple versions.
+Fails if a package isn't in the {l}allow{l:#} list in {l}[duplicates]{l:#} of {l}prep.toml{l:#}.
+
+{h}Usage:{h:#} {l}prep duplicates{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
   {l}-h   --help          {l:#}Print this help message.
 "
     )
@@ -125,14 +641,18 @@ fn format_msg() -> StyledStr {
     let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
     let help = format!(
         "\
-Format the Rust workspace with rustfmt.
+Format the Rust workspace with rustfmt, and optionally TOML files with Taplo.
 
 {h}Usage:{h:#} {l}prep fmt{l:#}    {p}[options]{p:#}
 ···      ····· {l}prep format{l:#} {p}[options]{p:#}
 
 {h}Options:{h:#}
-  {l}-s   --strict        {l:#}Use locked Rust toolchain version.
+  {l}-s   --strict        {l:#}Use locked Rust toolchain and Taplo versions.
   {l}-c   --check         {l:#}Verify that the workspace is already formatted.
+  {l}-t   --toml          {l:#}Also format TOML files with Taplo.
+  {l}-p   --package <pkg> {l:#}Only format the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Don't format the specified package. Can be repeated.
+  {l}     --since <rev>   {l:#}Only format files changed since the Git revision.
   {l}-h   --help          {l:#}Print this help message.
 "
     )
@@ -147,11 +667,16 @@ fn init_msg() -> StyledStr {
     let help = format!(
         "\
 Initialize Prep configuration for this Rust workspace.
+When run in a terminal, the configuration values are chosen interactively.
 
 {h}Usage:{h:#} {l}prep init{l:#} {p}[options]{p:#}
 
 {h}Options:{h:#}
-  {l}-f   --force         {l:#}Overwrite existing configuration.
+  {l}-f   --force         {l:#}Overwrite existing configuration, after confirming in a terminal.
+  {l}-g   --github        {l:#}Generate a GitHub Actions workflow that runs {l}prep ci{l:#} instead.
+  {l}-t   --write-toolchain-file{l:#}
+  ···                     ·····Generate a {l}rust-toolchain.toml{l:#} with the configured Rust version instead.
+  {l}-s   --scaffold      {l:#}Generate {l}rustfmt.toml{l:#}, {l}clippy.toml{l:#}, and {l}deny.toml{l:#} instead.
   {l}-h   --help          {l:#}Print this help message.
 "
     )
@@ -160,23 +685,582 @@ Initialize Prep configuration for this Rust workspace.
     StyledStr::from(help)
 }
 
-/// Returns the tools help message.
-pub fn tools_msg() -> StyledStr {
+/// Returns the `manifest-lint` help message.
+fn manifest_lint_msg() -> StyledStr {
     let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
     let help = format!(
         "\
-Manage all the tools that Prep uses.
+Verify that the dependencies in every workspace {l}Cargo.toml{l:#} are sorted and normalized
+with the locked cargo-sort, which respects {l}tomlfmt.toml{l:#}.
 
-{h}Usage:{h:#} {l}prep tools{l:#} {p}[command] [options]{p:#}
+{h}Usage:{h:#} {l}prep manifest-lint{l:#} {p}[options]{p:#}
 
-{h}Commands:{h:#}
-  {l}     list            {l:#}List information about all the tools.
-  {l}     help            {l:#}Print help for the provided command.
+{h}Options:{h:#}
+  {l}-f   --fix           {l:#}Rewrite the manifests instead of verifying them.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `publish` help message.
+fn publish_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Verify that the publishable workspace packages are ready to be published to crates.io.
+Each package is packaged and built with {l}cargo publish --dry-run{l:#} in dependency order.
+
+{h}Usage:{h:#} {l}prep publish --dry-run{l:#} {p}[options]{p:#}
 
 {h}Options:{h:#}
-  {l}-h   --help          {l:#}Print help for the provided command.
+  {l}-n   --dry-run       {l:#}Only verify the packaging, without publishing.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `readme` help message.
+fn readme_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Verify that the README of every package matches its crate-level docs.
+The docs are rendered between the {l}<!-- cargo-rdme start -->{l:#} and {l}<!-- cargo-rdme end -->{l:#}
+markers of the README. Configure the packages with {l}packages{l:#} in {l}[readme]{l:#} of {l}prep.toml{l:#},
+otherwise all packages with a README that contains the markers are verified.
+
+{h}Usage:{h:#} {l}prep readme{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-c   --check         {l:#}Verify the READMEs, which is the default.
+  {l}-f   --fix           {l:#}Regenerate the READMEs instead of verifying them.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `release` help message.
+fn release_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Release the publishable workspace packages.
+The versions are bumped in all the manifests, the {l}[Unreleased]{l:#} changelog sections
+are turned into the new versions, and the changes are committed with a signed tag.
+Without a level it is chosen interactively.
+
+{h}Usage:{h:#} {l}prep release{l:#} {p}[level] [options]{p:#}
+
+{h}Levels:{h:#}
+  {l}     major           {l:#}Incompatible changes, e.g. {l}1.2.3{l:#} -> {l}2.0.0{l:#}
+  {l}     minor           {l:#}Compatible additions, e.g. {l}1.2.3{l:#} -> {l}1.3.0{l:#}
+  {l}     patch           {l:#}Compatible fixes, e.g. {l}1.2.3{l:#} -> {l}1.2.4{l:#}
+
+{h}Options:{h:#}
+  {l}-p   --publish       {l:#}Also publish the packages to crates.io in dependency order,
+  ···                     ·····after confirming in a terminal.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `licenses` help message.
+fn licenses_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Report the licenses of all the dependencies of the workspace with the locked cargo-deny.
+Fails if a license isn't allowed by the {l}[licenses]{l:#} policy in {l}deny.toml{l:#}, if there is one.
+Use {l}prep init --scaffold{l:#} to create it.
+
+{h}Usage:{h:#} {l}prep licenses{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-f   --format <val>  {l:#}Output format. Possible values:
+  ···                     ·····{p}text{p:#} -> Human readable summary. (default)
+  ···                     ·····{p}json{p:#} -> Print the full inventory as JSON to stdout.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `run` help message.
+fn run_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Run a task configured in {l}[tasks]{l:#} of {l}prep.toml{l:#}, or list the tasks if none is given.
+Each step is a built-in CI step or check, Cargo arguments, or a shell command.
+
+{h}Usage:{h:#} {l}prep run{l:#} {p}[task] [options]{p:#}
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `sbom` help message.
+fn sbom_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Generate a software bill of materials of the workspace.
+It covers all the packages of {l}Cargo.lock{l:#} and the tools installed in the managed toolset.
+
+{h}Usage:{h:#} {l}prep sbom{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-f   --format <val>  {l:#}Document format. Possible values:
+  ···                     ·····{p}cyclonedx{p:#} -> CycloneDX 1.5 JSON. (default)
+  ···                     ·····{p}spdx{p:#}      -> SPDX 2.3 JSON.
+  {l}-o   --output <path> {l:#}Write the document to the file instead of stdout.
+  {l}-h   --help          {l:#}Print this help message.
 "
-    );
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `secrets` help message.
+fn secrets_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Scan the repository for committed credentials, e.g. AWS keys, tokens, and private keys.
+Ignored and binary files are skipped. False positives can be allowed with {l}allow{l:#} regexes and {l}exclude{l:#}
+file globs in {l}[secrets]{l:#} of {l}prep.toml{l:#}, or a {l}prep:allow-secret{l:#} comment on the line.
+With {l}ci = true{l:#} in {l}[secrets]{l:#}, {l}prep ci --extended{l:#} runs it too.
+
+{h}Usage:{h:#} {l}prep secrets{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `status` help message.
+fn status_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Show an overview of the project without running any checks.
+This covers where the project and its config were found, pending config migrations,
+the Git status, the required and installed tool versions, and the last {l}prep ci{l:#} run.
+
+{h}Usage:{h:#} {l}prep status{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `self` help message.
+pub fn self_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Manage the Prep installation.
+
+{h}Usage:{h:#} {l}prep self{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     update          {l:#}Update Prep to the latest release on GitHub.
+  ···                     ·····Use {l}--check{l:#} to only report whether there is a newer release.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `test` help message.
+fn test_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Run the tests of the Rust workspace with Cargo.
+With {l}retries{l:#} in {l}[test]{l:#} of {l}prep.toml{l:#} cargo-nextest retries failing tests
+and reports the tests which only passed on retry.
+
+{h}Usage:{h:#} {l}prep test{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain version.
+  {l}-d   --doc           {l:#}Only run the documentation tests.
+  {l}     --partition <n/m>{l:#}
+  ···                     ·····Only run the {p}n{p:#}th of {p}m{p:#} partitions of the tests with cargo-nextest.
+  ···                     ·····{p}m{p:#} defaults to {l}partitions{l:#} in {l}[test]{l:#} of {l}prep.toml{l:#}.
+  {l}-p   --package <pkg> {l:#}Only target the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Exclude the specified package. Can be repeated.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `graph` help message.
+fn graph_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Export the dependency graph of the workspace packages for architecture reviews.
+Packages which are in the graph in multiple versions are highlighted in red.
+
+{h}Usage:{h:#} {l}prep graph{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-f   --format <val>  {l:#}Graph format. Possible values:
+  ···                     ·····{p}dot{p:#}     -> Graphviz DOT. (default)
+  ···                     ·····{p}mermaid{p:#} -> Mermaid flowchart.
+  {l}-w   --workspace-only{l:#}
+  ···                     ·····Only include the dependencies between workspace packages.
+  {l}-d   --dev           {l:#}Also include dev-dependencies, drawn dashed.
+  {l}-b   --build         {l:#}Also include build-dependencies, drawn dotted.
+  {l}-o   --output <path> {l:#}Write the graph to the file instead of stdout.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the config help message.
+pub fn config_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Manage Prep configuration.
+
+{h}Usage:{h:#} {l}prep config{l:#} {p}<command> [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     get             {l:#}Print the effective value of a config key, e.g. {l}tools.rust{l:#}.
+  {l}     set             {l:#}Set a config key in {l}prep.toml{l:#}, preserving comments.
+  ···                     ·····Strings don't need to be quoted, e.g. {l}prep config set tools.rust =1.94{l:#}.
+  {l}     migrate         {l:#}Upgrade {l}prep.toml{l:#} and the user config to the current layout.
+  ···                     ·····Older layouts are also upgraded in memory on load, with a warning.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the hakari help message.
+pub fn hakari_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Manage the workspace-hack crate with the locked cargo-hakari.
+When {l}.config/hakari.toml{l:#} exists, {l}prep ci --extended{l:#} also verifies the crate.
+
+{h}Usage:{h:#} {l}prep hakari{l:#} {p}<command> [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     generate        {l:#}Regenerate the crate and add it as a dependency of all packages.
+  {l}     verify          {l:#}Verify that the crate is up to date, without writing anything.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the hooks help message.
+pub fn hooks_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Manage Git hooks which run Prep commands.
+
+{h}Usage:{h:#} {l}prep hooks{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     install         {l:#}Install the pre-commit and pre-push hooks.
+  ···                     ·····The commands are configured in {l}[git_hooks]{l:#} of {l}prep.toml{l:#}.
+  ···                     ·····Hooks without any commands configured are removed.
+  ···                     ·····Use {l}--force{l:#} to overwrite hooks not installed by Prep,
+  ···                     ·····which are backed up with a {l}.bak{l:#} extension first.
+  {l}     uninstall       {l:#}Remove the hooks installed by Prep.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `links` help message.
+fn links_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Verify the links in the Markdown files and Rust doc comments of the project with Lychee.
+URLs matching the {l}exclude{l:#} regular expressions in {l}[links]{l:#} of {l}prep.toml{l:#} are not checked.
+
+To run it in extended CI, add a {l}[[ci.step]]{l:#} with {l}builtin = \"links\"{l:#} or {l}\"links-offline\"{l:#}
+and {l}extended = true{l:#}.
+
+{h}Usage:{h:#} {l}prep links{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use the locked Lychee version.
+  {l}     --offline       {l:#}Only check links to project files, without network access.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the logs help message.
+pub fn logs_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Inspect the logs of previous runs, which are kept in {l}.prep/logs{l:#}.
+Each run records the commands, their durations and exit codes, and how tools were resolved.
+Read-only commands, e.g. {l}status{l:#}, are not logged, and neither are projects without {l}.prep{l:#}.
+
+{h}Usage:{h:#} {l}prep logs{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     tail            {l:#}Print the log of the last run.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `msrv` help message.
+pub fn msrv_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Find the minimum supported Rust version of the workspace with the locked cargo-msrv.
+It bisects the Rust releases by checking the workspace with them, installing them with rustup.
+
+{h}Usage:{h:#} {l}prep msrv{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     find            {l:#}Find the oldest Rust release that the workspace builds with.
+  ···                     ·····Use {l}--write{l:#} to record it as {l}rust-version{l:#} in {l}Cargo.toml{l:#}
+  ···                     ·····and as {l}msrv{l:#} in {l}[project]{l:#} of {l}prep.toml{l:#}.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `timings` help message.
+fn timings_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Build the workspace with Cargo and report the crates which take the longest to compile.
+The times of the last runs are kept in {l}.prep/timings{l:#} to report regressions over the previous run.
+Only the crates compiled in this run have times, so it's most useful for clean builds, e.g. in CI.
+
+{h}Usage:{h:#} {l}prep timings{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain version.
+  {l}-r   --release       {l:#}Build with the release profile.
+  {l}-n   --count <num>   {l:#}Number of the slowest crates to list. (default: 10)
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the tools help message.
+pub fn tools_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Manage all the tools that Prep uses.
+
+{h}Usage:{h:#} {l}prep tools{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     clean-temp      {l:#}Remove the leftovers of interrupted tool installations.
+  {l}     du              {l:#}Report the disk usage of all tool installations.
+  ···                     ·····Use {l}--budget <MiB>{l:#} to warn when the usage exceeds it.
+  {l}     export          {l:#}Bundle installations into a tarball, e.g. {l}export tools.tar.gz ripgrep{l:#}
+  ···                     ·····Omit the tools to export all, or select versions with {l}ripgrep@15.1.0{l:#}.
+  {l}     import          {l:#}Install the tools from a tarball created by {l}export{l:#}, e.g. for offline CI.
+  {l}     list            {l:#}List information about all the tools and their installations.
+  ···                     ·····Use {l}--all-versions{l:#} to also list installations of other versions.
+  ···                     ·····Use {l}--format json{l:#} for machine readable output.
+  {l}     outdated        {l:#}Report tools with newer releases on crates.io.
+  ···                     ·····Use {l}--toml{l:#} to print the config changes to update them.
+  {l}     prune           {l:#}Remove tool installations unused for 90 days.
+  ···                     ·····Use {l}--days <val>{l:#} to choose a different amount of days.
+  {l}     uninstall       {l:#}Remove a tool's installations, e.g. {l}uninstall ripgrep 15.1.0{l:#}
+  ···                     ·····Omit the version to remove all versions.
+  ···                     ·····Use {l}--dry-run{l:#} to only list what would be removed.
+  {l}     verify          {l:#}Verify the integrity of all tool installations.
+  ···                     ·····Use {l}--repair{l:#} to remove broken installations.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `unsafe-report` help message.
+fn unsafe_report_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Report the unsafe code used by the workspace packages and their dependencies with cargo-geiger.
+With {l}deny_new = true{l:#} in {l}[unsafe]{l:#} of {l}prep.toml{l:#} this fails when a workspace package
+uses more unsafe code than recorded in the baseline, and {l}prep ci --extended{l:#} runs it too.
+
+{h}Usage:{h:#} {l}prep unsafe-report{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-s   --strict        {l:#}Use locked Rust toolchain and cargo-geiger versions.
+  {l}     --update-baseline{l:#}
+  ···                     ·····Record the current unsafe code usage as the accepted baseline.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `vet` help message.
+fn vet_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Verify that all the third-party dependencies are audited with the locked cargo-vet.
+The imported audits aren't fetched, only the committed {l}supply-chain{l:#} directory is used.
+
+{h}Usage:{h:#} {l}prep vet{l:#} {p}[command] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     certify         {l:#}Record an audit with {l}cargo vet certify{l:#}.
+  ···                     ·····All the following arguments are passed to it as is.
+  {l}     help            {l:#}Print help for the provided command.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print help for the provided command.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `wasm` help message.
+fn wasm_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Build the WebAssembly compatible packages configured in {l}[wasm]{l:#} of {l}prep.toml{l:#}.
+The target is added with Rustup if missing, and with {l}bindgen{l:#} configured
+the JavaScript bindings are generated with the wasm-bindgen CLI.
+
+{h}Usage:{h:#} {l}prep wasm{l:#} {p}[options]{p:#}
+
+{h}Options:{h:#}
+  {l}-p   --package <pkg> {l:#}Only build the specified package. Can be repeated.
+  {l}     --exclude <pkg> {l:#}Don't build the specified package. Can be repeated.
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
+
+    StyledStr::from(help)
+}
+
+/// Returns the `watch` help message.
+fn watch_msg() -> StyledStr {
+    let (h, l, p) = (HEADER, LITERAL, PLACEHOLDER);
+    let help = format!(
+        "\
+Watch the workspace files and re-run the commands whenever they change.
+Cargo commands only target the packages with changed files.
+
+{h}Usage:{h:#} {l}prep watch{l:#} {p}[commands] [options]{p:#}
+
+{h}Commands:{h:#}
+  {l}     check           {l:#}Check with Cargo. (default)
+  {l}     clippy          {l:#}Analyze with Clippy. (default)
+  {l}     format          {l:#}Verify formatting.
+  {l}     copyright       {l:#}Verify copyright headers.
+
+{h}Options:{h:#}
+  {l}-h   --help          {l:#}Print this help message.
+"
+    )
+    .replace("·", "");
 
     StyledStr::from(help)
 }
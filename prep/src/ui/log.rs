@@ -0,0 +1,205 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Persistent structured logs of Prep runs.
+//!
+//! Every run writes its events as JSON lines to a new file in `.prep/logs`,
+//! and only the most recent logs are kept. Read-only commands aren't logged.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use time::UtcDateTime;
+
+/// The directory of the log files, inside the prep directory.
+pub const DIR_NAME: &str = "logs";
+
+/// The number of the most recent log files to keep.
+const MAX_LOGS: usize = 20;
+
+/// The log of the current run, or `None` when not logging.
+static LOG: Mutex<Option<Log>> = Mutex::new(None);
+
+/// An open log file.
+struct Log {
+    file: File,
+    start: Instant,
+}
+
+/// A single logged event, with `elapsed_secs` being the time since the start of the run.
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    /// Seconds since the start of the run.
+    pub elapsed_secs: f64,
+    /// What happened.
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// A logged event.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// The run started.
+    Start {
+        /// UTC date and time, e.g. `2026-01-31 12:00:00`.
+        time: String,
+        /// Prep version.
+        version: String,
+        /// Command line arguments, without the binary.
+        args: Vec<String>,
+    },
+    /// A command was started.
+    Command {
+        /// The command line, including the environment variables set for it.
+        command: String,
+    },
+    /// A command exited.
+    Exit {
+        /// The command line, including the environment variables set for it.
+        command: String,
+        /// How long the command ran.
+        duration_secs: f64,
+        /// Exit status, e.g. `exit status: 1`.
+        status: String,
+    },
+    /// A debugging message, e.g. how a tool was resolved.
+    Debug {
+        /// The message.
+        message: String,
+    },
+    /// The run finished.
+    Finish {
+        /// The error, if the run failed.
+        error: Option<String>,
+    },
+}
+
+/// Starts logging to a new file in the logs directory of the `prep_dir`,
+/// removing the oldest logs beyond the limit.
+///
+/// Nothing is logged if the `prep_dir` doesn't exist, so that it's not created in projects
+/// which don't use Prep.
+pub fn start(prep_dir: &Path, args: Vec<String>) -> Result<()> {
+    if !prep_dir.is_dir() {
+        return Ok(());
+    }
+    let logs_dir = &prep_dir.join(DIR_NAME);
+    fs::create_dir_all(logs_dir).context(format!(
+        "failed to create logs directory '{}'",
+        logs_dir.display()
+    ))?;
+    // The logs are machine specific, so keep them out of version control.
+    let gitignore = logs_dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, "*\n")
+            .context(format!("failed to write '{}'", gitignore.display()))?;
+    }
+
+    let mut logs = list(logs_dir)?;
+    while logs.len() >= MAX_LOGS {
+        let oldest = logs.remove(0);
+        fs::remove_file(&oldest)
+            .context(format!("failed to remove log file '{}'", oldest.display()))?;
+    }
+
+    let now = UtcDateTime::now();
+    let name = format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}{:03}-{}.jsonl",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.millisecond(),
+        std::process::id()
+    );
+    let path = logs_dir.join(name);
+    let file =
+        File::create(&path).context(format!("failed to create log file '{}'", path.display()))?;
+    *LOG.lock().unwrap() = Some(Log {
+        file,
+        start: Instant::now(),
+    });
+
+    event(Event::Start {
+        time: format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        ),
+        version: env!("CARGO_PKG_VERSION").into(),
+        args,
+    });
+
+    Ok(())
+}
+
+/// Records the `event`, if logging.
+///
+/// Logging is best effort, so failing to write the log never fails the run.
+pub fn event(event: Event) {
+    let mut log = LOG.lock().unwrap();
+    let Some(log) = log.as_mut() else {
+        return;
+    };
+    let record = Record {
+        elapsed_secs: log.start.elapsed().as_secs_f64(),
+        event,
+    };
+    if let Ok(json) = serde_json::to_string(&record) {
+        let _ = writeln!(log.file, "{json}");
+    }
+}
+
+/// Records the exit of the `command` after running for `duration`.
+pub fn exit(command: String, duration: Duration, status: String) {
+    event(Event::Exit {
+        command,
+        duration_secs: duration.as_secs_f64(),
+        status,
+    });
+}
+
+/// Records the end of the run with its `result` and stops logging.
+pub fn finish(result: &Result<()>) {
+    event(Event::Finish {
+        error: result.as_ref().err().map(|e| format!("{e:#}")),
+    });
+    *LOG.lock().unwrap() = None;
+}
+
+/// Returns the paths of the log files in `logs_dir`, oldest first.
+pub fn list(logs_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !logs_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let read_dir = logs_dir.read_dir().context(format!(
+        "failed to read logs directory '{}'",
+        logs_dir.display()
+    ))?;
+    let mut logs = Vec::new();
+    for entry in read_dir {
+        let entry = entry.context(format!(
+            "failed to read logs directory '{}'",
+            logs_dir.display()
+        ))?;
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            logs.push(path);
+        }
+    }
+    // The file names start with the date and time, so they sort chronologically.
+    logs.sort();
+    Ok(logs)
+}
@@ -1,14 +1,59 @@
 // Copyright 2026 the Prep Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub mod capture;
+pub mod color;
 pub mod help;
+pub mod log;
+pub mod progress;
+pub mod prompt;
 pub mod style;
+pub mod term;
 
 use std::ffi::OsStr;
 use std::process::Command;
+use std::sync::atomic::{AtomicU8, Ordering};
 
+use anstream::{eprint, eprintln};
 use clap::builder::StyledStr;
 
+/// The current [`Verbosity`].
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Amount of output printed about what Prep is doing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only warnings, errors, and the output of the tools themselves.
+    Quiet,
+    /// Also the commands being run.
+    Normal,
+    /// Also debugging details, e.g. how tool versions are resolved.
+    Verbose,
+}
+
+/// Sets the verbosity for the rest of the process.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Returns the current verbosity.
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// Returns the Cargo flags matching the current verbosity, i.e. `--quiet` or `--verbose`.
+pub fn cargo_verbosity_args() -> &'static [&'static str] {
+    match verbosity() {
+        Verbosity::Quiet => &["--quiet"],
+        Verbosity::Normal => &[],
+        Verbosity::Verbose => &["--verbose"],
+    }
+}
+
 /// Prints lines aligned lines with only the first line getting the header.
 pub fn print_lines(header: &str, lines: &str) {
     for (idx, line) in lines.split("\n").enumerate() {
@@ -20,23 +65,45 @@ pub fn print_lines(header: &str, lines: &str) {
     }
 }
 
-/// Prints the binary name and its arguments to stderr.
-pub fn print_cmd(cmd: &Command) {
+/// Returns the command line of `cmd`, including the environment variables set for it.
+///
+/// `PATH` is omitted, as it is long and only ever extended with the managed tool directories.
+pub fn cmd_line(cmd: &Command) -> String {
     let envs = cmd
         .get_envs()
-        .map(|(k, v)| format!("{}={}", k.display(), v.unwrap_or_default().display()))
+        .filter(|(k, _)| *k != "PATH")
+        .map(|(k, v)| format!("{}={}", k.display(), quote(v.unwrap_or_default())))
         .collect::<Vec<_>>()
         .join(" ");
-    let bin = cmd.get_program();
-    let args = cmd.get_args().collect::<Vec<_>>().join(OsStr::new(" "));
+    let bin = quote(cmd.get_program());
+    let args = cmd.get_args().map(quote).collect::<Vec<_>>().join(" ");
+    format!("{envs} {bin} {args}")
+}
+
+/// Returns `s` in double quotes if it contains spaces, e.g. `C:\Program Files\...`.
+fn quote(s: &OsStr) -> String {
+    let s = s.display().to_string();
+    if s.contains(' ') {
+        format!("\"{s}\"")
+    } else {
+        s
+    }
+}
+
+/// Prints the binary name and its arguments to stderr, unless quiet.
+///
+/// The command is always recorded in the log.
+pub fn print_cmd(cmd: &Command) {
+    let line = cmd_line(cmd);
+    log::event(log::Event::Command {
+        command: line.clone(),
+    });
+    if verbosity() == Verbosity::Quiet {
+        return;
+    }
 
     let h = style::HEADER;
-    eprintln!(
-        "     {h}Running{h:#} `{} {} {}`",
-        envs,
-        bin.display(),
-        args.display()
-    );
+    eprintln!("     {h}Running{h:#} `{line}`");
 }
 
 /// Prints the error with a colored prefix.
@@ -53,8 +120,22 @@ pub fn print_warn(warn: &str) {
     print_lines(&header, warn);
 }
 
-/// Prints the main help message.
+/// Prints the debugging message with a colored prefix, only when verbose.
+///
+/// The message is always recorded in the log.
+pub fn print_debug(msg: &str) {
+    log::event(log::Event::Debug {
+        message: msg.into(),
+    });
+    if verbosity() < Verbosity::Verbose {
+        return;
+    }
+    let i = style::INFO;
+    let header = format!("       {i}Debug{i:#}");
+    print_lines(&header, msg);
+}
+
+/// Prints the main help message, wrapped to the terminal width.
 pub fn print_help(msg: StyledStr) {
-    // TODO: Don't print ANSI codes when not supported by the environment.
-    eprint!("{}", msg.ansi());
+    eprint!("{}", help::fit(msg).ansi());
 }
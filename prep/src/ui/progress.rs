@@ -0,0 +1,90 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Progress indication for long running commands, e.g. tool installations.
+
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anstream::eprintln;
+
+use crate::ui::style::{ERROR, HEADER};
+use crate::ui::{self, Verbosity, capture, log};
+
+/// The spinner animation frames.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How often the spinner is redrawn.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Runs the command to completion just like [`capture::status`], while showing the `phase`.
+///
+/// In a terminal, a spinner with the `phase` and the elapsed time is shown below the output.
+/// Otherwise, e.g. in CI logs, the elapsed time is only printed once the command finishes.
+pub fn status(cmd: &mut Command, phase: &str) -> io::Result<ExitStatus> {
+    let quiet = ui::verbosity() == Verbosity::Quiet;
+    let start = Instant::now();
+    let status = if io::stderr().is_terminal() && !quiet {
+        let status = spin(cmd, phase, start)?;
+        log::exit(ui::cmd_line(cmd), start.elapsed(), status.to_string());
+        status
+    } else {
+        capture::status(cmd)?
+    };
+    if !quiet {
+        let (h, e) = (HEADER, ERROR);
+        let secs = start.elapsed().as_secs_f64();
+        if status.success() {
+            eprintln!("    {h}Finished{h:#} {phase} in {secs:.1}s");
+        } else {
+            eprintln!("      {e}Failed{e:#} {phase} after {secs:.1}s");
+        }
+    }
+    Ok(status)
+}
+
+/// Runs the command, printing its output above a spinner line until it exits.
+fn spin(cmd: &mut Command, phase: &str, start: Instant) -> io::Result<ExitStatus> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take().expect("child stdout was not piped");
+    let stderr = child.stderr.take().expect("child stderr was not piped");
+
+    let (tx, rx) = mpsc::channel();
+    let readers: [Box<dyn Read + Send>; 2] = [Box::new(stdout), Box::new(stderr)];
+    for reader in readers {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).split(b'\n').map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    // The channel disconnects once both readers are done.
+    drop(tx);
+
+    for frame in SPINNER.iter().cycle() {
+        match rx.recv_timeout(TICK) {
+            Ok(line) => {
+                clear_line();
+                capture::eprint(&format!("{}\n", String::from_utf8_lossy(&line)));
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        // The control codes must not be stripped, so this bypasses the color aware stream.
+        std::eprint!("\r{frame} {phase} ({}s)", start.elapsed().as_secs());
+    }
+    clear_line();
+
+    child.wait()
+}
+
+/// Clears the current line of the terminal and moves the cursor to its start.
+fn clear_line() {
+    std::eprint!("\r\x1b[2K");
+}
@@ -0,0 +1,173 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Line based interactive prompts.
+//!
+//! Without a terminal, or with `--yes`, nothing is asked and the defaults are used instead.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anstream::{eprint, eprintln};
+use anyhow::{Context, Result, bail};
+
+use crate::ui::style::{LITERAL, NOTE};
+
+/// Whether confirmations are answered with yes and other prompts with their defaults.
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether to answer all prompts without asking, for the rest of the process.
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Returns `true` if both stdin and stderr are terminals, so the user can answer prompts.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Returns `true` if the prompts will actually be asked, i.e. in a terminal without `--yes`.
+pub fn will_ask() -> bool {
+    !ASSUME_YES.load(Ordering::Relaxed) && is_interactive()
+}
+
+/// Asks the `question` and returns the answer, or the `default` when the answer is empty.
+pub fn text(question: &str, default: &str) -> Result<String> {
+    if !will_ask() {
+        return Ok(default.into());
+    }
+    let (n, l) = (NOTE, LITERAL);
+    let answer = ask(&format!("{n}{question}{n:#} [{l}{default}{l:#}]: "))?;
+    if answer.is_empty() {
+        Ok(default.into())
+    } else {
+        Ok(answer)
+    }
+}
+
+/// Asks the yes or no `question` and returns the answer, or the `default` when it is empty.
+///
+/// With `--yes` this returns `true` without asking, and without a terminal the `default`.
+pub fn confirm(question: &str, default: bool) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    if !is_interactive() {
+        return Ok(default);
+    }
+    let (n, l) = (NOTE, LITERAL);
+    let options = if default { "Y/n" } else { "y/N" };
+    loop {
+        let answer = ask(&format!("{n}{question}{n:#} [{l}{options}{l:#}]: "))?;
+        match answer.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("Please answer 'y' or 'n'."),
+        }
+    }
+}
+
+/// Asks the `question` with numbered `options` and returns the index of the chosen one.
+///
+/// The `default` is returned when the answer is empty, and when nothing can be asked.
+/// Without a `default` this fails when nothing can be asked.
+pub fn select(question: &str, options: &[&str], default: Option<usize>) -> Result<usize> {
+    if !will_ask() {
+        return default.context(format!(
+            "'{question}' must be answered, but prompts are disabled without a terminal or with --yes"
+        ));
+    }
+    let (n, l) = (NOTE, LITERAL);
+    print_options(options);
+    let hint = match default {
+        Some(default) => format!("1-{}, default {}", options.len(), default + 1),
+        None => format!("1-{}", options.len()),
+    };
+    loop {
+        let answer = ask(&format!("{n}{question}{n:#} [{l}{hint}{l:#}]: "))?;
+        if answer.is_empty()
+            && let Some(default) = default
+        {
+            return Ok(default);
+        }
+        match parse_option(&answer, options.len()) {
+            Some(idx) => return Ok(idx),
+            None => eprintln!("Please answer with a number from 1 to {}.", options.len()),
+        }
+    }
+}
+
+/// Asks the `question` with numbered `options` and returns the indices of the chosen ones.
+///
+/// The indices in `defaults` are returned when the answer is empty, and when nothing can be asked.
+/// Multiple options are separated by commas or spaces, and `none` chooses none of them.
+pub fn multiselect(question: &str, options: &[&str], defaults: &[usize]) -> Result<Vec<usize>> {
+    if !will_ask() {
+        return Ok(defaults.into());
+    }
+    let (n, l) = (NOTE, LITERAL);
+    print_options(options);
+    let hint = if defaults.is_empty() {
+        "none".into()
+    } else {
+        defaults
+            .iter()
+            .map(|idx| (idx + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    'ask: loop {
+        let answer = ask(&format!("{n}{question}{n:#} [{l}{hint}{l:#}]: "))?;
+        if answer.is_empty() {
+            return Ok(defaults.into());
+        }
+        if answer.eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+        let mut chosen = Vec::new();
+        for part in answer.split([',', ' ']).filter(|p| !p.is_empty()) {
+            let Some(idx) = parse_option(part, options.len()) else {
+                eprintln!(
+                    "Please answer with numbers from 1 to {} separated by commas, or 'none'.",
+                    options.len()
+                );
+                continue 'ask;
+            };
+            if !chosen.contains(&idx) {
+                chosen.push(idx);
+            }
+        }
+        chosen.sort_unstable();
+        return Ok(chosen);
+    }
+}
+
+/// Prints the `options` numbered from one.
+fn print_options(options: &[&str]) {
+    let l = LITERAL;
+    for (idx, option) in options.iter().enumerate() {
+        eprintln!("  {l}{:>2}{l:#}) {option}", idx + 1);
+    }
+}
+
+/// Parses the one-based option number `answer` into an index below `len`.
+fn parse_option(answer: &str, len: usize) -> Option<usize> {
+    let number = answer.trim().parse::<usize>().ok()?;
+    (1..=len).contains(&number).then(|| number - 1)
+}
+
+/// Prints the `prompt` to stderr and returns the trimmed line read from stdin.
+fn ask(prompt: &str) -> Result<String> {
+    eprint!("{prompt}");
+    io::stderr().flush().context("failed to flush stderr")?;
+    let mut line = String::new();
+    let read = io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("failed to read answer from stdin")?;
+    if read == 0 {
+        bail!("stdin closed while waiting for an answer");
+    }
+    Ok(line.trim().into())
+}
@@ -0,0 +1,32 @@
+// Copyright 2026 the Prep Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Terminal capability detection.
+
+use std::env;
+use std::io::{self, IsTerminal};
+
+use terminal_size::Width;
+
+/// Narrowest width that output is wrapped to, as anything narrower is unreadable anyway.
+const MIN_WIDTH: usize = 40;
+
+/// Returns the width of the terminal in columns, or `None` if stderr is not a terminal.
+///
+/// The `COLUMNS` environment variable takes precedence over the size reported by the terminal.
+pub fn width() -> Option<usize> {
+    if !io::stderr().is_terminal() {
+        return None;
+    }
+    let columns = env::var("COLUMNS").ok().and_then(|c| c.trim().parse().ok());
+    columns
+        .or_else(query_width)
+        .filter(|&w| w > 0)
+        .map(|w: usize| w.max(MIN_WIDTH))
+}
+
+/// Queries the width of the terminal attached to stderr.
+fn query_width() -> Option<usize> {
+    let (Width(width), _) = terminal_size::terminal_size_of(io::stderr())?;
+    Some(width.into())
+}